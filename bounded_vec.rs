@@ -0,0 +1,52 @@
+use crate::errors::AzAirdropError;
+use ink::prelude::{string::ToString, vec::Vec};
+
+// Vec that refuses to grow past a max length supplied by the caller (usually from config).
+// Encodes exactly like the Vec it wraps.
+#[derive(scale::Decode, scale::Encode, Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct BoundedVec<T>(Vec<T>);
+impl<T> Default for BoundedVec<T> {
+    fn default() -> Self {
+        BoundedVec(Vec::new())
+    }
+}
+impl<T: PartialEq> BoundedVec<T> {
+    pub fn into_inner(self) -> Vec<T> {
+        self.0
+    }
+
+    pub fn iter(&self) -> core::slice::Iter<'_, T> {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    // Returns true if the item was found and removed
+    pub fn remove_item(&mut self, item: &T) -> bool {
+        if let Some(index) = self.0.iter().position(|x| x == item) {
+            self.0.remove(index);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn try_push(&mut self, item: T, max_length: u32, name: &str) -> Result<(), AzAirdropError> {
+        if self.0.len() >= max_length as usize {
+            return Err(AzAirdropError::CapacityExceeded(name.to_string()));
+        }
+        self.0.push(item);
+
+        Ok(())
+    }
+}
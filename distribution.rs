@@ -0,0 +1,45 @@
+// Visits every index in 0..count exactly once, in an order derived from seed.
+// position => (step * position + offset) % count, where step is coprime to count.
+// It isn't cryptographically random, it only stops partial distribution passes
+// from always favouring the same cohort.
+pub struct Permutation {
+    step: u64,
+    offset: u64,
+    count: u64,
+}
+
+impl Permutation {
+    pub fn new(seed: &[u8; 32], count: u32) -> Self {
+        let count: u64 = u64::from(count).max(1);
+        let mut step: u64 = (u64_from(&seed[..8]) % count).max(1);
+        while gcd(step, count) != 1 {
+            step = if step + 1 >= count { 1 } else { step + 1 };
+        }
+
+        Self {
+            step,
+            offset: u64_from(&seed[8..16]) % count,
+            count,
+        }
+    }
+
+    pub fn index(&self, position: u32) -> u32 {
+        // This can't overflow as step and position are both below 2^32
+        ((self.step * u64::from(position) + self.offset) % self.count) as u32
+    }
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
+fn u64_from(bytes: &[u8]) -> u64 {
+    let mut buffer: [u8; 8] = [0; 8];
+    buffer.copy_from_slice(bytes);
+
+    u64::from_le_bytes(buffer)
+}
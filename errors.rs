@@ -1,6 +1,7 @@
 use ink::{
     env::Error as InkEnvError,
     prelude::{format, string::String},
+    primitives::AccountId,
     LangError,
 };
 use openbrush::contracts::psp22::PSP22Error;
@@ -8,11 +9,38 @@ use openbrush::contracts::psp22::PSP22Error;
 #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
 #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
 pub enum AzAirdropError {
+    AirdropAlreadyStarted,
+    AirdropNotStarted,
+    AllocationsFrozen,
+    AmountZero,
+    CapacityExceeded(String),
+    ClaimDeadlinePassed,
     ContractCall(LangError),
+    EscrowFinalized,
     InkEnvError(String),
+    // Balance doesn't cover to_be_collected, reserved budgets and unfinalized escrow
+    InsufficientContractBalance,
+    InvalidProof,
+    InvalidTgePercentage,
+    // requested is more than limit allows e.g. a reserved budget
+    LimitExceeded {
+        limit: u128,
+        requested: u128,
+    },
     NotFound(String),
+    // The total that would overflow e.g. to_be_collected
+    Overflow(String),
     PSP22Error(PSP22Error),
-    Unauthorised,
+    Paused,
+    // The limit that was reached
+    RecipientLimitReached(u32),
+    ScheduleFrozen,
+    // expected is None when several accounts are allowed e.g. admin and sub-admins
+    Unauthorised {
+        caller: AccountId,
+        expected: Option<AccountId>,
+    },
+    // Fallback for failures that don't have their own variant
     UnprocessableEntity(String),
 }
 impl From<InkEnvError> for AzAirdropError {
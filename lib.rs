@@ -1,1081 +1,10920 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 
-pub use self::az_airdrop::AzAirdropRef;
+pub use self::{
+    az_airdrop::AzAirdropRef, observer::VestingObserver, query::AzAirdropQuery,
+    role_registry::HasRole, sales::AzAirdropSales,
+};
+// Off-chain tooling builds trees and proofs with the same encoding the contract verifies against
+#[cfg(feature = "std")]
+pub use self::merkle::{leaf as merkle_leaf, verify as merkle_verify, MerkleHash};
 
+mod bounded_vec;
+mod distribution;
 mod errors;
+mod merkle;
+mod observer;
+mod query;
+mod role_registry;
+mod sales;
+mod schedule;
 
 #[ink::contract]
 mod az_airdrop {
-    use crate::errors::AzAirdropError;
+    use crate::{
+        bounded_vec::BoundedVec,
+        distribution::Permutation,
+        errors::AzAirdropError,
+        merkle::{self, MerkleHash},
+        query::AzAirdropQuery,
+        role_registry::{self, Role},
+        sales::AzAirdropSales,
+        schedule::{self, Curve, SchedulePhase, ScheduleWarning},
+    };
     use ink::{
         codegen::EmitEvent,
-        env::CallFlags,
+        env::{
+            call::{build_call, ExecutionInput, Selector},
+            hash::Blake2x256,
+            CallFlags, DefaultEnvironment,
+        },
         prelude::string::{String, ToString},
-        prelude::{vec, vec::Vec},
+        prelude::{format, vec, vec::Vec},
         reflect::ContractEventBase,
         storage::{Lazy, Mapping},
     };
     use openbrush::contracts::psp22::PSP22Ref;
     use primitive_types::U256;
 
+    // === CONSTANTS ===
+    const DAY: Timestamp = 86_400_000;
+    const DEFAULT_CLAIMED_DAYS_RETENTION: u32 = 90;
+    const DEFAULT_MAX_ORGANIZATION_MEMBERS: u32 = 100;
+    const DEFAULT_MAX_SUB_ADMINS: u32 = 50;
+    // Ids of the selector sets this version supports, see interfaces
+    const INTERFACE_BUDGETS: [u8; 4] = ink::selector_bytes!("az_airdrop::budgets");
+    const INTERFACE_CORE: [u8; 4] = ink::selector_bytes!("az_airdrop::core");
+    const INTERFACE_MERKLE: [u8; 4] = ink::selector_bytes!("az_airdrop::merkle");
+    const INTERFACE_ORGANIZATIONS: [u8; 4] = ink::selector_bytes!("az_airdrop::organizations");
+    const INTERFACE_QUERY: [u8; 4] = ink::selector_bytes!("az_airdrop::query");
+    const INTERFACE_ROLES: [u8; 4] = ink::selector_bytes!("az_airdrop::roles");
+    const INTERFACE_SALES: [u8; 4] = ink::selector_bytes!("az_airdrop::sales");
+    const INTERFACE_SCHEDULE_PHASES: [u8; 4] = ink::selector_bytes!("az_airdrop::schedule_phases");
+    // Gives the current address time to cancel a lost key reassign if its keys aren't lost
+    const LOST_KEY_REASSIGN_DELAY: Timestamp = 7 * DAY;
+    // Keeps the extra cost of a claim bounded after a long gap without claims
+    const MAX_CLAIMED_DAYS_PRUNED_PER_CLAIM: u32 = 10;
+    const MAX_MEMO_LENGTH: usize = 256;
+    const MAX_RECIPIENTS_LIST_LIMIT: u32 = 100;
+    // Gives recipients time to see a recipient limit change before it applies
+    const MAX_RECIPIENTS_UPDATE_DELAY: Timestamp = 2 * DAY;
+    // Gives claimants time to check a replacement merkle root before it applies
+    const MERKLE_ROOT_ROTATION_DELAY: Timestamp = 2 * DAY;
+    // So a misbehaving observer can't use up the gas of the call it's notified from
+    const OBSERVER_GAS_LIMIT: u64 = 5_000_000_000;
+    // Gives a recipient time to notice and react to a payout address they didn't register
+    const PAYOUT_ADDRESS_COOLDOWN: Timestamp = 2 * DAY;
+    // Bump when storage or message types change in a way integrators need to know about
+    const SCHEMA_VERSION: u16 = 2;
+    const STATEMENT_PROJECTION_PERIODS: u64 = 12;
+    // How long an allocation must sit fully vested and untouched before it can be marked unreachable
+    const UNREACHABLE_AFTER: Timestamp = 365 * DAY;
+    // How long a mark must stand before the allocation can be reassigned
+    const UNREACHABLE_REASSIGN_DELAY: Timestamp = 30 * DAY;
+
     // === TYPES ===
     type Event = <AzAirdrop as ContractEventBase>::Type;
     type Result<T> = core::result::Result<T, AzAirdropError>;
 
     // === EVENTS ===
     #[ink(event)]
-    pub struct RecipientAdd {
+    pub struct AddressChange {
+        #[ink(topic)]
+        old_address: AccountId,
+        #[ink(topic)]
+        new_address: AccountId,
+        allocation_id: u64,
+        external_ref: Option<[u8; 16]>,
+        caller: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct AllowanceRevoke {
+        #[ink(topic)]
+        spender: AccountId,
+        #[ink(topic)]
+        caller: AccountId,
+    }
+
+    // budget is the resulting reservation for address
+    #[ink(event)]
+    pub struct BudgetReserve {
         #[ink(topic)]
         address: AccountId,
-        amount: Balance,
+        budget: Balance,
+        #[ink(topic)]
         caller: AccountId,
-        description: Option<String>,
     }
 
     #[ink(event)]
-    pub struct RecipientSubtract {
+    pub struct Collect {
         #[ink(topic)]
         address: AccountId,
+        allocation_id: u64,
         amount: Balance,
         caller: AccountId,
-        description: Option<String>,
+        // receives the tokens
+        destination: AccountId,
+        external_ref: Option<[u8; 16]>,
+        // blake2x256 of the memo passed to the token transfer
+        memo_hash: Option<Hash>,
     }
 
-    // === STRUCTS ===
-    #[derive(Debug, Clone, scale::Encode, scale::Decode)]
-    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
-    pub struct Config {
-        pub admin: AccountId,
-        pub sub_admins: Vec<AccountId>,
-        pub token: AccountId,
-        pub to_be_collected: Balance,
-        pub start: Timestamp,
-        pub default_collectable_at_tge_percentage: u8,
-        pub default_cliff_duration: Timestamp,
-        pub default_vesting_duration: Timestamp,
+    // config is the resulting config, None when event_verbosity is Minimal
+    #[ink(event)]
+    pub struct ConfigUpdate {
+        #[ink(topic)]
+        caller: AccountId,
+        config: Option<Config>,
     }
 
-    #[derive(scale::Decode, scale::Encode, Debug, Clone, PartialEq)]
-    #[cfg_attr(
-        feature = "std",
-        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
-    )]
-    pub struct Recipient {
-        pub total_amount: Balance,
-        pub collected: Balance,
-        // % of total_amount
-        pub collectable_at_tge_percentage: u8,
-        // ms from start user has to wait before either starting vesting, or collecting remaining available.
-        pub cliff_duration: Timestamp,
-        // ms to collect all remaining after collection at tge
-        pub vesting_duration: Timestamp,
+    // amount is the escrow made available to the campaign
+    #[ink(event)]
+    pub struct EscrowFinalize {
+        #[ink(topic)]
+        caller: AccountId,
+        amount: Balance,
     }
 
-    // === CONTRACT ===
-    #[ink(storage)]
-    pub struct AzAirdrop {
-        admin: AccountId,
-        sub_admins_mapping: Mapping<AccountId, AccountId>,
-        sub_admins_as_vec: Lazy<Vec<AccountId>>,
-        token: AccountId,
-        to_be_collected: Balance,
-        start: Timestamp,
-        recipients: Mapping<AccountId, Recipient>,
-        default_collectable_at_tge_percentage: u8,
-        default_cliff_duration: Timestamp,
-        default_vesting_duration: Timestamp,
+    #[ink(event)]
+    pub struct EscrowWithdraw {
+        #[ink(topic)]
+        to: AccountId,
+        amount: Balance,
     }
-    impl AzAirdrop {
-        #[ink(constructor)]
-        pub fn new(
-            token: AccountId,
-            start: Timestamp,
-            default_collectable_at_tge_percentage: u8,
-            default_cliff_duration: Timestamp,
-            default_vesting_duration: Timestamp,
-        ) -> Result<Self> {
-            Self::validate_airdrop_calculation_variables(
-                start,
-                default_collectable_at_tge_percentage,
-                default_cliff_duration,
-                default_vesting_duration,
-            )?;
-
-            Ok(Self {
-                admin: Self::env().caller(),
-                sub_admins_mapping: Mapping::default(),
-                sub_admins_as_vec: Default::default(),
-                token,
-                to_be_collected: 0,
-                start,
-                recipients: Mapping::default(),
-                default_collectable_at_tge_percentage,
-                default_cliff_duration,
-                default_vesting_duration,
-            })
-        }
 
-        // === QUERIES ===
-        // 0 = start (collectable_at_tge)
-        // 1 = vesting_start = start + cliff_duration
-        // 2 = vesting_end = vesting_start + vesting_duration
-        #[ink(message)]
-        pub fn collectable_amount(
-            &self,
-            address: AccountId,
-            timestamp: Timestamp,
-        ) -> Result<Balance> {
-            let recipient: Recipient = self.show(address)?;
-            let mut total_collectable_at_time: Balance = 0;
-            if timestamp >= self.start {
-                // collectable at tge
-                let collectable_at_tge: Balance =
-                    (U256::from(recipient.collectable_at_tge_percentage)
-                        * U256::from(recipient.total_amount)
-                        / U256::from(100))
-                    .as_u128();
-                total_collectable_at_time = collectable_at_tge;
-                if recipient.vesting_duration > 0 {
-                    // This can't overflow as checks are done in validate_airdrop_calculation_variables
-                    let vesting_start: Timestamp = self.start + recipient.cliff_duration;
-                    let mut vesting_collectable: Balance = 0;
-                    if timestamp >= vesting_start {
-                        // This can't overflow
-                        let vesting_time_reached: Timestamp = timestamp - vesting_start;
-                        // This can't overflow
-                        let collectable_during_vesting: Balance =
-                            recipient.total_amount - collectable_at_tge;
-                        vesting_collectable = (U256::from(vesting_time_reached)
-                            * U256::from(collectable_during_vesting)
-                            / U256::from(recipient.vesting_duration))
-                        .as_u128();
-                    }
-                    // This can't overflow
-                    total_collectable_at_time = total_collectable_at_time + vesting_collectable;
-                }
-                if total_collectable_at_time > recipient.total_amount {
-                    total_collectable_at_time = recipient.total_amount
-                }
-            }
+    #[ink(event)]
+    pub struct Funded {
+        #[ink(topic)]
+        from: AccountId,
+        amount: Balance,
+        #[ink(topic)]
+        caller: AccountId,
+        new_balance: Option<Balance>,
+    }
 
-            Ok(total_collectable_at_time.saturating_sub(recipient.collected))
-        }
+    #[ink(event)]
+    pub struct LabelRemove {
+        label_id: u16,
+        #[ink(topic)]
+        caller: AccountId,
+    }
 
-        #[ink(message)]
-        pub fn config(&self) -> Config {
-            Config {
-                admin: self.admin,
-                sub_admins: self.sub_admins_as_vec.get_or_default(),
-                token: self.token,
-                to_be_collected: self.to_be_collected,
-                start: self.start,
-                default_collectable_at_tge_percentage: self.default_collectable_at_tge_percentage,
-                default_cliff_duration: self.default_cliff_duration,
-                default_vesting_duration: self.default_vesting_duration,
-            }
-        }
+    #[ink(event)]
+    pub struct LabelSet {
+        label_id: u16,
+        label: String,
+        #[ink(topic)]
+        caller: AccountId,
+    }
 
-        #[ink(message)]
-        pub fn show(&self, address: AccountId) -> Result<Recipient> {
-            self.recipients
-                .get(address)
-                .ok_or(AzAirdropError::NotFound("Recipient".to_string()))
-        }
+    #[ink(event)]
+    pub struct MerkleRootInvalidate {
+        #[ink(topic)]
+        caller: AccountId,
+    }
 
-        // === HANDLES ===
-        // Not a must, but good to have function
-        #[ink(message)]
-        pub fn acquire_token(&mut self, amount: Balance, from: AccountId) -> Result<()> {
-            let caller: AccountId = Self::env().caller();
-            Self::authorise(caller, self.admin)?;
-            self.airdrop_has_not_started()?;
+    #[ink(event)]
+    pub struct MerkleRootRotate {
+        merkle_root: MerkleHash,
+        applies_from: Timestamp,
+        total: Balance,
+        #[ink(topic)]
+        caller: AccountId,
+    }
 
-            PSP22Ref::transfer_from_builder(
-                &self.token,
-                from,
-                self.env().account_id(),
-                amount,
-                vec![],
-            )
-            .call_flags(CallFlags::default())
-            .invoke()?;
+    #[ink(event)]
+    pub struct ModeUpdate {
+        #[ink(topic)]
+        caller: AccountId,
+        admin_ops_enabled: bool,
+        claims_enabled: bool,
+    }
 
-            Ok(())
-        }
+    #[ink(event)]
+    pub struct NativeRescue {
+        #[ink(topic)]
+        to: AccountId,
+        amount: Balance,
+        #[ink(topic)]
+        caller: AccountId,
+    }
 
-        #[ink(message)]
-        pub fn collect(&mut self) -> Result<Balance> {
-            let caller: AccountId = Self::env().caller();
-            let mut recipient = self.show(caller)?;
+    #[ink(event)]
+    pub struct ObserverRegister {
+        observer: Option<AccountId>,
+        #[ink(topic)]
+        caller: AccountId,
+    }
 
-            let block_timestamp: Timestamp = Self::env().block_timestamp();
-            let collectable_amount: Balance = self.collectable_amount(caller, block_timestamp)?;
-            if collectable_amount == 0 {
-                return Err(AzAirdropError::UnprocessableEntity(
-                    "Amount is zero".to_string(),
-                ));
-            }
+    #[ink(event)]
+    pub struct OrganizationCreate {
+        org_id: u32,
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        caller: AccountId,
+    }
 
-            // transfer to caller
-            PSP22Ref::transfer_builder(&self.token, caller, collectable_amount, vec![])
-                .call_flags(CallFlags::default())
-                .invoke()?;
-            // increase recipient's collected
-            // These can't overflow, but might as well
-            recipient.collected = recipient.collected.saturating_add(collectable_amount);
-            self.recipients.insert(caller, &recipient);
-            self.to_be_collected = self.to_be_collected.saturating_sub(collectable_amount);
+    #[ink(event)]
+    pub struct RecipientAdd {
+        #[ink(topic)]
+        address: AccountId,
+        allocation_id: u64,
+        external_ref: Option<[u8; 16]>,
+        amount: Balance,
+        caller: AccountId,
+        description: Option<String>,
+        label_id: Option<u16>,
+        source: AllocationSource,
+    }
 
-            Ok(collectable_amount)
-        }
+    #[ink(event)]
+    pub struct RecipientLostKeyCancel {
+        #[ink(topic)]
+        address: AccountId,
+        #[ink(topic)]
+        caller: AccountId,
+    }
 
-        // This is for the sales smart contract to call
-        #[ink(message)]
-        pub fn recipient_add(
-            &mut self,
-            address: AccountId,
-            amount: Balance,
-            description: Option<String>,
-        ) -> Result<Recipient> {
-            self.authorise_to_update_recipient()?;
-            self.airdrop_has_not_started()?;
-            if let Some(new_to_be_collected) = amount.checked_add(self.to_be_collected) {
-                // Check that balance has enough to cover
-                let smart_contract_balance: Balance =
-                    PSP22Ref::balance_of(&self.token, Self::env().account_id());
-                if new_to_be_collected > smart_contract_balance {
-                    return Err(AzAirdropError::UnprocessableEntity(
-                        "Insufficient balance".to_string(),
-                    ));
-                }
+    #[ink(event)]
+    pub struct RecipientLostKeyPropose {
+        #[ink(topic)]
+        address: AccountId,
+        #[ink(topic)]
+        new_address: AccountId,
+        applies_from: Timestamp,
+        caller: AccountId,
+    }
 
-                let mut recipient: Recipient = self.recipients.get(address).unwrap_or(Recipient {
-                    total_amount: 0,
-                    collected: 0,
-                    collectable_at_tge_percentage: self.default_collectable_at_tge_percentage,
-                    cliff_duration: self.default_cliff_duration,
-                    vesting_duration: self.default_vesting_duration,
-                });
-                // This can't overflow
-                recipient.total_amount += amount;
-                self.recipients.insert(address, &recipient);
-                self.to_be_collected = new_to_be_collected;
+    // Unlike AddressChange the current address didn't take part, collected moves with the rest
+    #[ink(event)]
+    pub struct RecipientLostKeyReassign {
+        #[ink(topic)]
+        address: AccountId,
+        #[ink(topic)]
+        new_address: AccountId,
+        allocation_id: u64,
+        external_ref: Option<[u8; 16]>,
+        total_amount: Balance,
+        collected: Balance,
+        #[ink(topic)]
+        caller: AccountId,
+    }
 
-                // emit event
-                Self::emit_event(
-                    self.env(),
-                    Event::RecipientAdd(RecipientAdd {
-                        address,
-                        amount,
-                        caller: Self::env().caller(),
-                        description,
-                    }),
-                );
-
-                Ok(recipient)
-            } else {
-                return Err(AzAirdropError::UnprocessableEntity(
-                    "Amount will cause to_be_collected to overflow".to_string(),
-                ));
-            }
-        }
-
-        #[ink(message)]
-        pub fn recipient_subtract(
-            &mut self,
-            address: AccountId,
-            amount: Balance,
-            description: Option<String>,
-        ) -> Result<Recipient> {
-            self.authorise_to_update_recipient()?;
-            self.airdrop_has_not_started()?;
-            let mut recipient = self.show(address)?;
-            if amount > recipient.total_amount {
-                return Err(AzAirdropError::UnprocessableEntity(
-                    "Amount is greater than recipient's total amount".to_string(),
-                ));
-            }
+    #[ink(event)]
+    pub struct RecipientMarkUnreachable {
+        #[ink(topic)]
+        address: AccountId,
+        allocation_id: u64,
+        #[ink(topic)]
+        caller: AccountId,
+    }
 
-            // Update recipient
-            // This can't overflow because of the above check
-            recipient.total_amount -= amount;
-            self.recipients.insert(address, &recipient);
+    #[ink(event)]
+    pub struct RecipientRemove {
+        #[ink(topic)]
+        address: AccountId,
+        allocation_id: u64,
+        external_ref: Option<[u8; 16]>,
+        // total_amount released from to_be_collected
+        amount: Balance,
+        caller: AccountId,
+    }
 
-            // Update config
-            // This can't overflow but might as well
-            self.to_be_collected = self.to_be_collected.saturating_sub(amount);
+    #[ink(event)]
+    pub struct RecipientRevoke {
+        #[ink(topic)]
+        address: AccountId,
+        allocation_id: u64,
+        external_ref: Option<[u8; 16]>,
+        // unvested amount sent to to
+        amount: Balance,
+        to: AccountId,
+    }
 
-            // emit event
-            Self::emit_event(
-                self.env(),
-                Event::RecipientSubtract(RecipientSubtract {
-                    address,
-                    amount,
-                    caller: Self::env().caller(),
-                    description,
-                }),
-            );
+    #[ink(event)]
+    pub struct RecipientSubtract {
+        #[ink(topic)]
+        address: AccountId,
+        allocation_id: u64,
+        external_ref: Option<[u8; 16]>,
+        amount: Balance,
+        caller: AccountId,
+        description: Option<String>,
+    }
 
-            Ok(recipient)
-        }
+    // Everything after old_vesting_duration is the resulting recipient
+    #[ink(event)]
+    pub struct RecipientUpdate {
+        #[ink(topic)]
+        address: AccountId,
+        allocation_id: u64,
+        external_ref: Option<[u8; 16]>,
+        caller: AccountId,
+        old_collectable_at_tge_percentage: u8,
+        old_cliff_duration: Timestamp,
+        old_vesting_duration: Timestamp,
+        collectable_at_tge_percentage: u8,
+        cliff_duration: Timestamp,
+        vesting_duration: Timestamp,
+        schedule_phases: Option<Vec<SchedulePhase>>,
+        release_interval: Timestamp,
+        revocable: bool,
+    }
 
-        #[ink(message)]
-        pub fn return_spare_tokens(&mut self) -> Result<Balance> {
-            let caller: AccountId = Self::env().caller();
-            let contract_address: AccountId = Self::env().account_id();
-            Self::authorise(caller, self.admin)?;
+    #[ink(event)]
+    pub struct RoleGrant {
+        role: Role,
+        #[ink(topic)]
+        account: AccountId,
+        #[ink(topic)]
+        caller: AccountId,
+    }
 
-            let balance: Balance = PSP22Ref::balance_of(&self.token, contract_address);
-            // These can't overflow, but might as well
-            let spare_amount: Balance = balance.saturating_sub(self.to_be_collected);
-            if spare_amount > 0 {
-                PSP22Ref::transfer_builder(&self.token, caller, spare_amount, vec![])
-                    .call_flags(CallFlags::default())
-                    .invoke()?;
-            } else {
-                return Err(AzAirdropError::UnprocessableEntity(
-                    "Amount is zero".to_string(),
-                ));
-            }
+    #[ink(event)]
+    pub struct RoleRevoke {
+        role: Role,
+        #[ink(topic)]
+        account: AccountId,
+        #[ink(topic)]
+        caller: AccountId,
+    }
 
-            Ok(spare_amount)
-        }
+    #[ink(event)]
+    pub struct ScheduleHardened {
+        #[ink(topic)]
+        address: AccountId,
+        allocation_id: u64,
+        extra_cliff: Timestamp,
+        extra_vesting: Timestamp,
+        // resulting durations
+        cliff_duration: Timestamp,
+        vesting_duration: Timestamp,
+    }
 
-        #[ink(message)]
-        pub fn sub_admins_add(&mut self, address: AccountId) -> Result<Vec<AccountId>> {
-            let caller: AccountId = Self::env().caller();
-            Self::authorise(caller, self.admin)?;
+    #[ink(event)]
+    pub struct ScheduleTemplateAdd {
+        template_id: u16,
+        template: ScheduleTemplate,
+        #[ink(topic)]
+        caller: AccountId,
+    }
 
-            let mut sub_admins: Vec<AccountId> = self.sub_admins_as_vec.get_or_default();
-            if self.sub_admins_mapping.get(address).is_some() {
-                return Err(AzAirdropError::UnprocessableEntity(
-                    "Already a sub admin".to_string(),
-                ));
-            } else {
-                sub_admins.push(address.clone());
-                self.sub_admins_mapping.insert(address, &address.clone());
-            }
-            self.sub_admins_as_vec.set(&sub_admins);
+    #[ink(event)]
+    pub struct ScheduleTemplateRemove {
+        template_id: u16,
+        #[ink(topic)]
+        caller: AccountId,
+    }
 
-            Ok(sub_admins)
-        }
+    #[ink(event)]
+    pub struct ScheduleTemplateUpdate {
+        template_id: u16,
+        template: ScheduleTemplate,
+        #[ink(topic)]
+        caller: AccountId,
+    }
 
-        #[ink(message)]
-        pub fn sub_admins_remove(&mut self, address: AccountId) -> Result<Vec<AccountId>> {
-            let caller: AccountId = Self::env().caller();
-            Self::authorise(caller, self.admin)?;
+    #[ink(event)]
+    pub struct SourceTemplateUpdate {
+        source: AllocationSource,
+        template_id: Option<u16>,
+        #[ink(topic)]
+        caller: AccountId,
+    }
 
-            let mut sub_admins: Vec<AccountId> = self.sub_admins_as_vec.get_or_default();
-            if self.sub_admins_mapping.get(address).is_none() {
-                return Err(AzAirdropError::UnprocessableEntity(
-                    "Not a sub admin".to_string(),
-                ));
-            } else {
-                let index = sub_admins.iter().position(|x| *x == address).unwrap();
-                sub_admins.remove(index);
-                self.sub_admins_mapping.remove(address);
-            }
-            self.sub_admins_as_vec.set(&sub_admins);
+    #[ink(event)]
+    pub struct SpareReturned {
+        #[ink(topic)]
+        to: AccountId,
+        amount: Balance,
+        #[ink(topic)]
+        caller: AccountId,
+    }
 
-            Ok(sub_admins)
-        }
+    #[ink(event)]
+    pub struct SubAdminAdd {
+        #[ink(topic)]
+        address: AccountId,
+        #[ink(topic)]
+        caller: AccountId,
+    }
 
-        // #[derive(Debug, Clone, scale::Encode, scale::Decode)]
-        // #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
-        // pub struct Config {
-        //     admin: AccountId,
-        //     sub_admins: Vec<AccountId>,
-        //     token: AccountId,
-        //     to_be_collected: Balance,
-        //     start: Timestamp,
-        //     default_collectable_at_tge_percentage: u8,
-        //     default_cliff_duration: Timestamp,
-        //     default_vesting_duration: Timestamp,
-        // }
-        #[ink(message)]
-        pub fn update_config(
-            &mut self,
-            admin: Option<AccountId>,
-            start: Option<Timestamp>,
-            default_collectable_at_tge_percentage: Option<u8>,
-            default_cliff_duration: Option<Timestamp>,
-            default_vesting_duration: Option<Timestamp>,
-        ) -> Result<()> {
-            let caller: AccountId = Self::env().caller();
-            Self::authorise(caller, self.admin)?;
+    #[ink(event)]
+    pub struct SubAdminRemove {
+        #[ink(topic)]
+        address: AccountId,
+        #[ink(topic)]
+        caller: AccountId,
+    }
 
-            if let Some(admin_unwrapped) = admin {
-                self.admin = admin_unwrapped
-            }
-            if let Some(start_unwrapped) = start {
-                let block_timestamp: Timestamp = Self::env().block_timestamp();
-                if start_unwrapped > block_timestamp {
-                    if self.to_be_collected == 0 {
-                        self.start = start_unwrapped
-                    } else {
-                        return Err(AzAirdropError::UnprocessableEntity(
-                            "to_be_collected must be zero when changing start time".to_string(),
-                        ));
-                    }
-                } else {
-                    return Err(AzAirdropError::UnprocessableEntity(
-                        "New start time must be in the future".to_string(),
-                    ));
-                }
-            }
-            if let Some(default_collectable_at_tge_percentage_unwrapped) =
-                default_collectable_at_tge_percentage
-            {
-                self.default_collectable_at_tge_percentage =
-                    default_collectable_at_tge_percentage_unwrapped
-            }
-            if let Some(default_cliff_duration_unwrapped) = default_cliff_duration {
-                self.default_cliff_duration = default_cliff_duration_unwrapped
-            }
-            if let Some(default_vesting_duration_unwrapped) = default_vesting_duration {
-                self.default_vesting_duration = default_vesting_duration_unwrapped
-            }
-            Self::validate_airdrop_calculation_variables(
-                self.start,
-                self.default_collectable_at_tge_percentage,
-                self.default_cliff_duration,
-                self.default_vesting_duration,
-            )?;
+    // beneficiary receives the contract's deposit
+    #[ink(event)]
+    pub struct Terminate {
+        #[ink(topic)]
+        caller: AccountId,
+        beneficiary: AccountId,
+    }
 
-            // Will not let me check exact error
-            // when Config is returned
-            Ok(())
-        }
+    #[ink(event)]
+    pub struct TokenRescue {
+        #[ink(topic)]
+        token: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        amount: Balance,
+        caller: AccountId,
+    }
 
-        #[ink(message)]
-        pub fn update_recipient(
-            &mut self,
-            address: AccountId,
-            collectable_at_tge_percentage: Option<u8>,
-            cliff_duration: Option<Timestamp>,
-            vesting_duration: Option<Timestamp>,
-        ) -> Result<Recipient> {
-            self.authorise_to_update_recipient()?;
-            self.airdrop_has_not_started()?;
-            let mut recipient: Recipient = self.show(address)?;
+    #[ink(event)]
+    pub struct UnclaimedSwept {
+        #[ink(topic)]
+        to: AccountId,
+        amount: Balance,
+        #[ink(topic)]
+        caller: AccountId,
+    }
 
-            if let Some(collectable_at_tge_percentage_unwrapped) = collectable_at_tge_percentage {
-                recipient.collectable_at_tge_percentage = collectable_at_tge_percentage_unwrapped
-            }
-            if let Some(cliff_duration_unwrapped) = cliff_duration {
-                recipient.cliff_duration = cliff_duration_unwrapped
-            }
-            if let Some(vesting_duration_unwrapped) = vesting_duration {
-                recipient.vesting_duration = vesting_duration_unwrapped
-            }
-            Self::validate_airdrop_calculation_variables(
-                self.start,
-                recipient.collectable_at_tge_percentage,
-                recipient.cliff_duration,
-                recipient.vesting_duration,
-            )?;
+    // Recipients at positions offset..end were zeroed by sweep_unclaimed_range
+    #[ink(event)]
+    pub struct UnclaimedZeroed {
+        offset: u32,
+        end: u32,
+        zeroed: u32,
+        #[ink(topic)]
+        caller: AccountId,
+    }
 
-            self.recipients.insert(address, &recipient);
+    #[ink(event)]
+    pub struct WhitelistedCallerAdd {
+        #[ink(topic)]
+        address: AccountId,
+        #[ink(topic)]
+        caller: AccountId,
+    }
 
-            Ok(recipient)
-        }
+    #[ink(event)]
+    pub struct WhitelistedCallerRemove {
+        #[ink(topic)]
+        address: AccountId,
+        #[ink(topic)]
+        caller: AccountId,
+    }
 
-        // === PRIVATE ===
-        fn airdrop_has_not_started(&self) -> Result<()> {
-            let block_timestamp: Timestamp = Self::env().block_timestamp();
-            if block_timestamp >= self.start {
-                return Err(AzAirdropError::UnprocessableEntity(
-                    "Airdrop has started".to_string(),
-                ));
+    // === STRUCTS ===
+    // Label id and blake2x256 of the free text description of a recipient's first allocation
+    #[derive(scale::Decode, scale::Encode, Debug, Clone, Default, PartialEq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct AllocationOrigin {
+        pub label_id: Option<u16>,
+        pub description_hash: Option<Hash>,
+    }
+
+    // How an allocation was created.
+    // SnapshotImport and Referral are reserved for import and referral flows, nothing sets them yet.
+    #[derive(scale::Decode, scale::Encode, Debug, Clone, Copy, PartialEq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum AllocationSource {
+        AdminGrant,
+        SubAdminGrant,
+        SalePurchase,
+        MerkleClaim,
+        SnapshotImport,
+        Referral,
+    }
+
+    #[derive(Debug, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct CampaignSnapshot {
+        pub config: Config,
+        pub balance: Balance,
+        // balance not needed for to_be_collected or reserved budgets
+        pub spare: Balance,
+        pub started: bool,
+        // recipient_add is only allowed before start unless allow_additions_after_start is set
+        pub registration_open: bool,
+    }
+
+    #[derive(scale::Decode, scale::Encode, Debug, Clone, PartialEq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Claim {
+        pub amount: Balance,
+        pub timestamp: Timestamp,
+    }
+
+    #[derive(Debug, Clone, PartialEq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct Config {
+        pub admin: AccountId,
+        pub sub_admins: Vec<AccountId>,
+        pub token: AccountId,
+        pub to_be_collected: Balance,
+        pub start: Timestamp,
+        pub default_collectable_at_tge_percentage: u8,
+        pub default_cliff_duration: Timestamp,
+        pub default_vesting_duration: Timestamp,
+        pub max_sub_admins: u32,
+        pub max_organization_members: u32,
+        pub funder: Option<AccountId>,
+        pub total_reserved: Balance,
+        pub allocations_frozen: bool,
+        pub claimed_days_retention: u32,
+        pub compliance_mode: bool,
+        pub schedule_freeze_window: Timestamp,
+        pub merkle_root: Option<MerkleHash>,
+        pub merkle_root_version: u32,
+        // (root, applies from)
+        pub pending_merkle_root: Option<(MerkleHash, Timestamp)>,
+        pub merkle_unclaimed: Balance,
+        pub pending_merkle_unclaimed: Balance,
+        pub claims_enabled_at: Option<Timestamp>,
+        pub admin_ops_enabled: bool,
+        pub claims_enabled: bool,
+        pub event_verbosity: EventVerbosity,
+        pub clawback: Option<AccountId>,
+        pub claim_deadline: Option<Timestamp>,
+        pub role_registry: Option<AccountId>,
+        pub escrow_deadline: Option<Timestamp>,
+        pub escrow_finalized: bool,
+        pub total_escrowed: Balance,
+        pub observer: Option<AccountId>,
+        pub distribution_seed: Option<[u8; 32]>,
+        pub max_recipients: Option<u32>,
+        // (max_recipients, applies from)
+        pub pending_max_recipients: Option<(Option<u32>, Timestamp)>,
+        pub whitelisted_callers_only: bool,
+        pub allow_additions_after_start: bool,
+        pub native_reserve: Balance,
+    }
+
+    #[derive(Debug, Clone, PartialEq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Description {
+        Label(u16),
+        Text(String),
+    }
+
+    // Minimal keeps topics and amounts but leaves out descriptions and derived fields
+    #[derive(scale::Decode, scale::Encode, Debug, Clone, Copy, PartialEq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum EventVerbosity {
+        Full,
+        Minimal,
+    }
+
+    #[derive(Debug, Clone, PartialEq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct Health {
+        pub admin_ops_enabled: bool,
+        // balance covers to_be_collected, reserved budgets, unfinalized escrow and unclaimed merkle leaves
+        pub solvent: bool,
+        pub started: bool,
+        pub claims_enabled: bool,
+        pub last_admin_action_at: Option<Timestamp>,
+        pub finalized: bool,
+    }
+
+    #[derive(scale::Decode, scale::Encode, Debug, Clone, PartialEq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Organization {
+        pub owner: AccountId,
+        pub members: BoundedVec<AccountId>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct OrganizationVestingInfo {
+        pub total_amount: Balance,
+        pub collected: Balance,
+        pub collectable: Balance,
+    }
+
+    #[derive(scale::Decode, scale::Encode, Debug, Clone, PartialEq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Recipient {
+        // Assigned once when the allocation is created, for off-chain reconciliation
+        pub allocation_id: u64,
+        pub total_amount: Balance,
+        pub collected: Balance,
+        // % of total_amount
+        pub collectable_at_tge_percentage: u8,
+        // ms from start user has to wait before either starting vesting, or collecting remaining available.
+        pub cliff_duration: Timestamp,
+        // ms to collect all remaining after collection at tge
+        pub vesting_duration: Timestamp,
+        // When present, replaces the three fields above
+        pub schedule_phases: Option<Vec<SchedulePhase>>,
+        // Applies to vesting_duration and linear schedule phases
+        pub curve: Curve,
+        // Admin can cancel unvested tokens after start e.g. when a team member leaves
+        pub revocable: bool,
+        // Set by admin, e.g. an investor ID from a CRM. Unique across recipients.
+        pub external_ref: Option<[u8; 16]>,
+        // ms between unlocks during vesting_duration e.g. 30 days for monthly steps, 0 is continuous.
+        // Doesn't apply to schedule_phases.
+        pub release_interval: Timestamp,
+        // Anchors vesting to e.g. a later purchase date instead of the airdrop's start
+        pub start_override: Option<Timestamp>,
+        // Unlocked when the cliff ends, the rest after tge and cliff unlocks vests over vesting_duration
+        pub collectable_at_cliff_percentage: u8,
+        // Top-ups keep the source of the first allocation, RecipientAdd has the source of each add
+        pub source: AllocationSource,
+        // When present, the schedule template that the three schedule fields are read from
+        pub template_id: Option<u16>,
+    }
+
+    // Fields to change on a recipient, None leaves the field as is
+    #[derive(Debug, Clone, PartialEq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct RecipientPatch {
+        pub collectable_at_tge_percentage: Option<u8>,
+        pub cliff_duration: Option<Timestamp>,
+        pub vesting_duration: Option<Timestamp>,
+        pub curve: Option<Curve>,
+        pub start_override: Option<Timestamp>,
+        pub collectable_at_cliff_percentage: Option<u8>,
+    }
+
+    // Named schedule e.g. team, advisor or community that recipient_add can apply
+    #[derive(scale::Decode, scale::Encode, Debug, Clone, PartialEq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct ScheduleTemplate {
+        pub name: String,
+        pub collectable_at_tge_percentage: u8,
+        pub cliff_duration: Timestamp,
+        pub vesting_duration: Timestamp,
+    }
+
+    #[derive(scale::Decode, scale::Encode, Debug, Clone, Copy, PartialEq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct SubAdminPermissions {
+        pub can_add: bool,
+        pub can_subtract: bool,
+        // covers every update_recipient message
+        pub can_update_schedule: bool,
+    }
+
+    impl SubAdminPermissions {
+        fn allows(&self, permission: SubAdminPermission) -> bool {
+            match permission {
+                SubAdminPermission::Add => self.can_add,
+                SubAdminPermission::Subtract => self.can_subtract,
+                SubAdminPermission::UpdateSchedule => self.can_update_schedule,
             }
+        }
+    }
 
-            Ok(())
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum SubAdminPermission {
+        Add,
+        Subtract,
+        UpdateSchedule,
+    }
+
+    #[derive(Debug, Clone, PartialEq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct Statement {
+        pub address: AccountId,
+        pub recipient: Recipient,
+        // Label id of the first allocation, recipient has its source
+        pub category: Option<u16>,
+        // blake2x256 of the first allocation's free text description
+        pub description_hash: Option<Hash>,
+        pub start: Timestamp,
+        pub vesting_start: Timestamp,
+        pub vesting_end: Timestamp,
+        pub claims_count: u32,
+        pub last_claim: Option<Claim>,
+        pub collectable_now: Balance,
+        // (timestamp, cumulative vested amount) for upcoming period boundaries
+        pub projected_unlocks: Vec<(Timestamp, Balance)>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct Stats {
+        // total_collected + to_be_collected, so it drops once the claim deadline passes
+        pub total_allocated: Balance,
+        pub total_collected: Balance,
+        pub to_be_collected: Balance,
+        pub recipients_count: u32,
+        pub fully_collected_count: u32,
+    }
+
+    // === CONTRACT ===
+    #[ink(storage)]
+    pub struct AzAirdrop {
+        admin: AccountId,
+        sub_admins_mapping: Mapping<AccountId, SubAdminPermissions>,
+        sub_admins_as_vec: Lazy<BoundedVec<AccountId>>,
+        token: AccountId,
+        to_be_collected: Balance,
+        start: Timestamp,
+        recipients: Mapping<AccountId, Recipient>,
+        default_collectable_at_tge_percentage: u8,
+        default_cliff_duration: Timestamp,
+        default_vesting_duration: Timestamp,
+        organizations: Mapping<u32, Organization>,
+        organizations_count: u32,
+        organization_memberships: Mapping<AccountId, u32>,
+        collect_for_opt_ins: Mapping<AccountId, u32>,
+        claims: Mapping<(AccountId, u32), Claim>,
+        claims_count: Mapping<AccountId, u32>,
+        max_sub_admins: u32,
+        max_organization_members: u32,
+        labels: Mapping<u16, String>,
+        label_ids: Mapping<String, u16>,
+        funded: Mapping<AccountId, Balance>,
+        funder: Option<AccountId>,
+        budgets: Mapping<AccountId, Balance>,
+        total_reserved: Balance,
+        allocations_count: u64,
+        allocations_frozen: bool,
+        claimed_by_day: Mapping<u32, Balance>,
+        claimed_days_retention: u32,
+        oldest_claimed_day: Option<u32>,
+        unreachable_marks: Mapping<AccountId, Timestamp>,
+        // recipient => (new_address, proposed at)
+        lost_key_proposals: Mapping<AccountId, (AccountId, Timestamp)>,
+        compliance_mode: bool,
+        // (recipient, payout address) => active from
+        payout_addresses: Mapping<(AccountId, AccountId), Timestamp>,
+        // recipient => notification flags, their meaning is up to off-chain notifiers
+        notification_prefs: Mapping<AccountId, u8>,
+        schedule_freeze_window: Timestamp,
+        max_per_claims: Mapping<AccountId, Balance>,
+        merkle_root: Option<MerkleHash>,
+        // Increases every time a root takes effect
+        merkle_root_version: u32,
+        pending_merkle_root: Option<(MerkleHash, Timestamp)>,
+        // Amount the current root's unclaimed leaves add up to, kept covered like to_be_collected
+        merkle_unclaimed: Balance,
+        // Takes over from merkle_unclaimed when the pending root applies
+        pending_merkle_unclaimed: Balance,
+        // (merkle_root_version, leaf_index) => amount of the claimed leaf
+        merkle_claims: Mapping<(u32, u32), Balance>,
+        // address => largest leaf amount materialized, corrected leaves only top up the difference
+        merkle_claimed: Mapping<AccountId, Balance>,
+        last_admin_action_at: Option<Timestamp>,
+        // None means claims are enabled from start
+        claims_enabled_at: Option<Timestamp>,
+        // Switched off separately during incidents, e.g. freeze admin changes but keep claims going
+        admin_ops_enabled: bool,
+        claims_enabled: bool,
+        event_verbosity: EventVerbosity,
+        // Receives revoked tokens, admin when None
+        clawback: Option<AccountId>,
+        // Nothing can be collected from this point on
+        claim_deadline: Option<Timestamp>,
+        // Set by sweep_unclaimed, recipients' remaining balances can then be zeroed
+        unclaimed_swept: bool,
+        // Shared contract granting roles across az_* contracts, checked alongside local roles
+        role_registry: Option<AccountId>,
+        // XOR of blake2x256(address, total_amount, collected) over all recipients
+        ledger_digest: [u8; 32],
+        // index => address, kept contiguous by moving the last address into removed slots
+        recipient_addresses: Mapping<u32, AccountId>,
+        recipient_indexes: Mapping<AccountId, u32>,
+        recipients_count: u32,
+        external_refs: Mapping<[u8; 16], AccountId>,
+        // Sum of collected across recipients
+        total_collected: Balance,
+        fully_collected_count: u32,
+        // Co-funding phase. Deposits can be withdrawn if admin hasn't finalized by the deadline.
+        escrow_deadline: Option<Timestamp>,
+        escrow_finalized: bool,
+        escrow_deposits: Mapping<AccountId, Balance>,
+        total_escrowed: Balance,
+        // Notified of revocations and sweeps, see VestingObserver
+        observer: Option<AccountId>,
+        schedule_templates: Mapping<u16, ScheduleTemplate>,
+        // Ids aren't reused after a template is removed
+        schedule_templates_count: u16,
+        // how an allocation is created => template referenced by the recipients it creates
+        source_templates: Mapping<AllocationSource, u16>,
+        // template_id => how many recipients reference it, referenced templates can't be removed
+        schedule_template_recipients: Mapping<u16, u32>,
+        // Shuffles distribute_range when set
+        distribution_seed: Option<[u8; 32]>,
+        // (role, account) granted locally, sub-admins are kept in sub_admins_mapping
+        roles: Mapping<(Role, AccountId), ()>,
+        // recipient => address it proposed to move its allocation to
+        address_change_proposals: Mapping<AccountId, AccountId>,
+        // None means unlimited
+        max_recipients: Option<u32>,
+        // (max_recipients, applies from) queued by update_max_recipients
+        pending_max_recipients: Option<(Option<u32>, Timestamp)>,
+        // contracts e.g. sales contracts allowed to call recipient_add
+        whitelisted_callers: Mapping<AccountId, ()>,
+        // When set, only whitelisted contracts can add recipients
+        whitelisted_callers_only: bool,
+        // New recipients added after start vest from when they're added
+        allow_additions_after_start: bool,
+        // Native balance rescue_native leaves behind for storage deposits
+        native_reserve: Balance,
+        // Only stored when the first allocation had a label or description
+        allocation_origins: Mapping<AccountId, AllocationOrigin>,
+    }
+    impl AzAirdrop {
+        #[ink(constructor)]
+        pub fn new(
+            token: AccountId,
+            start: Timestamp,
+            default_collectable_at_tge_percentage: u8,
+            default_cliff_duration: Timestamp,
+            default_vesting_duration: Timestamp,
+        ) -> Result<Self> {
+            Self::validate_airdrop_calculation_variables(
+                start,
+                default_collectable_at_tge_percentage,
+                0,
+                default_cliff_duration,
+                default_vesting_duration,
+            )?;
+
+            Ok(Self {
+                admin: Self::env().caller(),
+                sub_admins_mapping: Mapping::default(),
+                sub_admins_as_vec: Default::default(),
+                token,
+                to_be_collected: 0,
+                start,
+                recipients: Mapping::default(),
+                default_collectable_at_tge_percentage,
+                default_cliff_duration,
+                default_vesting_duration,
+                organizations: Mapping::default(),
+                organizations_count: 0,
+                organization_memberships: Mapping::default(),
+                collect_for_opt_ins: Mapping::default(),
+                claims: Mapping::default(),
+                claims_count: Mapping::default(),
+                max_sub_admins: DEFAULT_MAX_SUB_ADMINS,
+                max_organization_members: DEFAULT_MAX_ORGANIZATION_MEMBERS,
+                labels: Mapping::default(),
+                label_ids: Mapping::default(),
+                funded: Mapping::default(),
+                funder: None,
+                budgets: Mapping::default(),
+                total_reserved: 0,
+                allocations_count: 0,
+                allocations_frozen: false,
+                claimed_by_day: Mapping::default(),
+                claimed_days_retention: DEFAULT_CLAIMED_DAYS_RETENTION,
+                oldest_claimed_day: None,
+                unreachable_marks: Mapping::default(),
+                lost_key_proposals: Mapping::default(),
+                compliance_mode: false,
+                payout_addresses: Mapping::default(),
+                notification_prefs: Mapping::default(),
+                schedule_freeze_window: 0,
+                max_per_claims: Mapping::default(),
+                merkle_root: None,
+                merkle_root_version: 0,
+                pending_merkle_root: None,
+                merkle_unclaimed: 0,
+                pending_merkle_unclaimed: 0,
+                merkle_claims: Mapping::default(),
+                merkle_claimed: Mapping::default(),
+                last_admin_action_at: None,
+                claims_enabled_at: None,
+                admin_ops_enabled: true,
+                claims_enabled: true,
+                event_verbosity: EventVerbosity::Full,
+                clawback: None,
+                claim_deadline: None,
+                unclaimed_swept: false,
+                role_registry: None,
+                ledger_digest: [0; 32],
+                recipient_addresses: Mapping::default(),
+                recipient_indexes: Mapping::default(),
+                recipients_count: 0,
+                external_refs: Mapping::default(),
+                total_collected: 0,
+                fully_collected_count: 0,
+                escrow_deadline: None,
+                escrow_finalized: false,
+                escrow_deposits: Mapping::default(),
+                total_escrowed: 0,
+                observer: None,
+                schedule_templates: Mapping::default(),
+                schedule_templates_count: 0,
+                source_templates: Mapping::default(),
+                schedule_template_recipients: Mapping::default(),
+                distribution_seed: None,
+                address_change_proposals: Mapping::default(),
+                roles: Mapping::default(),
+                max_recipients: None,
+                pending_max_recipients: None,
+                whitelisted_callers: Mapping::default(),
+                whitelisted_callers_only: false,
+                allow_additions_after_start: false,
+                native_reserve: 0,
+                allocation_origins: Mapping::default(),
+            })
         }
 
-        fn authorise(allowed: AccountId, received: AccountId) -> Result<()> {
-            if allowed != received {
-                return Err(AzAirdropError::Unauthorised);
-            }
+        // === QUERIES ===
+        // Remaining amount caller can allocate via recipient_add from its reservation
+        #[ink(message)]
+        pub fn budget(&self, caller: AccountId) -> Balance {
+            self.budgets.get(caller).unwrap_or(0)
+        }
 
-            Ok(())
+        // Total claimed on day (days since unix epoch).
+        // Days outside the retention window are pruned as claims come in.
+        #[ink(message)]
+        pub fn claimed_on_day(&self, day: u32) -> Balance {
+            self.claimed_by_day.get(day).unwrap_or(0)
         }
 
-        fn authorise_to_update_recipient(&self) -> Result<()> {
-            let caller: AccountId = Self::env().caller();
-            if caller == self.admin || self.sub_admins_mapping.get(caller).is_some() {
-                Ok(())
-            } else {
-                return Err(AzAirdropError::Unauthorised);
+        #[ink(message)]
+        pub fn collectable_amount(
+            &self,
+            address: AccountId,
+            timestamp: Timestamp,
+        ) -> Result<Balance> {
+            let recipient: Recipient = self.show(address)?;
+            if let Some(claim_deadline) = self.claim_deadline {
+                if timestamp >= claim_deadline {
+                    return Ok(0);
+                }
             }
+
+            Ok(self
+                .vested_amount(&recipient, timestamp)
+                .saturating_sub(recipient.collected))
         }
 
-        fn emit_event<EE: EmitEvent<Self>>(emitter: EE, event: Event) {
-            emitter.emit_event(event);
+        // What collect would pay at the current block, 0 while claims are paused or not enabled yet
+        #[ink(message)]
+        pub fn collectable_now(&self, address: AccountId) -> Result<Balance> {
+            let recipient_collectable: Balance =
+                self.collectable_amount(address, Self::env().block_timestamp())?;
+            if !self.claims_enabled
+                || self.claims_enabled_at_reached().is_err()
+                || self.claim_deadline_not_passed().is_err()
+            {
+                return Ok(0);
+            }
+
+            // The rest stays collectable for the next call
+            Ok(match self.max_per_claims.get(address) {
+                Some(max_per_claim) => recipient_collectable.min(max_per_claim),
+                None => recipient_collectable,
+            })
         }
 
-        fn validate_airdrop_calculation_variables(
-            start: Timestamp,
-            collectable_at_tge_percentage: u8,
-            cliff_duration: Timestamp,
-            vesting_duration: Timestamp,
-        ) -> Result<()> {
-            if collectable_at_tge_percentage > 100 {
-                return Err(AzAirdropError::UnprocessableEntity(
-                    "collectable_at_tge_percentage must be less than or equal to 100".to_string(),
-                ));
-            } else if collectable_at_tge_percentage == 100 {
-                if cliff_duration > 0 || vesting_duration > 0 {
-                    return Err(AzAirdropError::UnprocessableEntity(
-                        "cliff_duration and vesting_duration must be 0 when collectable_tge_percentage is 100"
-                            .to_string(),
-                    ));
+        // Sum of claims made strictly before timestamp
+        #[ink(message)]
+        pub fn collected_before(
+            &self,
+            address: AccountId,
+            timestamp: Timestamp,
+        ) -> Result<Balance> {
+            self.show(address)?;
+            let mut collected: Balance = 0;
+            for index in 0..self.claims_count.get(address).unwrap_or(0) {
+                if let Some(claim) = self.claims.get((address, index)) {
+                    if claim.timestamp < timestamp {
+                        // This can't overflow as it's bounded by recipient.collected
+                        collected = collected.saturating_add(claim.amount);
+                    }
                 }
-            } else if vesting_duration == 0 {
-                return Err(AzAirdropError::UnprocessableEntity(
-                    "vesting_duration must be greater than 0 when collectable_tge_percentage is not 100"
-                        .to_string(),
-                ));
             }
-            // This can't over flow because all values are u64
-            let end_timestamp: u128 =
-                u128::from(start) + u128::from(cliff_duration) + u128::from(vesting_duration);
-            if end_timestamp > Timestamp::MAX.into() {
-                return Err(AzAirdropError::UnprocessableEntity(
-                    "Combination of start, cliff_duration and vesting_duration exceeds limit"
-                        .to_string(),
-                ));
+
+            Ok(collected)
+        }
+
+        // Merkle fields are as they'll be once a pending root whose delay has passed is applied
+        #[ink(message)]
+        pub fn config(&self) -> Config {
+            let due_merkle_root: Option<MerkleHash> = self.due_merkle_root();
+
+            Config {
+                admin: self.admin,
+                sub_admins: self.sub_admins_as_vec.get_or_default().into_inner(),
+                token: self.token,
+                to_be_collected: self.to_be_collected,
+                start: self.start,
+                default_collectable_at_tge_percentage: self.default_collectable_at_tge_percentage,
+                default_cliff_duration: self.default_cliff_duration,
+                default_vesting_duration: self.default_vesting_duration,
+                max_sub_admins: self.max_sub_admins,
+                max_organization_members: self.max_organization_members,
+                funder: self.funder,
+                total_reserved: self.total_reserved,
+                allocations_frozen: self.allocations_frozen,
+                claimed_days_retention: self.claimed_days_retention,
+                compliance_mode: self.compliance_mode,
+                schedule_freeze_window: self.schedule_freeze_window,
+                merkle_root: due_merkle_root.or(self.merkle_root),
+                merkle_root_version: self
+                    .merkle_root_version
+                    .saturating_add(u32::from(due_merkle_root.is_some())),
+                pending_merkle_root: self
+                    .pending_merkle_root
+                    .filter(|_| due_merkle_root.is_none()),
+                merkle_unclaimed: if due_merkle_root.is_some() {
+                    self.pending_merkle_unclaimed
+                } else {
+                    self.merkle_unclaimed
+                },
+                pending_merkle_unclaimed: if due_merkle_root.is_some() {
+                    0
+                } else {
+                    self.pending_merkle_unclaimed
+                },
+                claims_enabled_at: self.claims_enabled_at,
+                admin_ops_enabled: self.admin_ops_enabled,
+                claims_enabled: self.claims_enabled,
+                event_verbosity: self.event_verbosity,
+                clawback: self.clawback,
+                claim_deadline: self.claim_deadline,
+                role_registry: self.role_registry,
+                escrow_deadline: self.escrow_deadline,
+                escrow_finalized: self.escrow_finalized,
+                total_escrowed: self.total_escrowed,
+                observer: self.observer,
+                distribution_seed: self.distribution_seed,
+                max_recipients: self.effective_max_recipients(),
+                pending_max_recipients: self
+                    .pending_max_recipients
+                    .filter(|(_, applies_from)| Self::env().block_timestamp() < *applies_from),
+                whitelisted_callers_only: self.whitelisted_callers_only,
+                allow_additions_after_start: self.allow_additions_after_start,
+                native_reserve: self.native_reserve,
             }
+        }
 
-            Ok(())
+        #[ink(message)]
+        pub fn escrowed_by(&self, address: AccountId) -> Balance {
+            self.escrow_deposits.get(address).unwrap_or(0)
         }
-    }
 
-    #[cfg(test)]
-    mod tests {
-        use super::*;
-        use ink::env::{
-            test::{default_accounts, set_caller, DefaultAccounts},
-            DefaultEnvironment,
-        };
+        #[ink(message)]
+        pub fn find_by_external_ref(
+            &self,
+            external_ref: [u8; 16],
+        ) -> Result<(AccountId, Recipient)> {
+            let address: AccountId = self
+                .external_refs
+                .get(external_ref)
+                .ok_or(AzAirdropError::NotFound("Recipient".to_string()))?;
 
-        const MOCK_START: Timestamp = 654_654;
+            Ok((address, self.show(address)?))
+        }
 
-        // === HELPERS ===
-        fn init() -> (DefaultAccounts<DefaultEnvironment>, AzAirdrop) {
-            let accounts = default_accounts();
-            set_caller::<DefaultEnvironment>(accounts.bob);
+        // Total amount of token acquired from address
+        #[ink(message)]
+        pub fn funded_by(&self, address: AccountId) -> Balance {
+            self.funded.get(address).unwrap_or(0)
+        }
+
+        // Granted locally, as a sub-admin or, for sub-admin rights only, through the role registry
+        #[ink(message)]
+        pub fn has_role(&self, role: Role, account: AccountId) -> bool {
+            account == self.admin
+                || self.roles.contains((role, account))
+                || (role == role_registry::RECIPIENT_MANAGER
+                    && (self.sub_admins_mapping.contains(account)
+                        || self.has_registry_role(role, account)))
+        }
+
+        // One call for monitoring to decide whether something needs attention
+        #[ink(message)]
+        pub fn health(&self) -> Health {
+            let block_timestamp: Timestamp = Self::env().block_timestamp();
+            let smart_contract_balance: Balance =
+                PSP22Ref::balance_of(&self.token, Self::env().account_id());
+            let started: bool = block_timestamp >= self.start;
+
+            Health {
+                admin_ops_enabled: self.admin_ops_enabled,
+                solvent: smart_contract_balance
+                    >= self
+                        .to_be_collected
+                        .saturating_add(self.total_reserved)
+                        .saturating_add(self.unfinalized_escrow())
+                        .saturating_add(self.merkle_committed()),
+                started,
+                claims_enabled: started
+                    && self.claims_enabled
+                    && self.claims_enabled_at_reached().is_ok()
+                    && self.claim_deadline_not_passed().is_ok(),
+                last_admin_action_at: self.last_admin_action_at,
+                finalized: self.escrow_finalized,
+            }
+        }
+
+        // Lets integrators feature-detect instead of assuming what a deployment supports
+        #[ink(message)]
+        pub fn interfaces(&self) -> Vec<[u8; 4]> {
+            vec![
+                INTERFACE_BUDGETS,
+                INTERFACE_CORE,
+                INTERFACE_MERKLE,
+                INTERFACE_ORGANIZATIONS,
+                INTERFACE_QUERY,
+                INTERFACE_ROLES,
+                INTERFACE_SALES,
+                INTERFACE_SCHEDULE_PHASES,
+            ]
+        }
+
+        #[ink(message)]
+        pub fn label(&self, label_id: u16) -> Result<String> {
+            self.labels
+                .get(label_id)
+                .ok_or(AzAirdropError::NotFound("Label".to_string()))
+        }
+
+        // For off-chain mirrors to detect desyncs without a full export.
+        // Only total_amount and collected are covered, not schedules.
+        #[ink(message)]
+        pub fn ledger_digest(&self) -> [u8; 32] {
+            self.ledger_digest
+        }
+
+        // (new_address, proposed at) of the pending lost key reassign for address
+        #[ink(message)]
+        pub fn lost_key_proposal(&self, address: AccountId) -> Option<(AccountId, Timestamp)> {
+            self.lost_key_proposals.get(address)
+        }
+
+        // Most that address receives per collect, None means no limit
+        #[ink(message)]
+        pub fn max_per_claim(&self, address: AccountId) -> Option<Balance> {
+            self.max_per_claims.get(address)
+        }
+
+        // 0 means not opted in
+        #[ink(message)]
+        pub fn notification_pref(&self, address: AccountId) -> u8 {
+            self.notification_prefs.get(address).unwrap_or(0)
+        }
+
+        #[ink(message)]
+        pub fn org_vesting_info(&self, org_id: u32) -> Result<OrganizationVestingInfo> {
+            let organization: Organization = self.organization_show(org_id)?;
+            let block_timestamp: Timestamp = Self::env().block_timestamp();
+            let mut org_vesting_info = OrganizationVestingInfo {
+                total_amount: 0,
+                collected: 0,
+                collectable: 0,
+            };
+            for member in organization.members.iter() {
+                // Members don't need to have an allocation
+                if let Some(recipient) = self.resolved_recipient(*member) {
+                    // These can't overflow as they are bounded by to_be_collected, but might as well
+                    org_vesting_info.total_amount = org_vesting_info
+                        .total_amount
+                        .saturating_add(recipient.total_amount);
+                    org_vesting_info.collected = org_vesting_info
+                        .collected
+                        .saturating_add(recipient.collected);
+                    org_vesting_info.collectable = org_vesting_info
+                        .collectable
+                        .saturating_add(self.collectable_amount(*member, block_timestamp)?);
+                }
+            }
+
+            Ok(org_vesting_info)
+        }
+
+        #[ink(message)]
+        pub fn organization_show(&self, org_id: u32) -> Result<Organization> {
+            self.organizations
+                .get(org_id)
+                .ok_or(AzAirdropError::NotFound("Organization".to_string()))
+        }
+
+        // Whether recipient's claims can be sent to destination.
+        // In compliance mode destination must be a payout address that recipient registered
+        // and whose cooldown has passed.
+        #[ink(message)]
+        pub fn payout_address_allowed(&self, recipient: AccountId, destination: AccountId) -> bool {
+            if !self.compliance_mode || recipient == destination {
+                return true;
+            }
+
+            match self.payout_addresses.get((recipient, destination)) {
+                Some(active_from) => Self::env().block_timestamp() >= active_from,
+                None => false,
+            }
+        }
+
+        // Order changes when recipients are removed, limit is capped at MAX_RECIPIENTS_LIST_LIMIT
+        #[ink(message)]
+        pub fn recipients_list(&self, offset: u32, limit: u32) -> Vec<(AccountId, Recipient)> {
+            let end: u32 = offset
+                .saturating_add(limit.min(MAX_RECIPIENTS_LIST_LIMIT))
+                .min(self.recipients_count);
+            (offset..end)
+                .filter_map(|index| self.recipient_addresses.get(index))
+                .filter_map(|address| {
+                    self.resolved_recipient(address)
+                        .map(|recipient| (address, recipient))
+                })
+                .collect()
+        }
+
+        // Dry run of retrofit_to_template, the addresses in the page it would point at the template
+        #[ink(message)]
+        pub fn retrofit_to_template_preview(
+            &self,
+            template_id: u16,
+            offset: u32,
+            limit: u32,
+        ) -> Result<Vec<AccountId>> {
+            self.schedule_template(template_id)?;
+
+            Ok(self
+                .retrofit_candidates(template_id, offset, limit)
+                .into_iter()
+                .map(|(address, _recipient)| address)
+                .collect())
+        }
+
+        // Flags durations that are probably misconfigured, e.g. seconds instead of ms
+        #[cfg(feature = "diagnostics")]
+        #[ink(message)]
+        pub fn schedule_sanity(&self, address: AccountId) -> Result<Vec<ScheduleWarning>> {
+            let recipient: Recipient = self.show(address)?;
+
+            Ok(schedule::sanity_warnings(
+                recipient.cliff_duration,
+                recipient.vesting_duration,
+                recipient.schedule_phases.as_deref(),
+            ))
+        }
+
+        #[ink(message)]
+        pub fn schedule_template(&self, template_id: u16) -> Result<ScheduleTemplate> {
+            self.schedule_templates
+                .get(template_id)
+                .ok_or(AzAirdropError::NotFound("Schedule template".to_string()))
+        }
+
+        #[ink(message)]
+        pub fn schema_version(&self) -> u16 {
+            SCHEMA_VERSION
+        }
+
+        // Schedule fields of recipients with a template are the template's current values
+        #[ink(message)]
+        pub fn show(&self, address: AccountId) -> Result<Recipient> {
+            self.resolved_recipient(address)
+                .ok_or(AzAirdropError::NotFound("Recipient".to_string()))
+        }
+
+        // None for unknown addresses, at most MAX_RECIPIENTS_LIST_LIMIT addresses
+        #[ink(message)]
+        pub fn show_many(&self, addresses: Vec<AccountId>) -> Result<Vec<Option<Recipient>>> {
+            Self::within_list_limit(addresses.len())?;
+
+            Ok(addresses
+                .into_iter()
+                .map(|address| self.resolved_recipient(address))
+                .collect())
+        }
+
+        // Everything needed to render the airdrop in one call
+        #[ink(message)]
+        pub fn snapshot(&self) -> CampaignSnapshot {
+            let balance: Balance = PSP22Ref::balance_of(&self.token, Self::env().account_id());
+            let started: bool = Self::env().block_timestamp() >= self.start;
+
+            CampaignSnapshot {
+                config: self.config(),
+                balance,
+                spare: balance
+                    .saturating_sub(self.to_be_collected)
+                    .saturating_sub(self.total_reserved)
+                    .saturating_sub(self.unfinalized_escrow())
+                    .saturating_sub(self.merkle_committed()),
+                started,
+                registration_open: !started || self.allow_additions_after_start,
+            }
+        }
+
+        #[ink(message)]
+        pub fn source_template(&self, source: AllocationSource) -> Option<u16> {
+            self.source_templates.get(source)
+        }
+
+        #[ink(message)]
+        pub fn statement(&self, address: AccountId) -> Result<Statement> {
+            let recipient: Recipient = self.show(address)?;
+            let block_timestamp: Timestamp = Self::env().block_timestamp();
+            let start: Timestamp = self.recipient_start(&recipient);
+            let (vesting_start, vesting_end): (Timestamp, Timestamp) =
+                if let Some(schedule_phases) = &recipient.schedule_phases {
+                    (
+                        start,
+                        start.saturating_add(schedule::end_offset(schedule_phases)),
+                    )
+                } else {
+                    // These can't overflow as checks are done in validate_airdrop_calculation_variables
+                    let vesting_start: Timestamp = start + recipient.cliff_duration;
+                    (vesting_start, vesting_start + recipient.vesting_duration)
+                };
+            // This can't underflow
+            let vesting_duration: Timestamp = vesting_end - vesting_start;
+            let allocation_origin: AllocationOrigin =
+                self.allocation_origins.get(address).unwrap_or_default();
+            let claims_count: u32 = self.claims_count.get(address).unwrap_or(0);
+            let last_claim: Option<Claim> = if claims_count > 0 {
+                self.claims.get((address, claims_count - 1))
+            } else {
+                None
+            };
+
+            // Boundaries split the vesting period into equal parts, tge is the first boundary
+            let mut boundaries: Vec<Timestamp> = vec![start];
+            if vesting_duration > 0 {
+                for period in 1..=STATEMENT_PROJECTION_PERIODS {
+                    // This can't overflow and the result is at most vesting_duration
+                    let offset: Timestamp = (u128::from(vesting_duration) * u128::from(period)
+                        / u128::from(STATEMENT_PROJECTION_PERIODS))
+                        as Timestamp;
+                    boundaries.push(vesting_start + offset);
+                }
+            }
+            let projected_unlocks: Vec<(Timestamp, Balance)> = boundaries
+                .into_iter()
+                .filter(|boundary| *boundary > block_timestamp)
+                .take(STATEMENT_PROJECTION_PERIODS as usize)
+                .map(|boundary| (boundary, self.vested_amount(&recipient, boundary)))
+                .collect();
+
+            Ok(Statement {
+                address,
+                collectable_now: self.collectable_now(address)?,
+                recipient,
+                category: allocation_origin.label_id,
+                description_hash: allocation_origin.description_hash,
+                start,
+                vesting_start,
+                vesting_end,
+                claims_count,
+                last_claim,
+                projected_unlocks,
+            })
+        }
+
+        #[ink(message)]
+        pub fn stats(&self) -> Stats {
+            // Whatever is left is void once the claim deadline passes, even before it's swept
+            let to_be_collected: Balance = if self.claim_deadline_not_passed().is_ok() {
+                self.to_be_collected
+            } else {
+                0
+            };
+
+            Stats {
+                total_allocated: self.total_collected.saturating_add(to_be_collected),
+                total_collected: self.total_collected,
+                to_be_collected,
+                recipients_count: self.recipients_count,
+                fully_collected_count: self.fully_collected_count,
+            }
+        }
+
+        // None when address isn't a sub-admin
+        #[ink(message)]
+        pub fn sub_admin_permissions(&self, address: AccountId) -> Option<SubAdminPermissions> {
+            self.sub_admins_mapping.get(address)
+        }
+
+        // When address was marked as unreachable, if it currently is
+        #[ink(message)]
+        pub fn unreachable_mark(&self, address: AccountId) -> Option<Timestamp> {
+            self.unreachable_marks.get(address)
+        }
+
+        // Cumulative amount vested at timestamp, ignoring what has been collected
+        #[ink(message)]
+        pub fn vested_at(&self, address: AccountId, timestamp: Timestamp) -> Result<Balance> {
+            let recipient: Recipient = self.show(address)?;
+
+            Ok(self.vested_amount(&recipient, timestamp))
+        }
+
+        #[ink(message)]
+        pub fn whitelisted_caller(&self, address: AccountId) -> bool {
+            self.whitelisted_callers.contains(address)
+        }
+
+        // === HANDLES ===
+        // Not a must, but good to have function
+        #[ink(message)]
+        pub fn acquire_token(&mut self, amount: Balance, from: AccountId) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::TREASURER)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+            self.airdrop_has_not_started()?;
+
+            self.transfer_from_funder(from, amount)
+        }
+
+        // Pulls from's allowance to this contract, capped at what the balance is short of covering.
+        // The balance can fall short when merkle roots or funder pulls aren't checked against it.
+        #[ink(message)]
+        pub fn acquire_token_max(&mut self, from: AccountId) -> Result<Balance> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::TREASURER)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+            self.airdrop_has_not_started()?;
+
+            let contract_address: AccountId = Self::env().account_id();
+            // This can't overflow as these are all covered by the balance, but might as well
+            let required_balance: Balance = self
+                .to_be_collected
+                .saturating_add(self.total_reserved)
+                .saturating_add(self.unfinalized_escrow())
+                .saturating_add(self.merkle_committed());
+            let shortfall: Balance = required_balance
+                .saturating_sub(PSP22Ref::balance_of(&self.token, contract_address));
+            let amount: Balance =
+                PSP22Ref::allowance(&self.token, from, contract_address).min(shortfall);
+            if amount == 0 {
+                return Err(AzAirdropError::AmountZero);
+            }
+            self.transfer_from_funder(from, amount)?;
+
+            Ok(amount)
+        }
+
+        // Called by the new address to take over the allocation old_address proposed to move
+        #[ink(message)]
+        pub fn address_change_accept(&mut self, old_address: AccountId) -> Result<Recipient> {
+            let caller: AccountId = Self::env().caller();
+            if self.address_change_proposals.get(old_address) != Some(caller) {
+                return Err(AzAirdropError::NotFound(
+                    "Address change proposal".to_string(),
+                ));
+            }
+            if self.recipients.contains(caller) {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "New address is already a recipient".to_string(),
+                ));
+            }
+
+            self.move_recipient(old_address, caller)
+        }
+
+        // Replaces any existing proposal, nothing moves until new_address accepts
+        #[ink(message)]
+        pub fn address_change_propose(&mut self, new_address: AccountId) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.show(caller)?;
+            if new_address == caller {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "New address must be different".to_string(),
+                ));
+            }
+            if self.recipients.contains(new_address) {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "New address is already a recipient".to_string(),
+                ));
+            }
+
+            self.address_change_proposals.insert(caller, &new_address);
+
+            Ok(())
+        }
+
+        // For drops too large to store every recipient up front.
+        // The first call for a leaf turns it into an allocation with the leaf's schedule,
+        // or the MerkleClaim source template when one is set, after that it's the same as collect.
+        // Each address has one leaf per root. When a rotated root corrects an address's amount
+        // upwards its new leaf tops the allocation up by the difference, the schedule stays.
+        #[ink(message)]
+        pub fn claim_with_proof(
+            &mut self,
+            leaf_index: u32,
+            amount: Balance,
+            collectable_at_tge_percentage: u8,
+            cliff_duration: Timestamp,
+            vesting_duration: Timestamp,
+            proof: Vec<MerkleHash>,
+        ) -> Result<Balance> {
+            let caller: AccountId = Self::env().caller();
+            self.apply_pending_merkle_root();
+            if !self
+                .merkle_claims
+                .contains((self.merkle_root_version, leaf_index))
+            {
+                let merkle_root: MerkleHash = self
+                    .merkle_root
+                    .ok_or(AzAirdropError::NotFound("Merkle root".to_string()))?;
+                let leaf: MerkleHash = merkle::leaf(
+                    leaf_index,
+                    caller,
+                    amount,
+                    collectable_at_tge_percentage,
+                    cliff_duration,
+                    vesting_duration,
+                );
+                if !merkle::verify(merkle_root, leaf, &proof) {
+                    return Err(AzAirdropError::InvalidProof);
+                }
+                let claimed: Option<Balance> = self.merkle_claimed.get(caller);
+                // The leaf's schedule can't be merged with an allocation from elsewhere
+                if claimed.is_none() && self.recipients.contains(caller) {
+                    return Err(AzAirdropError::UnprocessableEntity(
+                        "Caller is already a recipient".to_string(),
+                    ));
+                }
+                let top_up: Balance = amount.saturating_sub(claimed.unwrap_or(0));
+                // The leaf moves from what the root committed to to_be_collected
+                self.merkle_unclaimed = self.merkle_unclaimed.saturating_sub(amount);
+                let leaf_schedule: bool = claimed.is_none()
+                    && self
+                        .source_template_id(AllocationSource::MerkleClaim)
+                        .is_none();
+                if top_up > 0 {
+                    if leaf_schedule {
+                        Self::validate_airdrop_calculation_variables(
+                            self.start,
+                            collectable_at_tge_percentage,
+                            0,
+                            cliff_duration,
+                            vesting_duration,
+                        )?;
+                    }
+                    self.allocations_not_frozen()?;
+                    let new_to_be_collected: Balance = top_up
+                        .checked_add(self.to_be_collected)
+                        .ok_or(AzAirdropError::Overflow("to_be_collected".to_string()))?;
+                    self.cover_allocation(caller, top_up, new_to_be_collected)?;
+                    let mut recipient: Recipient = self.allocate(
+                        caller,
+                        top_up,
+                        caller,
+                        None,
+                        None,
+                        AllocationSource::MerkleClaim,
+                    )?;
+                    if leaf_schedule {
+                        recipient.collectable_at_tge_percentage = collectable_at_tge_percentage;
+                        recipient.cliff_duration = cliff_duration;
+                        recipient.vesting_duration = vesting_duration;
+                        self.insert_recipient(caller, &recipient);
+                    }
+                    self.to_be_collected = new_to_be_collected;
+                    self.merkle_claimed.insert(caller, &amount);
+                }
+                self.merkle_claims
+                    .insert((self.merkle_root_version, leaf_index), &amount);
+            }
+
+            self.collect_for_address(caller, caller, None)
+        }
+
+        #[ink(message)]
+        pub fn collect(&mut self) -> Result<Balance> {
+            let caller: AccountId = Self::env().caller();
+            self.collect_for_address(caller, caller, None)
+        }
+
+        // For organization owners to collect on behalf of members that have opted in.
+        // Tokens are always sent to the member.
+        #[ink(message)]
+        pub fn collect_for(&mut self, member: AccountId) -> Result<Balance> {
+            let caller: AccountId = Self::env().caller();
+            let org_id: u32 =
+                self.organization_memberships
+                    .get(member)
+                    .ok_or(AzAirdropError::NotFound(
+                        "Organization membership".to_string(),
+                    ))?;
+            let organization: Organization = self.organization_show(org_id)?;
+            Self::authorise(caller, organization.owner)?;
+            if self.collect_for_opt_ins.get(member) != Some(org_id) {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "Member has not opted in".to_string(),
+                ));
+            }
+
+            self.collect_for_address(member, member, None)
+        }
+
+        #[ink(message)]
+        pub fn collect_for_opt_in(&mut self, opt_in: bool) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            if opt_in {
+                let org_id: u32 =
+                    self.organization_memberships
+                        .get(caller)
+                        .ok_or(AzAirdropError::NotFound(
+                            "Organization membership".to_string(),
+                        ))?;
+                self.collect_for_opt_ins.insert(caller, &org_id);
+            } else {
+                self.collect_for_opt_ins.remove(caller);
+            }
+
+            Ok(())
+        }
+
+        // e.g. claiming from a cold wallet to a hot wallet.
+        // Under compliance mode the destination must be a registered payout address.
+        #[ink(message)]
+        pub fn collect_to(&mut self, destination: AccountId) -> Result<Balance> {
+            let caller: AccountId = Self::env().caller();
+            if !self.payout_address_allowed(caller, destination) {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "Payout address is not allowed".to_string(),
+                ));
+            }
+
+            self.collect_for_address(caller, destination, None)
+        }
+
+        // For custodians that need an exchange memo attached to the transfer.
+        // The memo is passed as the PSP22 transfer data.
+        #[ink(message)]
+        pub fn collect_with_memo(&mut self, memo: Vec<u8>) -> Result<Balance> {
+            let caller: AccountId = Self::env().caller();
+            if memo.is_empty() || memo.len() > MAX_MEMO_LENGTH {
+                return Err(AzAirdropError::UnprocessableEntity(format!(
+                    "Memo must be between 1 and {MAX_MEMO_LENGTH} bytes"
+                )));
+            }
+
+            self.collect_for_address(caller, caller, Some(memo))
+        }
+
+        // For custodial recipients that will never collect themselves.
+        // Tokens are always sent to the recipient.
+        #[ink(message)]
+        pub fn distribute(&mut self, address: AccountId) -> Result<Balance> {
+            self.authorise_to_update_recipient(None)?;
+
+            self.collect_for_address(address, address, None)
+        }
+
+        // An address that can't be collected for gets its error back instead of reverting the batch.
+        // At most MAX_RECIPIENTS_LIST_LIMIT addresses.
+        #[ink(message)]
+        pub fn distribute_batch(
+            &mut self,
+            addresses: Vec<AccountId>,
+        ) -> Result<Vec<Result<Balance>>> {
+            self.authorise_to_update_recipient(None)?;
+            Self::within_list_limit(addresses.len())?;
+
+            Ok(addresses
+                .into_iter()
+                .map(|address| self.collect_for_address(address, address, None))
+                .collect())
+        }
+
+        // Collects for the recipients at positions offset..offset + limit, limit is capped at
+        // MAX_RECIPIENTS_LIST_LIMIT. Positions follow the recipient index, or a permutation of it
+        // when distribution_seed is set so gas running out doesn't always cut off the same cohort.
+        // Order changes when recipients are removed.
+        #[ink(message)]
+        pub fn distribute_range(
+            &mut self,
+            offset: u32,
+            limit: u32,
+        ) -> Result<Vec<(AccountId, Result<Balance>)>> {
+            self.authorise_to_update_recipient(None)?;
+            let end: u32 = offset
+                .saturating_add(limit.min(MAX_RECIPIENTS_LIST_LIMIT))
+                .min(self.recipients_count);
+            let permutation: Option<Permutation> = self
+                .distribution_seed
+                .map(|seed| Permutation::new(&seed, self.recipients_count));
+
+            let mut results: Vec<(AccountId, Result<Balance>)> = Vec::new();
+            for position in offset..end {
+                let index: u32 = match permutation {
+                    Some(ref permutation) => permutation.index(position),
+                    None => position,
+                };
+                if let Some(address) = self.recipient_addresses.get(index) {
+                    results.push((address, self.collect_for_address(address, address, None)));
+                }
+            }
+
+            Ok(results)
+        }
+
+        // Anyone can co-fund while the escrow is open
+        #[ink(message)]
+        pub fn escrow_deposit(&mut self, amount: Balance) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.escrow_open()?;
+            if amount == 0 {
+                return Err(AzAirdropError::AmountZero);
+            }
+            let new_total_escrowed: Balance = self
+                .total_escrowed
+                .checked_add(amount)
+                .ok_or(AzAirdropError::Overflow("total_escrowed".to_string()))?;
+
+            self.transfer_from_funder(caller, amount)?;
+            // This can't overflow as it's part of total_escrowed
+            self.escrow_deposits
+                .insert(caller, &(self.escrowed_by(caller) + amount));
+            self.total_escrowed = new_total_escrowed;
+
+            Ok(())
+        }
+
+        // Makes escrowed deposits available to the campaign, after this they can't be withdrawn
+        #[ink(message)]
+        pub fn finalize(&mut self) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+            self.escrow_open()?;
+
+            self.escrow_finalized = true;
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::EscrowFinalize(EscrowFinalize {
+                    caller,
+                    amount: self.total_escrowed,
+                }),
+            );
+
+            Ok(())
+        }
+
+        // Lets a recipient publicly commit to a longer lock on their own allocation.
+        // Durations can only be extended so the schedule never gets looser.
+        #[ink(message)]
+        pub fn harden_schedule(
+            &mut self,
+            extra_cliff: Timestamp,
+            extra_vesting: Timestamp,
+        ) -> Result<Recipient> {
+            let caller: AccountId = Self::env().caller();
+            let mut recipient: Recipient = self.show(caller)?;
+            if extra_cliff == 0 && extra_vesting == 0 {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "extra_cliff or extra_vesting must be greater than 0".to_string(),
+                ));
+            }
+            if recipient.schedule_phases.is_some() {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "Schedules with phases can't be hardened".to_string(),
+                ));
+            }
+
+            // Hardened from the template's values, later template updates no longer apply
+            recipient.template_id = None;
+            recipient.cliff_duration = recipient.cliff_duration.saturating_add(extra_cliff);
+            recipient.vesting_duration = recipient.vesting_duration.saturating_add(extra_vesting);
+            Self::validate_airdrop_calculation_variables(
+                self.recipient_start(&recipient),
+                recipient.collectable_at_tge_percentage,
+                recipient.collectable_at_cliff_percentage,
+                recipient.cliff_duration,
+                recipient.vesting_duration,
+            )?;
+            self.insert_recipient(caller, &recipient);
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::ScheduleHardened(ScheduleHardened {
+                    address: caller,
+                    allocation_id: recipient.allocation_id,
+                    extra_cliff,
+                    extra_vesting,
+                    cliff_duration: recipient.cliff_duration,
+                    vesting_duration: recipient.vesting_duration,
+                }),
+            );
+
+            Ok(recipient)
+        }
+
+        // Emergency stop for a bad snapshot, takes effect immediately.
+        // Addresses that have already claimed keep their allocation.
+        #[ink(message)]
+        pub fn invalidate_merkle_root(&mut self) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+
+            self.merkle_root = None;
+            self.pending_merkle_root = None;
+            self.merkle_unclaimed = 0;
+            self.pending_merkle_unclaimed = 0;
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::MerkleRootInvalidate(MerkleRootInvalidate { caller }),
+            );
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn labels_remove(&mut self, label_id: u16) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+
+            let label: String = self.label(label_id)?;
+            self.labels.remove(label_id);
+            self.label_ids.remove(label);
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::LabelRemove(LabelRemove { label_id, caller }),
+            );
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn labels_set(&mut self, label_id: u16, label: String) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+            if let Some(existing_label_id) = self.label_ids.get(&label) {
+                if existing_label_id != label_id {
+                    return Err(AzAirdropError::UnprocessableEntity(
+                        "Label already exists".to_string(),
+                    ));
+                }
+            }
+
+            if let Some(previous_label) = self.labels.get(label_id) {
+                self.label_ids.remove(previous_label);
+            }
+            self.labels.insert(label_id, &label);
+            self.label_ids.insert(&label, &label_id);
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::LabelSet(LabelSet {
+                    label_id,
+                    label,
+                    caller,
+                }),
+            );
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn organization_create(&mut self, owner: AccountId) -> Result<u32> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+
+            let org_id: u32 = self.organizations_count;
+            self.organizations_count = self.organizations_count.checked_add(1).ok_or(
+                AzAirdropError::UnprocessableEntity("Organization limit reached".to_string()),
+            )?;
+            self.organizations.insert(
+                org_id,
+                &Organization {
+                    owner,
+                    members: BoundedVec::default(),
+                },
+            );
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::OrganizationCreate(OrganizationCreate {
+                    org_id,
+                    owner,
+                    caller,
+                }),
+            );
+
+            Ok(org_id)
+        }
+
+        #[ink(message)]
+        pub fn organization_members_add(
+            &mut self,
+            org_id: u32,
+            address: AccountId,
+        ) -> Result<Vec<AccountId>> {
+            let caller: AccountId = Self::env().caller();
+            let mut organization: Organization = self.organization_show(org_id)?;
+            Self::authorise(caller, organization.owner)?;
+            if self.organization_memberships.get(address).is_some() {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "Already a member of an organization".to_string(),
+                ));
+            }
+
+            organization.members.try_push(
+                address,
+                self.max_organization_members,
+                "Organization members",
+            )?;
+            self.organizations.insert(org_id, &organization);
+            self.organization_memberships.insert(address, &org_id);
+
+            Ok(organization.members.into_inner())
+        }
+
+        #[ink(message)]
+        pub fn organization_members_remove(
+            &mut self,
+            org_id: u32,
+            address: AccountId,
+        ) -> Result<Vec<AccountId>> {
+            let caller: AccountId = Self::env().caller();
+            let mut organization: Organization = self.organization_show(org_id)?;
+            Self::authorise(caller, organization.owner)?;
+            if self.organization_memberships.get(address) != Some(org_id) {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "Not a member of organization".to_string(),
+                ));
+            }
+
+            organization.members.remove_item(&address);
+            self.organizations.insert(org_id, &organization);
+            self.organization_memberships.remove(address);
+            self.collect_for_opt_ins.remove(address);
+
+            Ok(organization.members.into_inner())
+        }
+
+        #[ink(message)]
+        pub fn organization_update_owner(&mut self, org_id: u32, owner: AccountId) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            let mut organization: Organization = self.organization_show(org_id)?;
+            Self::authorise(caller, organization.owner)?;
+
+            organization.owner = owner;
+            self.organizations.insert(org_id, &organization);
+
+            Ok(())
+        }
+
+        // This is for the sales smart contract to call.
+        // After start, with allow_additions_after_start, only new recipients can be added and they vest from the addition.
+        // Whitelist it and enable whitelisted_callers_only to stop sub-admin keys calling it directly.
+        #[ink(message)]
+        pub fn recipient_add(
+            &mut self,
+            address: AccountId,
+            amount: Balance,
+            description: Option<Description>,
+            template_id: Option<u16>,
+            start_override: Option<Timestamp>,
+        ) -> Result<Recipient> {
+            self.authorise_to_update_recipient(Some(SubAdminPermission::Add))?;
+            self.whitelisted_caller_check(Self::env().caller())?;
+            let addition_start: Option<Timestamp> = self.addition_start(address)?;
+            self.allocations_not_frozen()?;
+            let (label_id, description): (Option<u16>, Option<String>) =
+                self.normalize_description(description)?;
+            let template: Option<ScheduleTemplate> = match template_id {
+                Some(template_id) => {
+                    // Existing schedules can only be changed through update_recipient
+                    if self.recipients.contains(address) {
+                        return Err(AzAirdropError::UnprocessableEntity(
+                            "Template can only be applied to new recipients".to_string(),
+                        ));
+                    }
+                    let template: ScheduleTemplate = self.schedule_template(template_id)?;
+                    Self::validate_airdrop_calculation_variables(
+                        self.start,
+                        template.collectable_at_tge_percentage,
+                        0,
+                        template.cliff_duration,
+                        template.vesting_duration,
+                    )?;
+                    Some(template)
+                }
+                None => None,
+            };
+            let start_override: Option<Timestamp> = match addition_start {
+                Some(addition_start) => {
+                    if start_override.unwrap_or(addition_start) < addition_start {
+                        return Err(AzAirdropError::UnprocessableEntity(
+                            "start_override can't be before the addition".to_string(),
+                        ));
+                    }
+                    Some(start_override.unwrap_or(addition_start))
+                }
+                None => start_override,
+            };
+            if let Some(start_override_unwrapped) = start_override {
+                // Existing schedules can only be changed through update_recipient
+                if self.recipients.contains(address) {
+                    return Err(AzAirdropError::UnprocessableEntity(
+                        "Start override can only be applied to new recipients".to_string(),
+                    ));
+                }
+                let (collectable_at_tge_percentage, cliff_duration, vesting_duration) =
+                    match template {
+                        Some(ref template) => (
+                            template.collectable_at_tge_percentage,
+                            template.cliff_duration,
+                            template.vesting_duration,
+                        ),
+                        None => self.default_schedule(self.allocation_source(Self::env().caller())),
+                    };
+                self.validate_start_override(start_override_unwrapped)?;
+                Self::validate_airdrop_calculation_variables(
+                    start_override_unwrapped,
+                    collectable_at_tge_percentage,
+                    0,
+                    cliff_duration,
+                    vesting_duration,
+                )?;
+            }
+            let new_to_be_collected: Balance = amount
+                .checked_add(self.to_be_collected)
+                .ok_or(AzAirdropError::Overflow("to_be_collected".to_string()))?;
+            let caller: AccountId = Self::env().caller();
+            self.cover_allocation(caller, amount, new_to_be_collected)?;
+
+            let mut recipient: Recipient = self.allocate(
+                address,
+                amount,
+                caller,
+                label_id,
+                description,
+                self.allocation_source(caller),
+            )?;
+            if template_id.is_some() || start_override.is_some() {
+                recipient.template_id = template_id.or(recipient.template_id);
+                recipient.start_override = start_override;
+                self.insert_recipient(address, &recipient);
+            }
+            self.to_be_collected = new_to_be_collected;
+
+            self.show(address)
+        }
+
+        // Covers the total amount once, then adds each entry as recipient_add would.
+        // Any invalid entry fails the whole batch.
+        #[ink(message)]
+        pub fn recipient_add_batch(
+            &mut self,
+            entries: Vec<(AccountId, Balance, Option<Description>)>,
+        ) -> Result<Vec<Recipient>> {
+            self.authorise_to_update_recipient(Some(SubAdminPermission::Add))?;
+            self.whitelisted_caller_check(Self::env().caller())?;
+            self.allocations_not_frozen()?;
+            let mut normalized_entries: Vec<(AccountId, Balance, Option<u16>, Option<String>)> =
+                Vec::with_capacity(entries.len());
+            let mut new_to_be_collected: Balance = self.to_be_collected;
+            // The same for every entry as they're all added in this block
+            let mut addition_start: Option<Timestamp> = None;
+            for (address, amount, description) in entries.into_iter() {
+                addition_start = self.addition_start(address)?;
+                let (label_id, description): (Option<u16>, Option<String>) =
+                    self.normalize_description(description)?;
+                new_to_be_collected = new_to_be_collected
+                    .checked_add(amount)
+                    .ok_or(AzAirdropError::Overflow("to_be_collected".to_string()))?;
+                normalized_entries.push((address, amount, label_id, description));
+            }
+            let caller: AccountId = Self::env().caller();
+            // This can't underflow as new_to_be_collected only increased
+            self.cover_allocation(
+                caller,
+                new_to_be_collected - self.to_be_collected,
+                new_to_be_collected,
+            )?;
+
+            let source: AllocationSource = self.allocation_source(caller);
+            let mut recipients: Vec<Recipient> = Vec::with_capacity(normalized_entries.len());
+            for (address, amount, label_id, description) in normalized_entries.into_iter() {
+                let mut recipient: Recipient =
+                    self.allocate(address, amount, caller, label_id, description, source)?;
+                // Recipients added after start vest from when they were added
+                if addition_start.is_some() {
+                    recipient.start_override = addition_start;
+                    self.insert_recipient(address, &recipient);
+                }
+                recipients.push(recipient);
+            }
+            self.to_be_collected = new_to_be_collected;
+
+            Ok(recipients)
+        }
+
+        // Cancels a pending lost key reassign.
+        // address can cancel it itself, which shows its keys aren't lost.
+        #[ink(message)]
+        pub fn recipient_lost_key_cancel(&mut self, address: AccountId) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            if caller != address {
+                self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+                self.admin_ops_not_paused()?;
+                self.record_admin_action();
+            }
+            if !self.lost_key_proposals.contains(address) {
+                return Err(AzAirdropError::NotFound("Lost key proposal".to_string()));
+            }
+
+            self.lost_key_proposals.remove(address);
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::RecipientLostKeyCancel(RecipientLostKeyCancel { address, caller }),
+            );
+
+            Ok(())
+        }
+
+        // For recipients that lost access to their keys, whatever they have collected.
+        // Replaces any existing proposal, recipient_lost_key_reassign can move the allocation
+        // after LOST_KEY_REASSIGN_DELAY.
+        #[ink(message)]
+        pub fn recipient_lost_key_propose(
+            &mut self,
+            address: AccountId,
+            new_address: AccountId,
+        ) -> Result<Timestamp> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+            self.show(address)?;
+            if self.recipients.contains(new_address) {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "New address is already a recipient".to_string(),
+                ));
+            }
+
+            let block_timestamp: Timestamp = Self::env().block_timestamp();
+            self.lost_key_proposals
+                .insert(address, &(new_address, block_timestamp));
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::RecipientLostKeyPropose(RecipientLostKeyPropose {
+                    address,
+                    new_address,
+                    applies_from: block_timestamp.saturating_add(LOST_KEY_REASSIGN_DELAY),
+                    caller,
+                }),
+            );
+
+            Ok(block_timestamp)
+        }
+
+        // Moves the full allocation, collected included, to the proposed address
+        #[ink(message)]
+        pub fn recipient_lost_key_reassign(&mut self, address: AccountId) -> Result<Recipient> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+            let (new_address, proposed_at): (AccountId, Timestamp) = self
+                .lost_key_proposals
+                .get(address)
+                .ok_or(AzAirdropError::NotFound("Lost key proposal".to_string()))?;
+            let block_timestamp: Timestamp = Self::env().block_timestamp();
+            if block_timestamp < proposed_at.saturating_add(LOST_KEY_REASSIGN_DELAY) {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "Reassign delay has not passed".to_string(),
+                ));
+            }
+            if self.recipients.contains(new_address) {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "New address is already a recipient".to_string(),
+                ));
+            }
+
+            let recipient: Recipient = self.move_recipient(address, new_address)?;
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::RecipientLostKeyReassign(RecipientLostKeyReassign {
+                    address,
+                    new_address,
+                    allocation_id: recipient.allocation_id,
+                    external_ref: recipient.external_ref,
+                    total_amount: recipient.total_amount,
+                    collected: recipient.collected,
+                    caller,
+                }),
+            );
+
+            Ok(recipient)
+        }
+
+        // For allocations that have been fully vested for UNREACHABLE_AFTER without a single claim
+        // e.g. a contract address that can't call collect.
+        // A collect by the recipient clears the mark.
+        #[ink(message)]
+        pub fn recipient_mark_unreachable(&mut self, address: AccountId) -> Result<Timestamp> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+            let recipient: Recipient = self.show(address)?;
+            if self.unreachable_marks.contains(address) {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "Recipient is already marked as unreachable".to_string(),
+                ));
+            }
+            let block_timestamp: Timestamp = Self::env().block_timestamp();
+            let matured: bool = block_timestamp
+                >= self
+                    .recipient_start(&recipient)
+                    .saturating_add(UNREACHABLE_AFTER)
+                && self.vested_amount(&recipient, block_timestamp - UNREACHABLE_AFTER)
+                    == recipient.total_amount;
+            if !matured || recipient.collected > 0 {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "Recipient has not been matured and unclaimed for long enough".to_string(),
+                ));
+            }
+
+            self.unreachable_marks.insert(address, &block_timestamp);
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::RecipientMarkUnreachable(RecipientMarkUnreachable {
+                    address,
+                    allocation_id: recipient.allocation_id,
+                    caller,
+                }),
+            );
+
+            Ok(block_timestamp)
+        }
+
+        // Moves an unreachable allocation to new_address once UNREACHABLE_REASSIGN_DELAY has passed
+        #[ink(message)]
+        pub fn recipient_reassign(
+            &mut self,
+            address: AccountId,
+            new_address: AccountId,
+        ) -> Result<Recipient> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+            let marked_at: Timestamp = self
+                .unreachable_marks
+                .get(address)
+                .ok_or(AzAirdropError::NotFound("Unreachable mark".to_string()))?;
+            let block_timestamp: Timestamp = Self::env().block_timestamp();
+            if block_timestamp < marked_at.saturating_add(UNREACHABLE_REASSIGN_DELAY) {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "Reassign delay has not passed".to_string(),
+                ));
+            }
+            if self.recipients.contains(new_address) {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "New address is already a recipient".to_string(),
+                ));
+            }
+
+            self.move_recipient(address, new_address)
+        }
+
+        // Deletes the recipient's storage entries so their storage deposit is refunded.
+        // Nothing can have been collected before start.
+        #[ink(message)]
+        pub fn recipient_remove(&mut self, address: AccountId) -> Result<Recipient> {
+            self.authorise_to_update_recipient(Some(SubAdminPermission::Subtract))?;
+            self.airdrop_has_not_started()?;
+            self.schedule_not_frozen()?;
+            let recipient: Recipient = self.show(address)?;
+
+            self.remove_recipient(address);
+            self.max_per_claims.remove(address);
+            self.unreachable_marks.remove(address);
+            self.lost_key_proposals.remove(address);
+            self.address_change_proposals.remove(address);
+            // This can't underflow as total_amount is part of to_be_collected
+            self.to_be_collected = self.to_be_collected.saturating_sub(recipient.total_amount);
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::RecipientRemove(RecipientRemove {
+                    address,
+                    allocation_id: recipient.allocation_id,
+                    external_ref: recipient.external_ref,
+                    amount: recipient.total_amount,
+                    caller: Self::env().caller(),
+                }),
+            );
+
+            Ok(recipient)
+        }
+
+        // Stops further vesting and sends the unvested amount to clawback (or admin).
+        // What has already vested stays collectable.
+        #[ink(message)]
+        pub fn recipient_revoke(&mut self, address: AccountId) -> Result<Balance> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+            let block_timestamp: Timestamp = Self::env().block_timestamp();
+            if block_timestamp < self.start {
+                return Err(AzAirdropError::AirdropNotStarted);
+            }
+            self.claim_deadline_not_passed()?;
+            let mut recipient: Recipient = self.show(address)?;
+            if !recipient.revocable {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "Recipient is not revocable".to_string(),
+                ));
+            }
+            let vested: Balance = self.vested_amount(&recipient, block_timestamp);
+            // This can't underflow as vested_amount is capped at total_amount
+            let unvested: Balance = recipient.total_amount - vested;
+            if unvested == 0 {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "Recipient has nothing left to vest".to_string(),
+                ));
+            }
+
+            // Everything left is vested and collectable
+            recipient.total_amount = vested;
+            recipient.collectable_at_tge_percentage = 100;
+            recipient.collectable_at_cliff_percentage = 0;
+            recipient.cliff_duration = 0;
+            recipient.vesting_duration = 0;
+            recipient.schedule_phases = None;
+            recipient.curve = Curve::Linear;
+            recipient.template_id = None;
+            self.insert_recipient(address, &recipient);
+            // This can't underflow as unvested is part of to_be_collected
+            self.to_be_collected -= unvested;
+            let to: AccountId = self.clawback.unwrap_or(self.admin);
+            PSP22Ref::transfer_builder(&self.token, to, unvested, vec![])
+                .call_flags(CallFlags::default())
+                .invoke()?;
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::RecipientRevoke(RecipientRevoke {
+                    address,
+                    allocation_id: recipient.allocation_id,
+                    external_ref: recipient.external_ref,
+                    amount: unvested,
+                    to,
+                }),
+            );
+            self.notify_observer(
+                ink::selector_bytes!("VestingObserver::on_revoke"),
+                Some(address),
+                unvested,
+            );
+
+            Ok(unvested)
+        }
+
+        #[ink(message)]
+        pub fn recipient_subtract(
+            &mut self,
+            address: AccountId,
+            amount: Balance,
+            description: Option<String>,
+        ) -> Result<Recipient> {
+            self.authorise_to_update_recipient(Some(SubAdminPermission::Subtract))?;
+            self.airdrop_has_not_started()?;
+            self.schedule_not_frozen()?;
+            let mut recipient = self.show(address)?;
+            if amount > recipient.total_amount {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "Amount is greater than recipient's total amount".to_string(),
+                ));
+            }
+
+            // Update recipient
+            // This can't overflow because of the above check
+            recipient.total_amount -= amount;
+            self.insert_recipient(address, &recipient);
+
+            // Update config
+            // This can't overflow but might as well
+            self.to_be_collected = self.to_be_collected.saturating_sub(amount);
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::RecipientSubtract(RecipientSubtract {
+                    address,
+                    allocation_id: recipient.allocation_id,
+                    external_ref: recipient.external_ref,
+                    amount,
+                    caller: Self::env().caller(),
+                    description: description.filter(|_| self.full_events()),
+                }),
+            );
+
+            Ok(recipient)
+        }
+
+        // e.g. an insurance contract paying out coverage on revocations and sweeps
+        #[ink(message)]
+        pub fn register_observer(&mut self, observer: Option<AccountId>) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+
+            self.observer = observer;
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::ObserverRegister(ObserverRegister { observer, caller }),
+            );
+
+            Ok(())
+        }
+
+        // Returns when address becomes usable as a payout address for caller
+        #[ink(message)]
+        pub fn register_payout_address(&mut self, address: AccountId) -> Result<Timestamp> {
+            let caller: AccountId = Self::env().caller();
+            self.show(caller)?;
+            if self.payout_addresses.contains((caller, address)) {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "Payout address is already registered".to_string(),
+                ));
+            }
+
+            let active_from: Timestamp = Self::env()
+                .block_timestamp()
+                .saturating_add(PAYOUT_ADDRESS_COOLDOWN);
+            self.payout_addresses
+                .insert((caller, address), &active_from);
+
+            Ok(active_from)
+        }
+
+        #[ink(message)]
+        pub fn rescue_native(&mut self, to: AccountId, amount: Balance) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+            let available: Balance = Self::env().balance().saturating_sub(self.native_reserve);
+            if amount > available {
+                return Err(AzAirdropError::LimitExceeded {
+                    limit: available,
+                    requested: amount,
+                });
+            }
+
+            Self::env().transfer(to, amount)?;
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::NativeRescue(NativeRescue { to, amount, caller }),
+            );
+
+            Ok(())
+        }
+
+        // For other PSP22 tokens sent here by mistake, the airdrop token goes through return_spare_tokens
+        #[ink(message)]
+        pub fn rescue_token(&mut self, token: AccountId, to: AccountId) -> Result<Balance> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+            if token == self.token {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "Airdrop token can't be rescued".to_string(),
+                ));
+            }
+
+            let balance: Balance = PSP22Ref::balance_of(&token, Self::env().account_id());
+            if balance == 0 {
+                return Err(AzAirdropError::AmountZero);
+            }
+            PSP22Ref::transfer_builder(&token, to, balance, vec![])
+                .call_flags(CallFlags::default())
+                .invoke()?;
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::TokenRescue(TokenRescue {
+                    token,
+                    to,
+                    amount: balance,
+                    caller,
+                }),
+            );
+
+            Ok(balance)
+        }
+
+        // Guarantees caller (e.g. a sales smart contract) an allocation quota for recipient_add.
+        // Replaces any existing reservation for caller.
+        #[ink(message)]
+        pub fn reserve_budget(&mut self, caller: AccountId, amount: Balance) -> Result<Balance> {
+            let admin: AccountId = Self::env().caller();
+            self.authorise_role(admin, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+
+            // These can't overflow as the existing reservation is part of total_reserved
+            let new_total_reserved: Balance = (self.total_reserved - self.budget(caller))
+                .checked_add(amount)
+                .ok_or(AzAirdropError::Overflow("total_reserved".to_string()))?;
+            let smart_contract_balance: Balance =
+                PSP22Ref::balance_of(&self.token, Self::env().account_id());
+            if self
+                .to_be_collected
+                .saturating_add(new_total_reserved)
+                .saturating_add(self.unfinalized_escrow())
+                .saturating_add(self.merkle_committed())
+                > smart_contract_balance
+            {
+                return Err(AzAirdropError::InsufficientContractBalance);
+            }
+            self.set_budget(caller, amount);
+            self.total_reserved = new_total_reserved;
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::BudgetReserve(BudgetReserve {
+                    address: caller,
+                    budget: amount,
+                    caller: admin,
+                }),
+            );
+
+            Ok(new_total_reserved)
+        }
+
+        // Points a page of recipients at the template so they follow its future updates.
+        // Recipients with schedule phases are skipped as phases replace the inline schedule.
+        #[ink(message)]
+        pub fn retrofit_to_template(
+            &mut self,
+            template_id: u16,
+            offset: u32,
+            limit: u32,
+        ) -> Result<Vec<AccountId>> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+            self.airdrop_has_not_started()?;
+            self.schedule_not_frozen()?;
+            let template: ScheduleTemplate = self.schedule_template(template_id)?;
+
+            let patch: RecipientPatch = RecipientPatch {
+                collectable_at_tge_percentage: Some(template.collectable_at_tge_percentage),
+                cliff_duration: Some(template.cliff_duration),
+                vesting_duration: Some(template.vesting_duration),
+                curve: None,
+                start_override: None,
+                collectable_at_cliff_percentage: Some(0),
+            };
+            let mut addresses: Vec<AccountId> = Vec::new();
+            for (address, _recipient) in self.retrofit_candidates(template_id, offset, limit) {
+                let mut recipient: Recipient = self.patch_recipient(address, &patch)?;
+                recipient.template_id = Some(template_id);
+                self.insert_recipient(address, &recipient);
+                addresses.push(address);
+            }
+
+            Ok(addresses)
+        }
+
+        // Spare tokens always go to admin, whoever triggers the return
+        #[ink(message)]
+        pub fn return_spare_tokens(&mut self) -> Result<Balance> {
+            let caller: AccountId = Self::env().caller();
+            let contract_address: AccountId = Self::env().account_id();
+            self.authorise_role(caller, role_registry::TREASURER)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+
+            let balance: Balance = PSP22Ref::balance_of(&self.token, contract_address);
+            // These can't overflow, but might as well
+            let spare_amount: Balance = balance
+                .saturating_sub(self.to_be_collected)
+                .saturating_sub(self.total_reserved)
+                .saturating_sub(self.unfinalized_escrow())
+                .saturating_sub(self.merkle_committed());
+            if spare_amount > 0 {
+                PSP22Ref::transfer_builder(&self.token, self.admin, spare_amount, vec![])
+                    .call_flags(CallFlags::default())
+                    .invoke()?;
+
+                // emit event
+                Self::emit_event(
+                    self.env(),
+                    Event::SpareReturned(SpareReturned {
+                        to: self.admin,
+                        amount: spare_amount,
+                        caller,
+                    }),
+                );
+            } else {
+                return Err(AzAirdropError::AmountZero);
+            }
+
+            Ok(spare_amount)
+        }
+
+        // Safety valve for integrations that leave an allowance on the token behind
+        #[ink(message)]
+        pub fn revoke_all_allowances(&mut self, spender: AccountId) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+
+            PSP22Ref::approve_builder(&self.token, spender, 0)
+                .call_flags(CallFlags::default())
+                .invoke()?;
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::AllowanceRevoke(AllowanceRevoke { spender, caller }),
+            );
+
+            Ok(())
+        }
+
+        // Sub-admins are granted through sub_admins_add so max_sub_admins applies
+        #[ink(message)]
+        pub fn role_grant(&mut self, role: Role, account: AccountId) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+            Self::grantable_role(role)?;
+
+            self.roles.insert((role, account), &());
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::RoleGrant(RoleGrant {
+                    role,
+                    account,
+                    caller,
+                }),
+            );
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn role_revoke(&mut self, role: Role, account: AccountId) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+            Self::grantable_role(role)?;
+            if !self.roles.contains((role, account)) {
+                return Err(AzAirdropError::NotFound("Role".to_string()));
+            }
+
+            self.roles.remove((role, account));
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::RoleRevoke(RoleRevoke {
+                    role,
+                    account,
+                    caller,
+                }),
+            );
+
+            Ok(())
+        }
+
+        // The first root applies immediately.
+        // Replacements apply after MERKLE_ROOT_ROTATION_DELAY so claimants can check them first.
+        // Addresses that have already claimed keep their allocation.
+        // total is what the root's unclaimed leaves add up to, it's kept out of spare tokens.
+        #[ink(message)]
+        pub fn rotate_merkle_root(
+            &mut self,
+            merkle_root: MerkleHash,
+            total: Balance,
+        ) -> Result<Timestamp> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+            self.apply_pending_merkle_root();
+            let block_timestamp: Timestamp = Self::env().block_timestamp();
+
+            self.pending_merkle_unclaimed = total;
+            let applies_from: Timestamp = if self.merkle_root.is_none() {
+                self.pending_merkle_root = Some((merkle_root, block_timestamp));
+                self.apply_pending_merkle_root();
+                block_timestamp
+            } else {
+                let applies_from: Timestamp =
+                    block_timestamp.saturating_add(MERKLE_ROOT_ROTATION_DELAY);
+                self.pending_merkle_root = Some((merkle_root, applies_from));
+                applies_from
+            };
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::MerkleRootRotate(MerkleRootRotate {
+                    merkle_root,
+                    applies_from,
+                    total,
+                    caller,
+                }),
+            );
+
+            Ok(applies_from)
+        }
+
+        #[ink(message)]
+        pub fn schedule_template_add(
+            &mut self,
+            name: String,
+            collectable_at_tge_percentage: u8,
+            cliff_duration: Timestamp,
+            vesting_duration: Timestamp,
+        ) -> Result<u16> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+            Self::validate_airdrop_calculation_variables(
+                self.start,
+                collectable_at_tge_percentage,
+                0,
+                cliff_duration,
+                vesting_duration,
+            )?;
+            let template_id: u16 = self.schedule_templates_count;
+            self.schedule_templates_count = self.schedule_templates_count.checked_add(1).ok_or(
+                AzAirdropError::UnprocessableEntity("Schedule template limit reached".to_string()),
+            )?;
+
+            let template: ScheduleTemplate = ScheduleTemplate {
+                name,
+                collectable_at_tge_percentage,
+                cliff_duration,
+                vesting_duration,
+            };
+            self.schedule_templates.insert(template_id, &template);
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::ScheduleTemplateAdd(ScheduleTemplateAdd {
+                    template_id,
+                    template,
+                    caller,
+                }),
+            );
+
+            Ok(template_id)
+        }
+
+        // Only templates that no recipient references can be removed
+        #[ink(message)]
+        pub fn schedule_template_remove(&mut self, template_id: u16) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+            self.schedule_template(template_id)?;
+            if self.schedule_template_recipients.contains(template_id) {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "Schedule template is in use".to_string(),
+                ));
+            }
+
+            self.schedule_templates.remove(template_id);
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::ScheduleTemplateRemove(ScheduleTemplateRemove {
+                    template_id,
+                    caller,
+                }),
+            );
+
+            Ok(())
+        }
+
+        // Only templates that no recipient references can be updated, as the new values would
+        // skip each recipient's validation. Add a new template and retrofit_to_template instead.
+        #[ink(message)]
+        pub fn schedule_template_update(
+            &mut self,
+            template_id: u16,
+            template: ScheduleTemplate,
+        ) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+            self.schedule_template(template_id)?;
+            if self.schedule_template_recipients.contains(template_id) {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "Schedule template is in use".to_string(),
+                ));
+            }
+            Self::validate_airdrop_calculation_variables(
+                self.start,
+                template.collectable_at_tge_percentage,
+                0,
+                template.cliff_duration,
+                template.vesting_duration,
+            )?;
+
+            self.schedule_templates.insert(template_id, &template);
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::ScheduleTemplateUpdate(ScheduleTemplateUpdate {
+                    template_id,
+                    template,
+                    caller,
+                }),
+            );
+
+            Ok(())
+        }
+
+        // Setting 0 opts out and frees the storage
+        #[ink(message)]
+        pub fn set_notification_pref(&mut self, flags: u8) -> Result<u8> {
+            let caller: AccountId = Self::env().caller();
+            self.show(caller)?;
+            if flags == 0 {
+                self.notification_prefs.remove(caller);
+            } else {
+                self.notification_prefs.insert(caller, &flags);
+            }
+
+            Ok(flags)
+        }
+
+        #[ink(message)]
+        pub fn sub_admins_add(
+            &mut self,
+            address: AccountId,
+            permissions: SubAdminPermissions,
+        ) -> Result<Vec<AccountId>> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+
+            let mut sub_admins: BoundedVec<AccountId> = self.sub_admins_as_vec.get_or_default();
+            if self.sub_admins_mapping.contains(address) {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "Already a sub admin".to_string(),
+                ));
+            } else {
+                sub_admins.try_push(address, self.max_sub_admins, "Sub admins")?;
+                self.sub_admins_mapping.insert(address, &permissions);
+            }
+            self.sub_admins_as_vec.set(&sub_admins);
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::SubAdminAdd(SubAdminAdd { address, caller }),
+            );
+
+            Ok(sub_admins.into_inner())
+        }
+
+        #[ink(message)]
+        pub fn sub_admins_remove(&mut self, address: AccountId) -> Result<Vec<AccountId>> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+
+            let mut sub_admins: BoundedVec<AccountId> = self.sub_admins_as_vec.get_or_default();
+            if !self.sub_admins_mapping.contains(address) {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "Not a sub admin".to_string(),
+                ));
+            } else {
+                sub_admins.remove_item(&address);
+                self.sub_admins_mapping.remove(address);
+            }
+            self.sub_admins_as_vec.set(&sub_admins);
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::SubAdminRemove(SubAdminRemove { address, caller }),
+            );
+
+            Ok(sub_admins.into_inner())
+        }
+
+        // Sends everything still owed to recipients to clawback, or admin if it isn't set,
+        // once the claim deadline has passed.
+        // Recipients' remaining balances are void from the deadline on.
+        #[ink(message)]
+        pub fn sweep_unclaimed(&mut self) -> Result<Balance> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::TREASURER)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+            let claim_deadline: Timestamp = self
+                .claim_deadline
+                .ok_or(AzAirdropError::NotFound("Claim deadline".to_string()))?;
+            if Self::env().block_timestamp() < claim_deadline {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "Claim deadline has not passed".to_string(),
+                ));
+            }
+            let unclaimed: Balance = self.to_be_collected;
+            if unclaimed == 0 {
+                return Err(AzAirdropError::AmountZero);
+            }
+
+            self.to_be_collected = 0;
+            self.unclaimed_swept = true;
+            let to: AccountId = self.clawback.unwrap_or(self.admin);
+            PSP22Ref::transfer_builder(&self.token, to, unclaimed, vec![])
+                .call_flags(CallFlags::default())
+                .invoke()?;
+            self.notify_observer(
+                ink::selector_bytes!("VestingObserver::on_sweep"),
+                None,
+                unclaimed,
+            );
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::UnclaimedSwept(UnclaimedSwept {
+                    to,
+                    amount: unclaimed,
+                    caller,
+                }),
+            );
+
+            Ok(unclaimed)
+        }
+
+        // Zeroes the remaining balances of the recipients at positions offset..offset + limit
+        // after sweep_unclaimed, so show matches what was swept. limit is capped at
+        // MAX_RECIPIENTS_LIST_LIMIT. Returns how many recipients were zeroed.
+        #[ink(message)]
+        pub fn sweep_unclaimed_range(&mut self, offset: u32, limit: u32) -> Result<u32> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::TREASURER)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+            if !self.unclaimed_swept {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "Unclaimed tokens have not been swept".to_string(),
+                ));
+            }
+
+            let end: u32 = offset
+                .saturating_add(limit.min(MAX_RECIPIENTS_LIST_LIMIT))
+                .min(self.recipients_count);
+            let mut zeroed: u32 = 0;
+            for index in offset..end {
+                if let Some(address) = self.recipient_addresses.get(index) {
+                    if let Some(mut recipient) = self.recipients.get(address) {
+                        if recipient.total_amount > recipient.collected {
+                            recipient.total_amount = recipient.collected;
+                            self.insert_recipient(address, &recipient);
+                            zeroed += 1;
+                        }
+                    }
+                }
+            }
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::UnclaimedZeroed(UnclaimedZeroed {
+                    offset,
+                    end,
+                    zeroed,
+                    caller,
+                }),
+            );
+
+            Ok(zeroed)
+        }
+
+        // Removes the contract to reclaim its deposit once there's nothing left to collect.
+        // Any remaining balance is sent to admin first.
+        #[ink(message)]
+        pub fn terminate(&mut self) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            if self.to_be_collected > 0 {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "Recipients have tokens to collect".to_string(),
+                ));
+            }
+            if self.total_reserved > 0
+                || self.unfinalized_escrow() > 0
+                || self.merkle_committed() > 0
+            {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "Tokens are reserved or escrowed".to_string(),
+                ));
+            }
+
+            let balance: Balance = PSP22Ref::balance_of(&self.token, Self::env().account_id());
+            if balance > 0 {
+                PSP22Ref::transfer_builder(&self.token, self.admin, balance, vec![])
+                    .call_flags(CallFlags::default())
+                    .invoke()?;
+
+                // emit event
+                Self::emit_event(
+                    self.env(),
+                    Event::SpareReturned(SpareReturned {
+                        to: self.admin,
+                        amount: balance,
+                        caller,
+                    }),
+                );
+            }
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::Terminate(Terminate {
+                    caller,
+                    beneficiary: self.admin,
+                }),
+            );
+
+            self.env().terminate_contract(self.admin)
+        }
+
+        #[ink(message)]
+        pub fn unregister_payout_address(&mut self, address: AccountId) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            if !self.payout_addresses.contains((caller, address)) {
+                return Err(AzAirdropError::NotFound("Payout address".to_string()));
+            }
+
+            self.payout_addresses.remove((caller, address));
+
+            Ok(())
+        }
+
+        // Still available while admin ops are disabled so they can be turned back on
+        #[ink(message)]
+        pub fn update_admin_ops_enabled(&mut self, admin_ops_enabled: bool) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::PAUSER)?;
+            self.record_admin_action();
+
+            self.admin_ops_enabled = admin_ops_enabled;
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::ModeUpdate(ModeUpdate {
+                    caller,
+                    admin_ops_enabled,
+                    claims_enabled: self.claims_enabled,
+                }),
+            );
+
+            Ok(())
+        }
+
+        // Stops new allocations while leaving claims and schedule edits alone
+        #[ink(message)]
+        pub fn update_allocations_frozen(&mut self, allocations_frozen: bool) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+
+            self.allocations_frozen = allocations_frozen;
+
+            // emit event
+            self.emit_config_update(caller);
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn update_allow_additions_after_start(
+            &mut self,
+            allow_additions_after_start: bool,
+        ) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+
+            self.allow_additions_after_start = allow_additions_after_start;
+
+            // emit event
+            self.emit_config_update(caller);
+
+            Ok(())
+        }
+
+        // Locked once it has passed so swept balances can't become collectable again
+        #[ink(message)]
+        pub fn update_claim_deadline(&mut self, claim_deadline: Option<Timestamp>) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+            self.claim_deadline_not_passed()?;
+            if let Some(claim_deadline_unwrapped) = claim_deadline {
+                let earliest: Timestamp = Self::env()
+                    .block_timestamp()
+                    .max(self.start)
+                    .max(self.claims_enabled_at.unwrap_or(0));
+                if claim_deadline_unwrapped <= earliest {
+                    return Err(AzAirdropError::UnprocessableEntity(
+                        "Claim deadline must be after now, start and claims_enabled_at".to_string(),
+                    ));
+                }
+            }
+
+            self.claim_deadline = claim_deadline;
+
+            // emit event
+            self.emit_config_update(caller);
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn update_claimed_days_retention(&mut self, claimed_days_retention: u32) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+            if claimed_days_retention == 0 {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "Claimed days retention must be greater than 0".to_string(),
+                ));
+            }
+
+            self.claimed_days_retention = claimed_days_retention;
+
+            // emit event
+            self.emit_config_update(caller);
+
+            Ok(())
+        }
+
+        // Emergency brake on collecting, available while admin ops are disabled
+        #[ink(message)]
+        pub fn update_claims_enabled(&mut self, claims_enabled: bool) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::PAUSER)?;
+            self.record_admin_action();
+
+            self.claims_enabled = claims_enabled;
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::ModeUpdate(ModeUpdate {
+                    caller,
+                    admin_ops_enabled: self.admin_ops_enabled,
+                    claims_enabled,
+                }),
+            );
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn update_claims_enabled_at(
+            &mut self,
+            claims_enabled_at: Option<Timestamp>,
+        ) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+            Self::validate_claims_enabled_at(self.start, claims_enabled_at)?;
+
+            self.claims_enabled_at = claims_enabled_at;
+
+            // emit event
+            self.emit_config_update(caller);
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn update_clawback(&mut self, clawback: Option<AccountId>) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+
+            self.clawback = clawback;
+
+            // emit event
+            self.emit_config_update(caller);
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn update_compliance_mode(&mut self, compliance_mode: bool) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+
+            self.compliance_mode = compliance_mode;
+
+            // emit event
+            self.emit_config_update(caller);
+
+            Ok(())
+        }
+
+        #[allow(clippy::too_many_arguments)]
+        #[ink(message)]
+        pub fn update_config(
+            &mut self,
+            admin: Option<AccountId>,
+            start: Option<Timestamp>,
+            default_collectable_at_tge_percentage: Option<u8>,
+            default_cliff_duration: Option<Timestamp>,
+            default_vesting_duration: Option<Timestamp>,
+            max_sub_admins: Option<u32>,
+            max_organization_members: Option<u32>,
+        ) -> Result<Config> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+
+            if let Some(admin_unwrapped) = admin {
+                self.admin = admin_unwrapped
+            }
+            if let Some(start_unwrapped) = start {
+                let block_timestamp: Timestamp = Self::env().block_timestamp();
+                if start_unwrapped > block_timestamp {
+                    if self.to_be_collected == 0 {
+                        self.start = start_unwrapped
+                    } else {
+                        return Err(AzAirdropError::UnprocessableEntity(
+                            "to_be_collected must be zero when changing start time".to_string(),
+                        ));
+                    }
+                } else {
+                    return Err(AzAirdropError::UnprocessableEntity(
+                        "New start time must be in the future".to_string(),
+                    ));
+                }
+            }
+            Self::validate_claims_enabled_at(self.start, self.claims_enabled_at)?;
+            if let Some(claim_deadline) = self.claim_deadline {
+                if claim_deadline <= self.start {
+                    return Err(AzAirdropError::UnprocessableEntity(
+                        "Claim deadline must be after start".to_string(),
+                    ));
+                }
+            }
+            if let Some(default_collectable_at_tge_percentage_unwrapped) =
+                default_collectable_at_tge_percentage
+            {
+                self.default_collectable_at_tge_percentage =
+                    default_collectable_at_tge_percentage_unwrapped
+            }
+            if let Some(default_cliff_duration_unwrapped) = default_cliff_duration {
+                self.default_cliff_duration = default_cliff_duration_unwrapped
+            }
+            if let Some(default_vesting_duration_unwrapped) = default_vesting_duration {
+                self.default_vesting_duration = default_vesting_duration_unwrapped
+            }
+            if let Some(max_sub_admins_unwrapped) = max_sub_admins {
+                self.max_sub_admins = max_sub_admins_unwrapped
+            }
+            if let Some(max_organization_members_unwrapped) = max_organization_members {
+                self.max_organization_members = max_organization_members_unwrapped
+            }
+            Self::validate_airdrop_calculation_variables(
+                self.start,
+                self.default_collectable_at_tge_percentage,
+                0,
+                self.default_cliff_duration,
+                self.default_vesting_duration,
+            )?;
+
+            let config: Config = self.config();
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::ConfigUpdate(ConfigUpdate {
+                    caller,
+                    config: Some(config.clone()).filter(|_| self.full_events()),
+                }),
+            );
+
+            Ok(config)
+        }
+
+        // Commit before a push distribution so the order can be checked against it
+        #[ink(message)]
+        pub fn update_distribution_seed(
+            &mut self,
+            distribution_seed: Option<[u8; 32]>,
+        ) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+
+            self.distribution_seed = distribution_seed;
+
+            // emit event
+            self.emit_config_update(caller);
+
+            Ok(())
+        }
+
+        // Can't be changed once anyone has deposited, so funders know what they signed up for
+        #[ink(message)]
+        pub fn update_escrow_deadline(&mut self, escrow_deadline: Option<Timestamp>) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+            if self.escrow_finalized {
+                return Err(AzAirdropError::EscrowFinalized);
+            }
+            if self.total_escrowed > 0 {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "Escrow has deposits".to_string(),
+                ));
+            }
+
+            self.escrow_deadline = escrow_deadline;
+
+            // emit event
+            self.emit_config_update(caller);
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn update_event_verbosity(&mut self, event_verbosity: EventVerbosity) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+
+            self.event_verbosity = event_verbosity;
+
+            // emit event
+            self.emit_config_update(caller);
+
+            Ok(())
+        }
+
+        // funder must give this contract a standing allowance
+        #[ink(message)]
+        pub fn update_funder(&mut self, funder: Option<AccountId>) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+
+            self.funder = funder;
+
+            // emit event
+            self.emit_config_update(caller);
+
+            Ok(())
+        }
+
+        // For recipients that want large amounts paid out in chunks e.g. exchanges
+        #[ink(message)]
+        pub fn update_max_per_claim(&mut self, max_per_claim: Option<Balance>) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.show(caller)?;
+            if let Some(max_per_claim_unwrapped) = max_per_claim {
+                if max_per_claim_unwrapped == 0 {
+                    return Err(AzAirdropError::UnprocessableEntity(
+                        "Max per claim must be greater than 0".to_string(),
+                    ));
+                }
+                self.max_per_claims.insert(caller, &max_per_claim_unwrapped);
+            } else {
+                self.max_per_claims.remove(caller);
+            }
+
+            Ok(())
+        }
+
+        // Keeps sweeps and distributions practical. Can't be set below the current recipients count.
+        // Applies after MAX_RECIPIENTS_UPDATE_DELAY and replaces any change still pending.
+        #[ink(message)]
+        pub fn update_max_recipients(&mut self, max_recipients: Option<u32>) -> Result<Timestamp> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+            if let Some(max_recipients_unwrapped) = max_recipients {
+                if max_recipients_unwrapped < self.recipients_count {
+                    return Err(AzAirdropError::UnprocessableEntity(
+                        "Max recipients must be greater than or equal to recipients count"
+                            .to_string(),
+                    ));
+                }
+            }
+
+            self.max_recipients = self.effective_max_recipients();
+            let applies_from: Timestamp = Self::env()
+                .block_timestamp()
+                .saturating_add(MAX_RECIPIENTS_UPDATE_DELAY);
+            self.pending_max_recipients = Some((max_recipients, applies_from));
+
+            // emit event
+            self.emit_config_update(caller);
+
+            Ok(applies_from)
+        }
+
+        #[ink(message)]
+        pub fn update_native_reserve(&mut self, native_reserve: Balance) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+
+            self.native_reserve = native_reserve;
+
+            // emit event
+            self.emit_config_update(caller);
+
+            Ok(())
+        }
+
+        #[allow(clippy::too_many_arguments)]
+        #[ink(message)]
+        pub fn update_recipient(
+            &mut self,
+            address: AccountId,
+            collectable_at_tge_percentage: Option<u8>,
+            cliff_duration: Option<Timestamp>,
+            vesting_duration: Option<Timestamp>,
+            curve: Option<Curve>,
+            start_override: Option<Timestamp>,
+            collectable_at_cliff_percentage: Option<u8>,
+        ) -> Result<Recipient> {
+            self.authorise_to_update_recipient(Some(SubAdminPermission::UpdateSchedule))?;
+            self.airdrop_has_not_started()?;
+            self.schedule_not_frozen()?;
+            let patch: RecipientPatch = RecipientPatch {
+                collectable_at_tge_percentage,
+                cliff_duration,
+                vesting_duration,
+                curve,
+                start_override,
+                collectable_at_cliff_percentage,
+            };
+            if let Some(ref curve_unwrapped) = patch.curve {
+                schedule::validate_curve(curve_unwrapped)?;
+            }
+
+            self.patch_recipient(address, &patch)
+        }
+
+        // Applies the same patch to each address, an invalid entry doesn't stop the rest
+        #[ink(message)]
+        pub fn update_recipient_batch(
+            &mut self,
+            addresses: Vec<AccountId>,
+            patch: RecipientPatch,
+        ) -> Result<Vec<Result<Recipient>>> {
+            self.authorise_to_update_recipient(Some(SubAdminPermission::UpdateSchedule))?;
+            self.airdrop_has_not_started()?;
+            self.schedule_not_frozen()?;
+            if let Some(ref curve_unwrapped) = patch.curve {
+                schedule::validate_curve(curve_unwrapped)?;
+            }
+
+            Ok(addresses
+                .into_iter()
+                .map(|address| self.patch_recipient(address, &patch))
+                .collect())
+        }
+
+        // Follows the allocation when it is reassigned
+        #[ink(message)]
+        pub fn update_recipient_external_ref(
+            &mut self,
+            address: AccountId,
+            external_ref: Option<[u8; 16]>,
+        ) -> Result<Recipient> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+            let mut recipient: Recipient = self.show(address)?;
+            if let Some(external_ref) = external_ref {
+                if let Some(existing_address) = self.external_refs.get(external_ref) {
+                    if existing_address != address {
+                        return Err(AzAirdropError::UnprocessableEntity(
+                            "External ref is already in use".to_string(),
+                        ));
+                    }
+                }
+            }
+
+            let old_recipient: Recipient = recipient.clone();
+            recipient.external_ref = external_ref;
+            self.insert_recipient(address, &recipient);
+
+            // emit event
+            self.emit_recipient_update(address, &old_recipient, &recipient);
+
+            Ok(recipient)
+        }
+
+        #[ink(message)]
+        pub fn update_recipient_release_interval(
+            &mut self,
+            address: AccountId,
+            release_interval: Timestamp,
+        ) -> Result<Recipient> {
+            self.authorise_to_update_recipient(Some(SubAdminPermission::UpdateSchedule))?;
+            self.airdrop_has_not_started()?;
+            self.schedule_not_frozen()?;
+            let mut recipient: Recipient = self.show(address)?;
+            if recipient.schedule_phases.is_some() {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "release_interval doesn't apply to schedule phases".to_string(),
+                ));
+            }
+            Self::validate_release_interval(release_interval, recipient.vesting_duration)?;
+
+            let old_recipient: Recipient = recipient.clone();
+            recipient.release_interval = release_interval;
+            self.insert_recipient(address, &recipient);
+
+            // emit event
+            self.emit_recipient_update(address, &old_recipient, &recipient);
+
+            Ok(recipient)
+        }
+
+        // Has to be decided before start so recipients know what they are getting
+        #[ink(message)]
+        pub fn update_recipient_revocable(
+            &mut self,
+            address: AccountId,
+            revocable: bool,
+        ) -> Result<Recipient> {
+            self.authorise_to_update_recipient(Some(SubAdminPermission::UpdateSchedule))?;
+            self.airdrop_has_not_started()?;
+            self.schedule_not_frozen()?;
+            let mut recipient: Recipient = self.show(address)?;
+
+            let old_recipient: Recipient = recipient.clone();
+            recipient.revocable = revocable;
+            self.insert_recipient(address, &recipient);
+
+            // emit event
+            self.emit_recipient_update(address, &old_recipient, &recipient);
+
+            Ok(recipient)
+        }
+
+        #[ink(message)]
+        pub fn update_recipient_schedule_phases(
+            &mut self,
+            address: AccountId,
+            schedule_phases: Option<Vec<SchedulePhase>>,
+        ) -> Result<Recipient> {
+            self.authorise_to_update_recipient(Some(SubAdminPermission::UpdateSchedule))?;
+            self.airdrop_has_not_started()?;
+            self.schedule_not_frozen()?;
+            let mut recipient: Recipient = self.show(address)?;
+            if let Some(ref schedule_phases_unwrapped) = schedule_phases {
+                schedule::validate(self.recipient_start(&recipient), schedule_phases_unwrapped)?;
+            }
+
+            let old_recipient: Recipient = recipient.clone();
+            recipient.schedule_phases = schedule_phases;
+            self.insert_recipient(address, &recipient);
+
+            // emit event
+            self.emit_recipient_update(address, &old_recipient, &recipient);
+
+            Ok(recipient)
+        }
+
+        // Shorthand for schedule phases that only have instant unlocks
+        #[ink(message)]
+        pub fn update_recipient_tranches(
+            &mut self,
+            address: AccountId,
+            tranches: Vec<(Timestamp, u8)>,
+        ) -> Result<Recipient> {
+            self.update_recipient_schedule_phases(
+                address,
+                Some(schedule::phases_from_tranches(&tranches)),
+            )
+        }
+
+        // Only sub-admin rights are granted through the registry, admin stays local
+        #[ink(message)]
+        pub fn update_role_registry(&mut self, role_registry: Option<AccountId>) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+
+            self.role_registry = role_registry;
+
+            // emit event
+            self.emit_config_update(caller);
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn update_schedule_freeze_window(
+            &mut self,
+            schedule_freeze_window: Timestamp,
+        ) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+            // Stops the window being shrunk to get out of it
+            self.schedule_not_frozen()?;
+
+            self.schedule_freeze_window = schedule_freeze_window;
+
+            // emit event
+            self.emit_config_update(caller);
+
+            Ok(())
+        }
+
+        // Recipients created through source without a template reference this template instead
+        // of getting the contract defaults. For MerkleClaim it replaces the leaf's schedule.
+        #[ink(message)]
+        pub fn update_source_template(
+            &mut self,
+            source: AllocationSource,
+            template_id: Option<u16>,
+        ) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+
+            if let Some(template_id_unwrapped) = template_id {
+                self.schedule_template(template_id_unwrapped)?;
+                self.source_templates.insert(source, &template_id_unwrapped);
+            } else {
+                self.source_templates.remove(source);
+            }
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::SourceTemplateUpdate(SourceTemplateUpdate {
+                    source,
+                    template_id,
+                    caller,
+                }),
+            );
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn update_whitelisted_callers_only(
+            &mut self,
+            whitelisted_callers_only: bool,
+        ) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+
+            self.whitelisted_callers_only = whitelisted_callers_only;
+
+            // emit event
+            self.emit_config_update(caller);
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn whitelisted_callers_add(&mut self, address: AccountId) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+            if self.whitelisted_callers.contains(address) {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "Already whitelisted".to_string(),
+                ));
+            }
+
+            self.whitelisted_callers.insert(address, &());
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::WhitelistedCallerAdd(WhitelistedCallerAdd { address, caller }),
+            );
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn whitelisted_callers_remove(&mut self, address: AccountId) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::DEFAULT_ADMIN)?;
+            self.admin_ops_not_paused()?;
+            self.record_admin_action();
+            if !self.whitelisted_callers.contains(address) {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "Not whitelisted".to_string(),
+                ));
+            }
+
+            self.whitelisted_callers.remove(address);
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::WhitelistedCallerRemove(WhitelistedCallerRemove { address, caller }),
+            );
+
+            Ok(())
+        }
+
+        // Permissionless refund when admin didn't finalize by the escrow deadline
+        #[ink(message)]
+        pub fn withdraw_escrow(&mut self) -> Result<Balance> {
+            let caller: AccountId = Self::env().caller();
+            let escrow_deadline: Timestamp = self
+                .escrow_deadline
+                .ok_or(AzAirdropError::NotFound("Escrow deadline".to_string()))?;
+            if self.escrow_finalized || Self::env().block_timestamp() < escrow_deadline {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "Escrow can't be withdrawn".to_string(),
+                ));
+            }
+            let amount: Balance = self.escrowed_by(caller);
+            if amount == 0 {
+                return Err(AzAirdropError::AmountZero);
+            }
+
+            self.escrow_deposits.remove(caller);
+            // These can't underflow as amount is part of both
+            self.total_escrowed -= amount;
+            self.funded
+                .insert(caller, &self.funded_by(caller).saturating_sub(amount));
+            PSP22Ref::transfer_builder(&self.token, caller, amount, vec![])
+                .call_flags(CallFlags::default())
+                .invoke()?;
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::EscrowWithdraw(EscrowWithdraw { to: caller, amount }),
+            );
+
+            Ok(amount)
+        }
+
+        // === PRIVATE ===
+        // None before start, otherwise the block timestamp that the new recipient vests from
+        fn addition_start(&self, address: AccountId) -> Result<Option<Timestamp>> {
+            if !self.allow_additions_after_start {
+                self.airdrop_has_not_started()?;
+            }
+            let block_timestamp: Timestamp = Self::env().block_timestamp();
+            if block_timestamp < self.start {
+                return Ok(None);
+            }
+            // Topping up would change how much of the existing allocation has vested
+            if self.recipients.contains(address) {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "Only new recipients can be added after start".to_string(),
+                ));
+            }
+
+            Ok(Some(block_timestamp))
+        }
+
+        fn admin_ops_not_paused(&self) -> Result<()> {
+            if !self.admin_ops_enabled {
+                return Err(AzAirdropError::Paused);
+            }
+
+            Ok(())
+        }
+
+        fn airdrop_has_not_started(&self) -> Result<()> {
+            let block_timestamp: Timestamp = Self::env().block_timestamp();
+            if block_timestamp >= self.start {
+                return Err(AzAirdropError::AirdropAlreadyStarted);
+            }
+
+            Ok(())
+        }
+
+        // Adds amount to address's allocation, creating the recipient if needed.
+        // to_be_collected must be updated by the caller.
+        fn allocate(
+            &mut self,
+            address: AccountId,
+            amount: Balance,
+            caller: AccountId,
+            label_id: Option<u16>,
+            description: Option<String>,
+            source: AllocationSource,
+        ) -> Result<Recipient> {
+            let mut recipient: Recipient = match self.recipients.get(address) {
+                Some(recipient) => recipient,
+                None => {
+                    if let Some(max_recipients) = self.effective_max_recipients() {
+                        if self.recipients_count >= max_recipients {
+                            return Err(AzAirdropError::RecipientLimitReached(max_recipients));
+                        }
+                    }
+                    let allocation_id: u64 = self.allocations_count;
+                    self.allocations_count = self.allocations_count.checked_add(1).ok_or(
+                        AzAirdropError::UnprocessableEntity("Allocation limit reached".to_string()),
+                    )?;
+                    let (collectable_at_tge_percentage, cliff_duration, vesting_duration) =
+                        self.default_schedule(source);
+                    let description_hash: Option<Hash> = description.as_ref().map(|description| {
+                        let mut output: [u8; 32] = [0; 32];
+                        ink::env::hash_bytes::<Blake2x256>(description.as_bytes(), &mut output);
+                        Hash::from(output)
+                    });
+                    if label_id.is_some() || description_hash.is_some() {
+                        self.allocation_origins.insert(
+                            address,
+                            &AllocationOrigin {
+                                label_id,
+                                description_hash,
+                            },
+                        );
+                    }
+                    Recipient {
+                        allocation_id,
+                        total_amount: 0,
+                        collected: 0,
+                        collectable_at_tge_percentage,
+                        cliff_duration,
+                        vesting_duration,
+                        schedule_phases: None,
+                        curve: Curve::Linear,
+                        revocable: false,
+                        external_ref: None,
+                        release_interval: 0,
+                        start_override: None,
+                        collectable_at_cliff_percentage: 0,
+                        source,
+                        template_id: self.source_template_id(source),
+                    }
+                }
+            };
+            // This can't overflow as it's bounded by to_be_collected
+            recipient.total_amount += amount;
+            self.insert_recipient(address, &recipient);
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::RecipientAdd(RecipientAdd {
+                    address,
+                    allocation_id: recipient.allocation_id,
+                    external_ref: recipient.external_ref,
+                    amount,
+                    caller,
+                    description: description.filter(|_| self.full_events()),
+                    label_id: label_id.filter(|_| self.full_events()),
+                    source,
+                }),
+            );
+
+            Ok(recipient)
+        }
+
+        fn allocation_source(&self, caller: AccountId) -> AllocationSource {
+            if caller == self.admin {
+                AllocationSource::AdminGrant
+            } else if self.whitelisted_callers.contains(caller) {
+                AllocationSource::SalePurchase
+            } else {
+                AllocationSource::SubAdminGrant
+            }
+        }
+
+        fn allocations_not_frozen(&self) -> Result<()> {
+            if self.allocations_frozen {
+                return Err(AzAirdropError::AllocationsFrozen);
+            }
+
+            Ok(())
+        }
+
+        fn apply_pending_merkle_root(&mut self) {
+            if let Some(merkle_root) = self.due_merkle_root() {
+                self.merkle_root = Some(merkle_root);
+                self.merkle_root_version = self.merkle_root_version.saturating_add(1);
+                self.pending_merkle_root = None;
+                self.merkle_unclaimed = self.pending_merkle_unclaimed;
+                self.pending_merkle_unclaimed = 0;
+            }
+        }
+
+        fn authorise(caller: AccountId, expected: AccountId) -> Result<()> {
+            if caller != expected {
+                return Err(AzAirdropError::Unauthorised {
+                    caller,
+                    expected: Some(expected),
+                });
+            }
+
+            Ok(())
+        }
+
+        // DEFAULT_ADMIN reports the admin as expected, like authorise
+        fn authorise_role(&self, caller: AccountId, role: Role) -> Result<()> {
+            if role == role_registry::DEFAULT_ADMIN {
+                return Self::authorise(caller, self.admin);
+            }
+            if !self.has_role(role, caller) {
+                return Err(AzAirdropError::Unauthorised {
+                    caller,
+                    expected: None,
+                });
+            }
+
+            Ok(())
+        }
+
+        // Permissions only narrow local sub-admins, the admin and registry sub-admins can do everything.
+        // Leaving permission out only requires being a sub-admin e.g. for distributing.
+        fn authorise_to_update_recipient(
+            &self,
+            permission: Option<SubAdminPermission>,
+        ) -> Result<()> {
+            let caller: AccountId = Self::env().caller();
+            self.authorise_role(caller, role_registry::RECIPIENT_MANAGER)?;
+            if let (Some(permission), Some(permissions)) =
+                (permission, self.sub_admins_mapping.get(caller))
+            {
+                if caller != self.admin
+                    && !permissions.allows(permission)
+                    && !self.has_registry_role(role_registry::RECIPIENT_MANAGER, caller)
+                {
+                    return Err(AzAirdropError::Unauthorised {
+                        caller,
+                        expected: None,
+                    });
+                }
+            }
+
+            self.admin_ops_not_paused()
+        }
+
+        fn claim_deadline_not_passed(&self) -> Result<()> {
+            if let Some(claim_deadline) = self.claim_deadline {
+                if Self::env().block_timestamp() >= claim_deadline {
+                    return Err(AzAirdropError::ClaimDeadlinePassed);
+                }
+            }
+
+            Ok(())
+        }
+
+        // Vesting still accrues from start, this only gates when it can be collected
+        fn claims_enabled_at_reached(&self) -> Result<()> {
+            if let Some(claims_enabled_at) = self.claims_enabled_at {
+                if Self::env().block_timestamp() < claims_enabled_at {
+                    return Err(AzAirdropError::UnprocessableEntity(
+                        "Claims are not enabled yet".to_string(),
+                    ));
+                }
+            }
+
+            Ok(())
+        }
+
+        fn collect_for_address(
+            &mut self,
+            address: AccountId,
+            destination: AccountId,
+            memo: Option<Vec<u8>>,
+        ) -> Result<Balance> {
+            if !self.claims_enabled {
+                return Err(AzAirdropError::Paused);
+            }
+            let mut recipient = self.show(address)?;
+            self.claims_enabled_at_reached()?;
+            self.claim_deadline_not_passed()?;
+
+            let block_timestamp: Timestamp = Self::env().block_timestamp();
+            let collectable_amount: Balance = self.collectable_now(address)?;
+            if collectable_amount == 0 {
+                return Err(AzAirdropError::AmountZero);
+            }
+
+            // transfer to destination
+            let memo_hash: Option<Hash> = memo.as_ref().map(|memo| {
+                let mut output: [u8; 32] = [0; 32];
+                ink::env::hash_bytes::<Blake2x256>(memo, &mut output);
+                Hash::from(output)
+            });
+            PSP22Ref::transfer_builder(
+                &self.token,
+                destination,
+                collectable_amount,
+                memo.unwrap_or_default(),
+            )
+            .call_flags(CallFlags::default())
+            .invoke()?;
+            // increase recipient's collected
+            // These can't overflow, but might as well
+            recipient.collected = recipient.collected.saturating_add(collectable_amount);
+            self.insert_recipient(address, &recipient);
+            self.to_be_collected = self.to_be_collected.saturating_sub(collectable_amount);
+            // record claim
+            let claims_count: u32 = self.claims_count.get(address).unwrap_or(0);
+            self.claims.insert(
+                (address, claims_count),
+                &Claim {
+                    amount: collectable_amount,
+                    timestamp: block_timestamp,
+                },
+            );
+            self.claims_count
+                .insert(address, &claims_count.saturating_add(1));
+            self.record_claimed_on_day(block_timestamp, collectable_amount);
+            // recipient has shown it can claim
+            self.unreachable_marks.remove(address);
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::Collect(Collect {
+                    address,
+                    allocation_id: recipient.allocation_id,
+                    amount: collectable_amount,
+                    caller: Self::env().caller(),
+                    destination,
+                    external_ref: recipient.external_ref,
+                    memo_hash,
+                }),
+            );
+
+            Ok(collectable_amount)
+        }
+
+        // Draws amount from caller's budget if it has one,
+        // otherwise makes sure the balance covers new_to_be_collected and reserved budgets
+        fn cover_allocation(
+            &mut self,
+            caller: AccountId,
+            amount: Balance,
+            new_to_be_collected: Balance,
+        ) -> Result<()> {
+            if let Some(budget) = self.budgets.get(caller) {
+                // Reserved budgets are already covered by the balance
+                if amount > budget {
+                    return Err(AzAirdropError::LimitExceeded {
+                        limit: budget,
+                        requested: amount,
+                    });
+                }
+                // These can't overflow because of the above check
+                self.set_budget(caller, budget - amount);
+                self.total_reserved -= amount;
+            } else {
+                // Check that balance has enough to cover, excluding reserved budgets, unfinalized escrow
+                // and unclaimed merkle leaves.
+                // This can't overflow as these are all covered by the balance, but might as well
+                let required_balance: Balance = new_to_be_collected
+                    .saturating_add(self.total_reserved)
+                    .saturating_add(self.unfinalized_escrow())
+                    .saturating_add(self.merkle_committed());
+                let smart_contract_balance: Balance =
+                    PSP22Ref::balance_of(&self.token, Self::env().account_id());
+                if required_balance > smart_contract_balance {
+                    // Pull the shortfall from the funder if there is one
+                    if let Some(funder) = self.funder {
+                        self.transfer_from_funder(
+                            funder,
+                            required_balance - smart_contract_balance,
+                        )?;
+                    } else {
+                        return Err(AzAirdropError::InsufficientContractBalance);
+                    }
+                }
+            }
+
+            Ok(())
+        }
+
+        // (collectable_at_tge_percentage, cliff_duration, vesting_duration) for new recipients
+        // created through source. Falls back to the contract defaults without a template.
+        fn default_schedule(&self, source: AllocationSource) -> (u8, Timestamp, Timestamp) {
+            match self
+                .source_template_id(source)
+                .and_then(|template_id| self.schedule_templates.get(template_id))
+            {
+                Some(template) => (
+                    template.collectable_at_tge_percentage,
+                    template.cliff_duration,
+                    template.vesting_duration,
+                ),
+                None => (
+                    self.default_collectable_at_tge_percentage,
+                    self.default_cliff_duration,
+                    self.default_vesting_duration,
+                ),
+            }
+        }
+
+        // The pending root once its delay has passed, it's only applied on the next claim_with_proof
+        fn due_merkle_root(&self) -> Option<MerkleHash> {
+            match self.pending_merkle_root {
+                Some((merkle_root, applies_from))
+                    if Self::env().block_timestamp() >= applies_from =>
+                {
+                    Some(merkle_root)
+                }
+                _ => None,
+            }
+        }
+
+        // The pending limit once its delay has passed, otherwise the current one
+        fn effective_max_recipients(&self) -> Option<u32> {
+            match self.pending_max_recipients {
+                Some((max_recipients, applies_from))
+                    if Self::env().block_timestamp() >= applies_from =>
+                {
+                    max_recipients
+                }
+                _ => self.max_recipients,
+            }
+        }
+
+        fn emit_config_update(&self, caller: AccountId) {
+            Self::emit_event(
+                self.env(),
+                Event::ConfigUpdate(ConfigUpdate {
+                    caller,
+                    config: Some(self.config()).filter(|_| self.full_events()),
+                }),
+            );
+        }
+
+        fn emit_event<EE: EmitEvent<Self>>(emitter: EE, event: Event) {
+            emitter.emit_event(event);
+        }
+
+        fn emit_recipient_update(
+            &self,
+            address: AccountId,
+            old_recipient: &Recipient,
+            recipient: &Recipient,
+        ) {
+            Self::emit_event(
+                self.env(),
+                Event::RecipientUpdate(RecipientUpdate {
+                    address,
+                    allocation_id: recipient.allocation_id,
+                    external_ref: recipient.external_ref,
+                    caller: Self::env().caller(),
+                    old_collectable_at_tge_percentage: old_recipient.collectable_at_tge_percentage,
+                    old_cliff_duration: old_recipient.cliff_duration,
+                    old_vesting_duration: old_recipient.vesting_duration,
+                    collectable_at_tge_percentage: recipient.collectable_at_tge_percentage,
+                    cliff_duration: recipient.cliff_duration,
+                    vesting_duration: recipient.vesting_duration,
+                    schedule_phases: recipient.schedule_phases.clone(),
+                    release_interval: recipient.release_interval,
+                    revocable: recipient.revocable,
+                }),
+            );
+        }
+
+        fn escrow_open(&self) -> Result<()> {
+            let escrow_deadline: Timestamp = self
+                .escrow_deadline
+                .ok_or(AzAirdropError::NotFound("Escrow deadline".to_string()))?;
+            if self.escrow_finalized {
+                return Err(AzAirdropError::EscrowFinalized);
+            }
+            if Self::env().block_timestamp() >= escrow_deadline {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "Escrow deadline has passed".to_string(),
+                ));
+            }
+
+            Ok(())
+        }
+
+        fn full_events(&self) -> bool {
+            self.event_verbosity == EventVerbosity::Full
+        }
+
+        fn fully_collected(recipient: &Recipient) -> bool {
+            recipient.total_amount > 0 && recipient.collected >= recipient.total_amount
+        }
+
+        fn grantable_role(role: Role) -> Result<()> {
+            if role != role_registry::PAUSER && role != role_registry::TREASURER {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "Role can't be granted".to_string(),
+                ));
+            }
+
+            Ok(())
+        }
+
+        // A registry that can't be reached or returns garbage grants nothing
+        fn has_registry_role(&self, role: Role, account: AccountId) -> bool {
+            let Some(role_registry) = self.role_registry else {
+                return false;
+            };
+
+            matches!(
+                build_call::<DefaultEnvironment>()
+                    .call(role_registry)
+                    .exec_input(
+                        ExecutionInput::new(Selector::new(ink::selector_bytes!(
+                            "HasRole::has_role"
+                        )))
+                        .push_arg(role)
+                        .push_arg(account),
+                    )
+                    .returns::<bool>()
+                    .call_flags(CallFlags::default())
+                    .try_invoke(),
+                Ok(Ok(true))
+            )
+        }
+
+        // All recipient writes go through here so ledger_digest stays in step
+        fn insert_recipient(&mut self, address: AccountId, recipient: &Recipient) {
+            let previous: Option<Recipient> = self.recipients.get(address);
+            if let Some(external_ref) = previous.as_ref().and_then(|previous| previous.external_ref)
+            {
+                self.external_refs.remove(external_ref);
+            }
+            if let Some(external_ref) = recipient.external_ref {
+                self.external_refs.insert(external_ref, &address);
+            }
+            let previous_template_id: Option<u16> =
+                previous.as_ref().and_then(|previous| previous.template_id);
+            if previous_template_id != recipient.template_id {
+                if let Some(template_id) = previous_template_id {
+                    self.untrack_template(template_id);
+                }
+                if let Some(template_id) = recipient.template_id {
+                    self.track_template(template_id);
+                }
+            }
+            if let Some(previous) = previous {
+                self.toggle_ledger_digest(address, &previous);
+                self.untrack_collected(&previous);
+            } else {
+                self.recipient_addresses
+                    .insert(self.recipients_count, &address);
+                self.recipient_indexes
+                    .insert(address, &self.recipients_count);
+                // This can't overflow as allocations_count would reach its limit first
+                self.recipients_count = self.recipients_count.saturating_add(1);
+            }
+            self.toggle_ledger_digest(address, recipient);
+            self.track_collected(recipient);
+            self.recipients.insert(address, recipient);
+        }
+
+        // Covers whichever root ends up being claimed against while a replacement is pending
+        fn merkle_committed(&self) -> Balance {
+            if self.due_merkle_root().is_some() {
+                self.pending_merkle_unclaimed
+            } else if self.pending_merkle_root.is_some() {
+                self.merkle_unclaimed.max(self.pending_merkle_unclaimed)
+            } else {
+                self.merkle_unclaimed
+            }
+        }
+
+        // Moves the allocation and the preferences that follow it, the claim history stays with address
+        fn move_recipient(
+            &mut self,
+            address: AccountId,
+            new_address: AccountId,
+        ) -> Result<Recipient> {
+            let recipient: Recipient = self.show(address)?;
+            let notification_pref: Option<u8> = self.notification_prefs.get(address);
+            let max_per_claim: Option<Balance> = self.max_per_claims.get(address);
+            let allocation_origin: Option<AllocationOrigin> = self.allocation_origins.get(address);
+            self.remove_recipient(address);
+            self.max_per_claims.remove(address);
+            self.unreachable_marks.remove(address);
+            self.lost_key_proposals.remove(address);
+            self.address_change_proposals.remove(address);
+            self.insert_recipient(new_address, &recipient);
+            if let Some(notification_pref) = notification_pref {
+                self.notification_prefs
+                    .insert(new_address, &notification_pref);
+            }
+            if let Some(max_per_claim) = max_per_claim {
+                self.max_per_claims.insert(new_address, &max_per_claim);
+            }
+            if let Some(allocation_origin) = allocation_origin {
+                self.allocation_origins
+                    .insert(new_address, &allocation_origin);
+            }
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::AddressChange(AddressChange {
+                    old_address: address,
+                    new_address,
+                    allocation_id: recipient.allocation_id,
+                    external_ref: recipient.external_ref,
+                    caller: Self::env().caller(),
+                }),
+            );
+
+            Ok(recipient)
+        }
+
+        // Free text matching a label is converted to that label's id
+        fn normalize_description(
+            &self,
+            description: Option<Description>,
+        ) -> Result<(Option<u16>, Option<String>)> {
+            match description {
+                Some(Description::Label(label_id)) => {
+                    self.label(label_id)?;
+                    Ok((Some(label_id), None))
+                }
+                Some(Description::Text(text)) => {
+                    if let Some(label_id) = self.label_ids.get(&text) {
+                        Ok((Some(label_id), None))
+                    } else {
+                        Ok((None, Some(text)))
+                    }
+                }
+                None => Ok((None, None)),
+            }
+        }
+
+        // Best effort, a failing observer doesn't stop the action it's notified of
+        fn notify_observer(&self, selector: [u8; 4], address: Option<AccountId>, amount: Balance) {
+            let Some(observer) = self.observer else {
+                return;
+            };
+
+            let input = ExecutionInput::new(Selector::new(selector));
+            let call = build_call::<DefaultEnvironment>()
+                .call(observer)
+                .gas_limit(OBSERVER_GAS_LIMIT)
+                .call_flags(CallFlags::default());
+            let _ = match address {
+                Some(address) => call
+                    .exec_input(input.push_arg(address).push_arg(amount))
+                    .returns::<()>()
+                    .try_invoke(),
+                None => call
+                    .exec_input(input.push_arg(amount))
+                    .returns::<()>()
+                    .try_invoke(),
+            };
+        }
+
+        // Curve must already be validated
+        fn patch_recipient(
+            &mut self,
+            address: AccountId,
+            patch: &RecipientPatch,
+        ) -> Result<Recipient> {
+            let mut recipient: Recipient = self.show(address)?;
+            let old_recipient: Recipient = recipient.clone();
+            // The recipient keeps the template's values as its own from here on
+            if patch.collectable_at_tge_percentage.is_some()
+                || patch.cliff_duration.is_some()
+                || patch.vesting_duration.is_some()
+            {
+                recipient.template_id = None;
+            }
+            if let Some(collectable_at_tge_percentage_unwrapped) =
+                patch.collectable_at_tge_percentage
+            {
+                recipient.collectable_at_tge_percentage = collectable_at_tge_percentage_unwrapped
+            }
+            if let Some(cliff_duration_unwrapped) = patch.cliff_duration {
+                recipient.cliff_duration = cliff_duration_unwrapped
+            }
+            if let Some(vesting_duration_unwrapped) = patch.vesting_duration {
+                recipient.vesting_duration = vesting_duration_unwrapped
+            }
+            if let Some(ref curve_unwrapped) = patch.curve {
+                recipient.curve = curve_unwrapped.clone()
+            }
+            if let Some(collectable_at_cliff_percentage_unwrapped) =
+                patch.collectable_at_cliff_percentage
+            {
+                recipient.collectable_at_cliff_percentage =
+                    collectable_at_cliff_percentage_unwrapped
+            }
+            if let Some(start_override_unwrapped) = patch.start_override {
+                self.validate_start_override(start_override_unwrapped)?;
+                recipient.start_override = Some(start_override_unwrapped)
+            }
+            if let Some(ref schedule_phases) = recipient.schedule_phases {
+                schedule::validate(self.recipient_start(&recipient), schedule_phases)?;
+            }
+            Self::validate_airdrop_calculation_variables(
+                self.recipient_start(&recipient),
+                recipient.collectable_at_tge_percentage,
+                recipient.collectable_at_cliff_percentage,
+                recipient.cliff_duration,
+                recipient.vesting_duration,
+            )?;
+            Self::validate_release_interval(
+                recipient.release_interval,
+                recipient.vesting_duration,
+            )?;
+
+            self.insert_recipient(address, &recipient);
+
+            // emit event
+            self.emit_recipient_update(address, &old_recipient, &recipient);
+
+            Ok(recipient)
+        }
+
+        fn recipient_start(&self, recipient: &Recipient) -> Timestamp {
+            recipient.start_override.unwrap_or(self.start)
+        }
+
+        fn record_admin_action(&mut self) {
+            self.last_admin_action_at = Some(Self::env().block_timestamp());
+        }
+
+        // Adds amount to the bucket for timestamp's day and prunes buckets
+        // that fall outside the last claimed_days_retention days
+        fn record_claimed_on_day(&mut self, timestamp: Timestamp, amount: Balance) {
+            let day: u32 = u32::try_from(timestamp / DAY).unwrap_or(u32::MAX);
+            let claimed: Balance = self.claimed_by_day.get(day).unwrap_or(0);
+            self.claimed_by_day
+                .insert(day, &claimed.saturating_add(amount));
+
+            let cutoff: u32 = day.saturating_sub(self.claimed_days_retention.saturating_sub(1));
+            let mut oldest_claimed_day: u32 = self.oldest_claimed_day.unwrap_or(day);
+            let mut pruned: u32 = 0;
+            while oldest_claimed_day < cutoff && pruned < MAX_CLAIMED_DAYS_PRUNED_PER_CLAIM {
+                self.claimed_by_day.remove(oldest_claimed_day);
+                // This can't overflow as it's less than cutoff
+                oldest_claimed_day += 1;
+                pruned += 1;
+            }
+            self.oldest_claimed_day = Some(oldest_claimed_day);
+        }
+
+        fn remove_recipient(&mut self, address: AccountId) {
+            if let Some(previous) = self.recipients.get(address) {
+                self.toggle_ledger_digest(address, &previous);
+                self.untrack_collected(&previous);
+                if let Some(external_ref) = previous.external_ref {
+                    self.external_refs.remove(external_ref);
+                }
+                if let Some(template_id) = previous.template_id {
+                    self.untrack_template(template_id);
+                }
+                self.recipients.remove(address);
+                self.notification_prefs.remove(address);
+                self.allocation_origins.remove(address);
+            }
+            if let Some(index) = self.recipient_indexes.get(address) {
+                // This can't underflow as address is indexed
+                let last_index: u32 = self.recipients_count - 1;
+                if let Some(last_address) = self.recipient_addresses.get(last_index) {
+                    self.recipient_addresses.insert(index, &last_address);
+                    self.recipient_indexes.insert(last_address, &index);
+                }
+                self.recipient_addresses.remove(last_index);
+                self.recipient_indexes.remove(address);
+                self.recipients_count = last_index;
+            }
+        }
+
+        fn resolved_recipient(&self, address: AccountId) -> Option<Recipient> {
+            let mut recipient: Recipient = self.recipients.get(address)?;
+            if let Some(template) = recipient
+                .template_id
+                .and_then(|template_id| self.schedule_templates.get(template_id))
+            {
+                recipient.collectable_at_tge_percentage = template.collectable_at_tge_percentage;
+                recipient.cliff_duration = template.cliff_duration;
+                recipient.vesting_duration = template.vesting_duration;
+            }
+
+            Some(recipient)
+        }
+
+        fn retrofit_candidates(
+            &self,
+            template_id: u16,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<(AccountId, Recipient)> {
+            self.recipients_list(offset, limit)
+                .into_iter()
+                .filter(|(_address, recipient)| {
+                    recipient.schedule_phases.is_none()
+                        && recipient.template_id != Some(template_id)
+                })
+                .collect()
+        }
+
+        // Recipients get schedule_freeze_window before start where their schedules can't be worsened
+        fn schedule_not_frozen(&self) -> Result<()> {
+            let block_timestamp: Timestamp = Self::env().block_timestamp();
+            if block_timestamp >= self.start.saturating_sub(self.schedule_freeze_window) {
+                return Err(AzAirdropError::ScheduleFrozen);
+            }
+
+            Ok(())
+        }
+
+        fn set_budget(&mut self, caller: AccountId, amount: Balance) {
+            if amount == 0 {
+                self.budgets.remove(caller);
+            } else {
+                self.budgets.insert(caller, &amount);
+            }
+        }
+
+        // None if source has no template or its template was removed
+        fn source_template_id(&self, source: AllocationSource) -> Option<u16> {
+            self.source_templates
+                .get(source)
+                .filter(|template_id| self.schedule_templates.contains(template_id))
+        }
+
+        // XOR is its own inverse, so this both adds and removes an entry
+        fn toggle_ledger_digest(&mut self, address: AccountId, recipient: &Recipient) {
+            let mut entry: [u8; 32] = [0; 32];
+            ink::env::hash_encoded::<Blake2x256, _>(
+                &(address, recipient.total_amount, recipient.collected),
+                &mut entry,
+            );
+            for (digest_byte, entry_byte) in self.ledger_digest.iter_mut().zip(entry.iter()) {
+                *digest_byte ^= entry_byte;
+            }
+        }
+
+        fn track_collected(&mut self, recipient: &Recipient) {
+            // These can't overflow as collected is bounded by the total supply
+            self.total_collected = self.total_collected.saturating_add(recipient.collected);
+            if Self::fully_collected(recipient) {
+                self.fully_collected_count = self.fully_collected_count.saturating_add(1);
+            }
+        }
+
+        fn track_template(&mut self, template_id: u16) {
+            // This can't overflow as it's bounded by recipients_count
+            let count: u32 = self
+                .schedule_template_recipients
+                .get(template_id)
+                .unwrap_or(0)
+                .saturating_add(1);
+            self.schedule_template_recipients
+                .insert(template_id, &count);
+        }
+
+        fn transfer_from_funder(&mut self, from: AccountId, amount: Balance) -> Result<()> {
+            PSP22Ref::transfer_from_builder(
+                &self.token,
+                from,
+                self.env().account_id(),
+                amount,
+                vec![],
+            )
+            .call_flags(CallFlags::default())
+            .invoke()?;
+            // record in funding ledger
+            // This can't overflow as it's bounded by the token's total supply, but might as well
+            let funded: Balance = self.funded.get(from).unwrap_or(0);
+            self.funded.insert(from, &funded.saturating_add(amount));
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::Funded(Funded {
+                    from,
+                    amount,
+                    caller: Self::env().caller(),
+                    // Skips the extra cross contract call when minimal
+                    new_balance: if self.full_events() {
+                        Some(PSP22Ref::balance_of(&self.token, Self::env().account_id()))
+                    } else {
+                        None
+                    },
+                }),
+            );
+
+            Ok(())
+        }
+
+        // Held for refunds until finalized, so it can't cover allocations, budgets or spare
+        fn unfinalized_escrow(&self) -> Balance {
+            if self.escrow_finalized {
+                0
+            } else {
+                self.total_escrowed
+            }
+        }
+
+        fn untrack_collected(&mut self, recipient: &Recipient) {
+            // These can't underflow as recipient was tracked when it was inserted
+            self.total_collected = self.total_collected.saturating_sub(recipient.collected);
+            if Self::fully_collected(recipient) {
+                self.fully_collected_count = self.fully_collected_count.saturating_sub(1);
+            }
+        }
+
+        fn untrack_template(&mut self, template_id: u16) {
+            let count: u32 = self
+                .schedule_template_recipients
+                .get(template_id)
+                .unwrap_or(0);
+            // Drop the entry at 0 so its storage deposit is refunded
+            if count > 1 {
+                self.schedule_template_recipients
+                    .insert(template_id, &(count - 1));
+            } else {
+                self.schedule_template_recipients.remove(template_id);
+            }
+        }
+
+        fn validate_airdrop_calculation_variables(
+            start: Timestamp,
+            collectable_at_tge_percentage: u8,
+            collectable_at_cliff_percentage: u8,
+            cliff_duration: Timestamp,
+            vesting_duration: Timestamp,
+        ) -> Result<()> {
+            let collectable_percentage: u16 = u16::from(collectable_at_tge_percentage)
+                + u16::from(collectable_at_cliff_percentage);
+            if collectable_at_tge_percentage > 100 {
+                return Err(AzAirdropError::InvalidTgePercentage);
+            } else if collectable_percentage > 100 {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "collectable_at_tge_percentage and collectable_at_cliff_percentage must sum to 100 or less"
+                        .to_string(),
+                ));
+            } else if collectable_at_tge_percentage == 100 {
+                if cliff_duration > 0 || vesting_duration > 0 {
+                    return Err(AzAirdropError::UnprocessableEntity(
+                        "cliff_duration and vesting_duration must be 0 when collectable_tge_percentage is 100"
+                            .to_string(),
+                    ));
+                }
+            } else if collectable_percentage == 100 {
+                if vesting_duration > 0 {
+                    return Err(AzAirdropError::UnprocessableEntity(
+                        "vesting_duration must be 0 when tge and cliff percentages sum to 100"
+                            .to_string(),
+                    ));
+                }
+            } else if vesting_duration == 0 {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "vesting_duration must be greater than 0 when collectable_tge_percentage is not 100"
+                        .to_string(),
+                ));
+            }
+            // This can't over flow because all values are u64
+            let end_timestamp: u128 =
+                u128::from(start) + u128::from(cliff_duration) + u128::from(vesting_duration);
+            if end_timestamp > Timestamp::MAX.into() {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "Combination of start, cliff_duration and vesting_duration exceeds limit"
+                        .to_string(),
+                ));
+            }
+
+            Ok(())
+        }
+
+        fn validate_claims_enabled_at(
+            start: Timestamp,
+            claims_enabled_at: Option<Timestamp>,
+        ) -> Result<()> {
+            if let Some(claims_enabled_at_unwrapped) = claims_enabled_at {
+                if claims_enabled_at_unwrapped < start {
+                    return Err(AzAirdropError::UnprocessableEntity(
+                        "claims_enabled_at must be greater than or equal to start".to_string(),
+                    ));
+                }
+            }
+
+            Ok(())
+        }
+
+        // Longer intervals would leave nothing unlocked until the end of vesting
+        fn validate_release_interval(
+            release_interval: Timestamp,
+            vesting_duration: Timestamp,
+        ) -> Result<()> {
+            if release_interval > vesting_duration {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "release_interval must be less than or equal to vesting_duration".to_string(),
+                ));
+            }
+
+            Ok(())
+        }
+
+        // Collecting is gated on the airdrop's start, so vesting can't be anchored before it
+        fn validate_start_override(&self, start_override: Timestamp) -> Result<()> {
+            if start_override < self.start {
+                return Err(AzAirdropError::UnprocessableEntity(
+                    "start_override must be greater than or equal to start".to_string(),
+                ));
+            }
+
+            Ok(())
+        }
+
+        // 0 = start (collectable_at_tge), start_override if set
+        // 1 = vesting_start = start + cliff_duration
+        // 2 = vesting_end = vesting_start + vesting_duration
+        fn vested_amount(&self, recipient: &Recipient, timestamp: Timestamp) -> Balance {
+            let mut total_collectable_at_time: Balance = 0;
+            let start: Timestamp = self.recipient_start(recipient);
+            if timestamp >= start {
+                if let Some(schedule_phases) = &recipient.schedule_phases {
+                    return schedule::vested_amount(
+                        recipient.total_amount,
+                        schedule_phases,
+                        &recipient.curve,
+                        timestamp - start,
+                    );
+                }
+                // Everything is collectable at tge, skip the U256 maths.
+                // validate_airdrop_calculation_variables ensures there's no cliff or vesting.
+                if recipient.collectable_at_tge_percentage == 100 {
+                    return recipient.total_amount;
+                }
+                // collectable at tge
+                let collectable_at_tge: Balance =
+                    (U256::from(recipient.collectable_at_tge_percentage)
+                        * U256::from(recipient.total_amount)
+                        / U256::from(100))
+                    .as_u128();
+                total_collectable_at_time = collectable_at_tge;
+                // This can't overflow as checks are done in validate_airdrop_calculation_variables
+                let vesting_start: Timestamp = start + recipient.cliff_duration;
+                if timestamp >= vesting_start {
+                    // Nothing is left to vest, this avoids leaving rounding dust behind
+                    if recipient.vesting_duration == 0 {
+                        return recipient.total_amount;
+                    }
+                    // collectable at end of cliff
+                    let collectable_at_cliff: Balance =
+                        (U256::from(recipient.collectable_at_cliff_percentage)
+                            * U256::from(recipient.total_amount)
+                            / U256::from(100))
+                        .as_u128();
+                    // This can't overflow
+                    let mut vesting_time_reached: Timestamp = timestamp - vesting_start;
+                    // Round down to completed intervals, the last partial one unlocks at the end
+                    if recipient.release_interval > 0
+                        && vesting_time_reached < recipient.vesting_duration
+                    {
+                        vesting_time_reached -= vesting_time_reached % recipient.release_interval;
+                    }
+                    // This can't overflow as the percentages sum to at most 100
+                    let collectable_during_vesting: Balance =
+                        recipient.total_amount - collectable_at_tge - collectable_at_cliff;
+                    let vesting_collectable: Balance = schedule::curved_amount(
+                        collectable_during_vesting,
+                        vesting_time_reached,
+                        recipient.vesting_duration,
+                        &recipient.curve,
+                    );
+                    // This can't overflow
+                    total_collectable_at_time =
+                        total_collectable_at_time + collectable_at_cliff + vesting_collectable;
+                }
+                if total_collectable_at_time > recipient.total_amount {
+                    total_collectable_at_time = recipient.total_amount
+                }
+            }
+
+            total_collectable_at_time
+        }
+
+        // Contracts only, so a whitelisted address can't be used from a key
+        fn whitelisted_caller_check(&self, caller: AccountId) -> Result<()> {
+            if self.whitelisted_callers_only
+                && !(self.whitelisted_callers.contains(caller) && Self::env().is_contract(&caller))
+            {
+                return Err(AzAirdropError::Unauthorised {
+                    caller,
+                    expected: None,
+                });
+            }
+
+            Ok(())
+        }
+
+        // Keeps calls that take a list of addresses within the block weight limit
+        fn within_list_limit(requested: usize) -> Result<()> {
+            if requested > MAX_RECIPIENTS_LIST_LIMIT as usize {
+                return Err(AzAirdropError::LimitExceeded {
+                    limit: MAX_RECIPIENTS_LIST_LIMIT.into(),
+                    requested: requested as u128,
+                });
+            }
+
+            Ok(())
+        }
+    }
+
+    impl AzAirdropQuery for AzAirdrop {
+        #[ink(message)]
+        fn collectable_amount(&self, address: AccountId, timestamp: Timestamp) -> Result<Balance> {
+            AzAirdrop::collectable_amount(self, address, timestamp)
+        }
+
+        #[ink(message)]
+        fn config(&self) -> Config {
+            AzAirdrop::config(self)
+        }
+
+        #[ink(message)]
+        fn show(&self, address: AccountId) -> Result<Recipient> {
+            AzAirdrop::show(self, address)
+        }
+
+        #[ink(message)]
+        fn stats(&self) -> Stats {
+            AzAirdrop::stats(self)
+        }
+    }
+
+    impl AzAirdropSales for AzAirdrop {
+        #[ink(message)]
+        fn recipient_add(
+            &mut self,
+            address: AccountId,
+            amount: Balance,
+            description: Option<Description>,
+            template_id: Option<u16>,
+            start_override: Option<Timestamp>,
+        ) -> Result<Recipient> {
+            AzAirdrop::recipient_add(
+                self,
+                address,
+                amount,
+                description,
+                template_id,
+                start_override,
+            )
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::{
+            test::{default_accounts, set_caller, DefaultAccounts},
+            DefaultEnvironment,
+        };
+
+        const MOCK_START: Timestamp = 654_654;
+        const ALL_PERMISSIONS: SubAdminPermissions = SubAdminPermissions {
+            can_add: true,
+            can_subtract: true,
+            can_update_schedule: true,
+        };
+
+        // === HELPERS ===
+        fn init() -> (DefaultAccounts<DefaultEnvironment>, AzAirdrop) {
+            let accounts = default_accounts();
+            set_caller::<DefaultEnvironment>(accounts.bob);
             let az_airdrop = AzAirdrop::new(mock_token(), MOCK_START, 100, 0, 0).unwrap();
             (accounts, az_airdrop)
         }
 
-        fn mock_token() -> AccountId {
-            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
-            accounts.django
+        fn mock_token() -> AccountId {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            accounts.django
+        }
+
+        // === TESTS ===
+        // === TEST CONSTRUCTOR ===
+        #[ink::test]
+        fn test_new() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            let result = AzAirdrop::new(mock_token(), MOCK_START, 0, 0, 0);
+            assert!(result.is_err());
+        }
+
+        // === TEST QUERIES ===
+        #[ink::test]
+        fn test_az_airdrop_query() {
+            let (accounts, mut az_airdrop) = init();
+            let recipient: Recipient = Recipient {
+                allocation_id: 0,
+                total_amount: 100,
+                collected: 40,
+                collectable_at_tge_percentage: 100,
+                cliff_duration: 0,
+                vesting_duration: 0,
+                schedule_phases: None,
+                curve: Curve::Linear,
+                revocable: false,
+                external_ref: None,
+                release_interval: 0,
+                start_override: None,
+                collectable_at_cliff_percentage: 0,
+                source: AllocationSource::AdminGrant,
+                template_id: None,
+            };
+            az_airdrop.recipients.insert(accounts.django, &recipient);
+            // * it returns the same as the inherent queries
+            assert_eq!(
+                AzAirdropQuery::show(&az_airdrop, accounts.django),
+                Ok(recipient)
+            );
+            assert_eq!(
+                AzAirdropQuery::collectable_amount(&az_airdrop, accounts.django, MOCK_START),
+                Ok(60)
+            );
+            assert_eq!(AzAirdropQuery::config(&az_airdrop).admin, accounts.bob);
+            assert_eq!(AzAirdropQuery::stats(&az_airdrop), az_airdrop.stats());
+        }
+
+        #[ink::test]
+        fn test_az_airdrop_sales() {
+            let (accounts, mut az_airdrop) = init();
+            az_airdrop.budgets.insert(accounts.bob, &100);
+            az_airdrop.total_reserved = 100;
+            // * it adds recipients through the inherent handle
+            let recipient: Recipient = AzAirdropSales::recipient_add(
+                &mut az_airdrop,
+                accounts.django,
+                100,
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+            assert_eq!(az_airdrop.to_be_collected, 100);
+            assert_eq!(az_airdrop.show(accounts.django), Ok(recipient));
+        }
+
+        #[ink::test]
+        fn test_claimed_on_day() {
+            let (_accounts, mut az_airdrop) = init();
+            let day: u32 = 19_000;
+            let timestamp: Timestamp = Timestamp::from(day) * DAY;
+            // when nothing has been claimed on day
+            // * it returns 0
+            assert_eq!(az_airdrop.claimed_on_day(day), 0);
+            // when claims are recorded on day
+            // * it returns the sum of the claims
+            az_airdrop.record_claimed_on_day(timestamp, 5);
+            az_airdrop.record_claimed_on_day(timestamp + DAY - 1, 7);
+            assert_eq!(az_airdrop.claimed_on_day(day), 12);
+            // when a claim is recorded after the retention window has passed
+            az_airdrop.claimed_days_retention = 2;
+            az_airdrop.record_claimed_on_day(timestamp + DAY, 3);
+            // = when day is still within the retention window
+            // = * it keeps the bucket
+            assert_eq!(az_airdrop.claimed_on_day(day), 12);
+            // = when day is outside the retention window
+            // = * it prunes the bucket
+            az_airdrop.record_claimed_on_day(timestamp + 2 * DAY, 1);
+            assert_eq!(az_airdrop.claimed_on_day(day), 0);
+            assert_eq!(az_airdrop.claimed_on_day(day + 1), 3);
+            assert_eq!(az_airdrop.claimed_on_day(day + 2), 1);
+        }
+
+        #[ink::test]
+        fn test_collectable_amount() {
+            let (accounts, mut az_airdrop) = init();
+            let recipient_address: AccountId = accounts.django;
+            let mut recipient: Recipient = Recipient {
+                allocation_id: 0,
+                total_amount: 100,
+                collected: 0,
+                collectable_at_tge_percentage: 100,
+                cliff_duration: 0,
+                vesting_duration: 0,
+                schedule_phases: None,
+                curve: Curve::Linear,
+                revocable: false,
+                external_ref: None,
+                release_interval: 0,
+                start_override: None,
+                collectable_at_cliff_percentage: 0,
+                source: AllocationSource::AdminGrant,
+                template_id: None,
+            };
+            // when recipient does not exist
+            // * it returns an error
+            let mut result = az_airdrop.collectable_amount(recipient_address, 0);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Recipient".to_string(),))
+            );
+            // when recipient exists
+            az_airdrop.recipients.insert(recipient_address, &recipient);
+            // = when provided timestamp is before the start time
+            // = * it returns zero
+            result = az_airdrop.collectable_amount(recipient_address, MOCK_START - 1);
+            let mut result_unwrapped: Balance = result.unwrap();
+            assert_eq!(result_unwrapped, 0);
+            // = when provided timestamp is greater than or equal to start time
+            // == when collectable_at_tge_percentage is positive
+            // === when collectable_at_tge_percentagne is 100
+            // === * it returns the total_amount
+            result = az_airdrop.collectable_amount(recipient_address, MOCK_START);
+            result_unwrapped = result.unwrap();
+            assert_eq!(result_unwrapped, recipient.total_amount);
+            // === when collectable_at_tge_percentage is 20
+            // ==== when vesting time has not been reached
+            // ==== * it returns 20
+            recipient = az_airdrop
+                .update_recipient(
+                    recipient_address,
+                    Some(20),
+                    Some(1),
+                    Some(100),
+                    None,
+                    None,
+                    None,
+                )
+                .unwrap();
+            result = az_airdrop.collectable_amount(recipient_address, MOCK_START);
+            result_unwrapped = result.unwrap();
+            assert_eq!(result_unwrapped, 20);
+            result = az_airdrop.collectable_amount(recipient_address, MOCK_START + 1);
+            result_unwrapped = result.unwrap();
+            assert_eq!(result_unwrapped, 20);
+            // ==== when partial vesting time has been reached
+            result = az_airdrop
+                .collectable_amount(recipient_address, MOCK_START + recipient.cliff_duration + 2);
+            // ==== * it returns the partial amount
+            result_unwrapped = result.unwrap();
+            assert_eq!(result_unwrapped, 20 + (2 * 80 / 100));
+            // ==== when total vesting time has been reached
+            result = az_airdrop.collectable_amount(
+                recipient_address,
+                MOCK_START + recipient.cliff_duration + recipient.vesting_duration * 1_000_000,
+            );
+            // ==== * it returns the total amount
+            result_unwrapped = result.unwrap();
+            assert_eq!(result_unwrapped, recipient.total_amount);
+            // ==== * it factors in recipient.collected
+            recipient.collected = 20;
+            az_airdrop.recipients.insert(recipient_address, &recipient);
+            result = az_airdrop.collectable_amount(
+                recipient_address,
+                MOCK_START + recipient.cliff_duration + recipient.vesting_duration,
+            );
+            result_unwrapped = result.unwrap();
+            assert_eq!(result_unwrapped, recipient.total_amount - 20);
+        }
+
+        #[ink::test]
+        fn test_collectable_now() {
+            let (accounts, mut az_airdrop) = init();
+            let recipient_address: AccountId = accounts.django;
+            // when recipient does not exist
+            // * it returns an error
+            let mut result = az_airdrop.collectable_now(recipient_address);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Recipient".to_string()))
+            );
+            // when recipient exists
+            az_airdrop.recipients.insert(
+                recipient_address,
+                &Recipient {
+                    allocation_id: 0,
+                    total_amount: 100,
+                    collected: 10,
+                    collectable_at_tge_percentage: 100,
+                    cliff_duration: 0,
+                    vesting_duration: 0,
+                    schedule_phases: None,
+                    curve: Curve::Linear,
+                    revocable: false,
+                    external_ref: None,
+                    release_interval: 0,
+                    start_override: None,
+                    collectable_at_cliff_percentage: 0,
+                    source: AllocationSource::AdminGrant,
+                    template_id: None,
+                },
+            );
+            // = when airdrop has not started
+            // = * it returns zero
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(MOCK_START - 1);
+            result = az_airdrop.collectable_now(recipient_address);
+            assert_eq!(result, Ok(0));
+            // = when airdrop has started
+            // = * it uses the current block timestamp
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(MOCK_START);
+            result = az_airdrop.collectable_now(recipient_address);
+            assert_eq!(result, Ok(90));
+            // == when recipient has a max per claim
+            // == * it caps the amount
+            az_airdrop.max_per_claims.insert(recipient_address, &50);
+            result = az_airdrop.collectable_now(recipient_address);
+            assert_eq!(result, Ok(50));
+            // == when claims are not enabled yet
+            // == * it returns zero
+            az_airdrop.claims_enabled_at = Some(MOCK_START + 1);
+            result = az_airdrop.collectable_now(recipient_address);
+            assert_eq!(result, Ok(0));
+            az_airdrop.claims_enabled_at = None;
+            // == when claims are paused
+            // == * it returns zero
+            az_airdrop.claims_enabled = false;
+            result = az_airdrop.collectable_now(recipient_address);
+            assert_eq!(result, Ok(0));
+        }
+
+        #[ink::test]
+        fn test_collected_before() {
+            let (accounts, mut az_airdrop) = init();
+            let recipient_address: AccountId = accounts.django;
+            // when recipient does not exist
+            // * it raises an error
+            let result = az_airdrop.collected_before(recipient_address, MOCK_START);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Recipient".to_string()))
+            );
+            // when recipient exists
+            az_airdrop.recipients.insert(
+                recipient_address,
+                &Recipient {
+                    allocation_id: 0,
+                    total_amount: 100,
+                    collected: 30,
+                    collectable_at_tge_percentage: 100,
+                    cliff_duration: 0,
+                    vesting_duration: 0,
+                    schedule_phases: None,
+                    curve: Curve::Linear,
+                    revocable: false,
+                    external_ref: None,
+                    release_interval: 0,
+                    start_override: None,
+                    collectable_at_cliff_percentage: 0,
+                    source: AllocationSource::AdminGrant,
+                    template_id: None,
+                },
+            );
+            az_airdrop.claims.insert(
+                (recipient_address, 0),
+                &Claim {
+                    amount: 10,
+                    timestamp: MOCK_START,
+                },
+            );
+            az_airdrop.claims.insert(
+                (recipient_address, 1),
+                &Claim {
+                    amount: 20,
+                    timestamp: MOCK_START + 5,
+                },
+            );
+            az_airdrop.claims_count.insert(recipient_address, &2);
+            // * it sums the claims made strictly before the timestamp
+            assert_eq!(
+                az_airdrop.collected_before(recipient_address, MOCK_START),
+                Ok(0)
+            );
+            assert_eq!(
+                az_airdrop.collected_before(recipient_address, MOCK_START + 5),
+                Ok(10)
+            );
+            assert_eq!(
+                az_airdrop.collected_before(recipient_address, MOCK_START + 6),
+                Ok(30)
+            );
+        }
+
+        #[ink::test]
+        fn test_config() {
+            let (accounts, az_airdrop) = init();
+            let config = az_airdrop.config();
+            // * it returns the config
+            assert_eq!(config.token, mock_token());
+            assert_eq!(config.admin, accounts.bob);
+            assert_eq!(
+                config.sub_admins,
+                az_airdrop.sub_admins_as_vec.get_or_default().into_inner()
+            );
+            assert_eq!(config.start, MOCK_START);
+            assert_eq!(config.default_collectable_at_tge_percentage, 100);
+            assert_eq!(config.default_cliff_duration, 0);
+            assert_eq!(config.default_vesting_duration, 0);
+        }
+
+        #[ink::test]
+        fn test_find_by_external_ref() {
+            let (accounts, mut az_airdrop) = init();
+            let external_ref: [u8; 16] = [1; 16];
+            // when external ref is not in use
+            // * it raises an error
+            assert_eq!(
+                az_airdrop.find_by_external_ref(external_ref),
+                Err(AzAirdropError::NotFound("Recipient".to_string()))
+            );
+            // when external ref is in use
+            // * it returns the address and recipient
+            let recipient: Recipient = Recipient {
+                allocation_id: 0,
+                total_amount: 5,
+                collected: 0,
+                collectable_at_tge_percentage: 0,
+                cliff_duration: 0,
+                vesting_duration: 0,
+                schedule_phases: None,
+                curve: Curve::Linear,
+                revocable: false,
+                external_ref: Some(external_ref),
+                release_interval: 0,
+                start_override: None,
+                collectable_at_cliff_percentage: 0,
+                source: AllocationSource::AdminGrant,
+                template_id: None,
+            };
+            az_airdrop.insert_recipient(accounts.django, &recipient);
+            assert_eq!(
+                az_airdrop.find_by_external_ref(external_ref),
+                Ok((accounts.django, recipient))
+            );
+            // = when the recipient is removed
+            // = * it raises an error
+            az_airdrop.remove_recipient(accounts.django);
+            assert_eq!(
+                az_airdrop.find_by_external_ref(external_ref),
+                Err(AzAirdropError::NotFound("Recipient".to_string()))
+            );
+        }
+
+        #[ink::test]
+        fn test_has_role() {
+            let (accounts, mut az_airdrop) = init();
+            // when account is the admin
+            // * it returns true for every role
+            assert!(az_airdrop.has_role(role_registry::DEFAULT_ADMIN, accounts.bob));
+            assert!(az_airdrop.has_role(role_registry::PAUSER, accounts.bob));
+            assert!(az_airdrop.has_role(role_registry::RECIPIENT_MANAGER, accounts.bob));
+            assert!(az_airdrop.has_role(role_registry::TREASURER, accounts.bob));
+            // when account is a sub-admin
+            // * it only has the sub-admin role
+            az_airdrop
+                .sub_admins_add(accounts.charlie, ALL_PERMISSIONS)
+                .unwrap();
+            assert!(az_airdrop.has_role(role_registry::RECIPIENT_MANAGER, accounts.charlie));
+            assert!(!az_airdrop.has_role(role_registry::DEFAULT_ADMIN, accounts.charlie));
+            assert!(!az_airdrop.has_role(role_registry::PAUSER, accounts.charlie));
+            // when role is granted locally
+            // * it returns true for that role only
+            az_airdrop
+                .role_grant(role_registry::PAUSER, accounts.django)
+                .unwrap();
+            assert!(az_airdrop.has_role(role_registry::PAUSER, accounts.django));
+            assert!(!az_airdrop.has_role(role_registry::TREASURER, accounts.django));
+            assert!(!az_airdrop.has_role(role_registry::RECIPIENT_MANAGER, accounts.django));
+        }
+
+        #[ink::test]
+        fn test_interfaces() {
+            let (_accounts, az_airdrop) = init();
+            // * it returns the supported selector set ids
+            let interfaces: Vec<[u8; 4]> = az_airdrop.interfaces();
+            assert!(interfaces.contains(&ink::selector_bytes!("az_airdrop::core")));
+            assert!(interfaces.contains(&ink::selector_bytes!("az_airdrop::query")));
+            assert!(interfaces.contains(&ink::selector_bytes!("az_airdrop::sales")));
+            assert!(interfaces.contains(&ink::selector_bytes!("az_airdrop::merkle")));
+            assert!(interfaces.contains(&ink::selector_bytes!("az_airdrop::roles")));
+            assert!(!interfaces.contains(&ink::selector_bytes!("az_airdrop::campaigns")));
+        }
+
+        #[ink::test]
+        fn test_ledger_digest() {
+            let (accounts, mut az_airdrop) = init();
+            let recipient: Recipient = Recipient {
+                allocation_id: 0,
+                total_amount: 5,
+                collected: 0,
+                collectable_at_tge_percentage: 0,
+                cliff_duration: 0,
+                vesting_duration: 0,
+                schedule_phases: None,
+                curve: Curve::Linear,
+                revocable: false,
+                external_ref: None,
+                release_interval: 0,
+                start_override: None,
+                collectable_at_cliff_percentage: 0,
+                source: AllocationSource::AdminGrant,
+                template_id: None,
+            };
+            // when there are no recipients
+            // * it returns zero
+            assert_eq!(az_airdrop.ledger_digest(), [0; 32]);
+            // when a recipient is added
+            // * it changes
+            az_airdrop.insert_recipient(accounts.django, &recipient);
+            az_airdrop.to_be_collected = recipient.total_amount;
+            let digest: [u8; 32] = az_airdrop.ledger_digest();
+            assert_ne!(digest, [0; 32]);
+            // when a recipient is updated
+            // * it changes
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(az_airdrop.start - 1);
+            az_airdrop
+                .recipient_subtract(accounts.django, 1, None)
+                .unwrap();
+            assert_ne!(az_airdrop.ledger_digest(), digest);
+            // = when it is updated back
+            // = * it matches the earlier digest
+            az_airdrop.insert_recipient(accounts.django, &recipient);
+            assert_eq!(az_airdrop.ledger_digest(), digest);
+            // when all recipients are removed
+            // * it returns zero
+            az_airdrop.recipient_remove(accounts.django).unwrap();
+            assert_eq!(az_airdrop.ledger_digest(), [0; 32]);
+        }
+
+        #[ink::test]
+        fn test_org_vesting_info() {
+            let (accounts, mut az_airdrop) = init();
+            // when organization does not exist
+            // * it raises an error
+            let result = az_airdrop.org_vesting_info(0);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Organization".to_string()))
+            );
+            // when organization exists
+            let org_id: u32 = az_airdrop.organization_create(accounts.charlie).unwrap();
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            az_airdrop
+                .organization_members_add(org_id, accounts.django)
+                .unwrap();
+            az_airdrop
+                .organization_members_add(org_id, accounts.eve)
+                .unwrap();
+            az_airdrop
+                .organization_members_add(org_id, accounts.frank)
+                .unwrap();
+            az_airdrop.recipients.insert(
+                accounts.django,
+                &Recipient {
+                    allocation_id: 0,
+                    total_amount: 10,
+                    collected: 0,
+                    collectable_at_tge_percentage: 100,
+                    cliff_duration: 0,
+                    vesting_duration: 0,
+                    schedule_phases: None,
+                    curve: Curve::Linear,
+                    revocable: false,
+                    external_ref: None,
+                    release_interval: 0,
+                    start_override: None,
+                    collectable_at_cliff_percentage: 0,
+                    source: AllocationSource::AdminGrant,
+                    template_id: None,
+                },
+            );
+            az_airdrop.recipients.insert(
+                accounts.eve,
+                &Recipient {
+                    allocation_id: 0,
+                    total_amount: 20,
+                    collected: 5,
+                    collectable_at_tge_percentage: 100,
+                    cliff_duration: 0,
+                    vesting_duration: 0,
+                    schedule_phases: None,
+                    curve: Curve::Linear,
+                    revocable: false,
+                    external_ref: None,
+                    release_interval: 0,
+                    start_override: None,
+                    collectable_at_cliff_percentage: 0,
+                    source: AllocationSource::AdminGrant,
+                    template_id: None,
+                },
+            );
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(az_airdrop.start);
+            // * it aggregates the members that have an allocation
+            assert_eq!(
+                az_airdrop.org_vesting_info(org_id).unwrap(),
+                OrganizationVestingInfo {
+                    total_amount: 30,
+                    collected: 5,
+                    collectable: 25,
+                }
+            );
+        }
+
+        #[ink::test]
+        fn test_payout_address_allowed() {
+            let (accounts, mut az_airdrop) = init();
+            let recipient_address: AccountId = accounts.django;
+            let destination: AccountId = accounts.eve;
+            // when compliance mode is off
+            // * it returns true
+            assert!(az_airdrop.payout_address_allowed(recipient_address, destination));
+            // when compliance mode is on
+            az_airdrop.compliance_mode = true;
+            // = when destination is the recipient
+            // = * it returns true
+            assert!(az_airdrop.payout_address_allowed(recipient_address, recipient_address));
+            // = when destination has not been registered
+            // = * it returns false
+            assert!(!az_airdrop.payout_address_allowed(recipient_address, destination));
+            // = when destination has been registered
+            az_airdrop
+                .payout_addresses
+                .insert((recipient_address, destination), &(MOCK_START + 1));
+            // == when cooldown has not passed
+            // == * it returns false
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(MOCK_START);
+            assert!(!az_airdrop.payout_address_allowed(recipient_address, destination));
+            // == when cooldown has passed
+            // == * it returns true
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(MOCK_START + 1);
+            assert!(az_airdrop.payout_address_allowed(recipient_address, destination));
+        }
+
+        #[ink::test]
+        fn test_recipients_list() {
+            let (accounts, mut az_airdrop) = init();
+            let recipient: Recipient = Recipient {
+                allocation_id: 0,
+                total_amount: 0,
+                collected: 0,
+                collectable_at_tge_percentage: 0,
+                cliff_duration: 0,
+                vesting_duration: 0,
+                schedule_phases: None,
+                curve: Curve::Linear,
+                revocable: false,
+                external_ref: None,
+                release_interval: 0,
+                start_override: None,
+                collectable_at_cliff_percentage: 0,
+                source: AllocationSource::AdminGrant,
+                template_id: None,
+            };
+            // when there are no recipients
+            // * it returns an empty list
+            assert_eq!(az_airdrop.recipients_list(0, 10), vec![]);
+            // when there are recipients
+            az_airdrop.insert_recipient(accounts.django, &recipient);
+            az_airdrop.insert_recipient(accounts.eve, &recipient);
+            az_airdrop.insert_recipient(accounts.frank, &recipient);
+            // = * it returns the requested page
+            assert_eq!(
+                az_airdrop.recipients_list(1, 10),
+                vec![
+                    (accounts.eve, recipient.clone()),
+                    (accounts.frank, recipient.clone())
+                ]
+            );
+            assert_eq!(
+                az_airdrop.recipients_list(0, 1),
+                vec![(accounts.django, recipient.clone())]
+            );
+            // = * it does not list an address twice when it is updated
+            az_airdrop.insert_recipient(accounts.django, &recipient);
+            assert_eq!(az_airdrop.recipients_list(0, 10).len(), 3);
+            // = when a recipient is removed
+            // = * it moves the last recipient into its place
+            az_airdrop.remove_recipient(accounts.django);
+            assert_eq!(
+                az_airdrop.recipients_list(0, 10),
+                vec![
+                    (accounts.frank, recipient.clone()),
+                    (accounts.eve, recipient.clone())
+                ]
+            );
+            az_airdrop.remove_recipient(accounts.eve);
+            az_airdrop.remove_recipient(accounts.frank);
+            assert_eq!(az_airdrop.recipients_list(0, 10), vec![]);
+        }
+
+        #[ink::test]
+        fn test_retrofit_to_template_preview() {
+            let (accounts, mut az_airdrop) = init();
+            // when template does not exist
+            // * it raises an error
+            assert_eq!(
+                az_airdrop.retrofit_to_template_preview(0, 0, 10),
+                Err(AzAirdropError::NotFound("Schedule template".to_string()))
+            );
+            // when template exists
+            az_airdrop.schedule_templates.insert(
+                0,
+                &ScheduleTemplate {
+                    name: "Team".to_string(),
+                    collectable_at_tge_percentage: 10,
+                    cliff_duration: 5,
+                    vesting_duration: 50,
+                },
+            );
+            az_airdrop.insert_recipient(
+                accounts.django,
+                &Recipient {
+                    allocation_id: 0,
+                    total_amount: 10,
+                    collected: 0,
+                    collectable_at_tge_percentage: 0,
+                    cliff_duration: 0,
+                    vesting_duration: 100,
+                    schedule_phases: None,
+                    curve: Curve::Linear,
+                    revocable: false,
+                    external_ref: None,
+                    release_interval: 0,
+                    start_override: None,
+                    collectable_at_cliff_percentage: 0,
+                    source: AllocationSource::AdminGrant,
+                    template_id: None,
+                },
+            );
+            az_airdrop.insert_recipient(
+                accounts.eve,
+                &Recipient {
+                    allocation_id: 0,
+                    total_amount: 10,
+                    collected: 0,
+                    collectable_at_tge_percentage: 10,
+                    cliff_duration: 5,
+                    vesting_duration: 50,
+                    schedule_phases: None,
+                    curve: Curve::Linear,
+                    revocable: false,
+                    external_ref: None,
+                    release_interval: 0,
+                    start_override: None,
+                    collectable_at_cliff_percentage: 0,
+                    source: AllocationSource::AdminGrant,
+                    template_id: Some(0),
+                },
+            );
+            az_airdrop.insert_recipient(
+                accounts.frank,
+                &Recipient {
+                    allocation_id: 0,
+                    total_amount: 10,
+                    collected: 0,
+                    collectable_at_tge_percentage: 0,
+                    cliff_duration: 0,
+                    vesting_duration: 100,
+                    schedule_phases: Some(vec![SchedulePhase::Instant { at: 0, bps: 10_000 }]),
+                    curve: Curve::Linear,
+                    revocable: false,
+                    external_ref: None,
+                    release_interval: 0,
+                    start_override: None,
+                    collectable_at_cliff_percentage: 0,
+                    source: AllocationSource::AdminGrant,
+                    template_id: None,
+                },
+            );
+            // * it lists recipients that don't reference the template yet
+            assert_eq!(
+                az_airdrop.retrofit_to_template_preview(0, 0, 10),
+                Ok(vec![accounts.django])
+            );
+            // * it only looks at the requested page
+            assert_eq!(
+                az_airdrop.retrofit_to_template_preview(0, 1, 10),
+                Ok(vec![])
+            );
+        }
+
+        #[cfg(feature = "diagnostics")]
+        #[ink::test]
+        fn test_schedule_sanity() {
+            let (accounts, mut az_airdrop) = init();
+            let recipient_address: AccountId = accounts.django;
+            // when recipient does not exist
+            // * it raises an error
+            let result = az_airdrop.schedule_sanity(recipient_address);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Recipient".to_string()))
+            );
+            // when recipient exists
+            let mut recipient: Recipient = Recipient {
+                allocation_id: 0,
+                total_amount: 100,
+                collected: 0,
+                collectable_at_tge_percentage: 20,
+                cliff_duration: 0,
+                vesting_duration: 31_556_952_000,
+                schedule_phases: None,
+                curve: Curve::Linear,
+                revocable: false,
+                external_ref: None,
+                release_interval: 0,
+                start_override: None,
+                collectable_at_cliff_percentage: 0,
+                source: AllocationSource::AdminGrant,
+                template_id: None,
+            };
+            az_airdrop.recipients.insert(recipient_address, &recipient);
+            // = when durations are plausible
+            // = * it returns no warnings
+            assert_eq!(az_airdrop.schedule_sanity(recipient_address), Ok(vec![]));
+            // = when durations look like they were entered in seconds
+            recipient.cliff_duration = 2_592_000;
+            recipient.vesting_duration = 86_400;
+            az_airdrop.recipients.insert(recipient_address, &recipient);
+            // = * it flags them as too short
+            assert_eq!(
+                az_airdrop.schedule_sanity(recipient_address),
+                Ok(vec![
+                    ScheduleWarning::CliffDurationTooShort,
+                    ScheduleWarning::VestingDurationTooShort
+                ])
+            );
+            // = when schedule phases last longer than 20 years
+            recipient.schedule_phases = Some(vec![SchedulePhase::Linear {
+                duration: 31_556_952_000 * 21,
+                bps: 10_000,
+            }]);
+            az_airdrop.recipients.insert(recipient_address, &recipient);
+            // = * it flags them as too long
+            assert_eq!(
+                az_airdrop.schedule_sanity(recipient_address),
+                Ok(vec![ScheduleWarning::VestingDurationTooLong])
+            );
+        }
+
+        #[ink::test]
+        fn test_show_many() {
+            let (accounts, mut az_airdrop) = init();
+            let recipient: Recipient = Recipient {
+                allocation_id: 0,
+                total_amount: 5,
+                collected: 0,
+                collectable_at_tge_percentage: 100,
+                cliff_duration: 0,
+                vesting_duration: 0,
+                schedule_phases: None,
+                curve: Curve::Linear,
+                revocable: false,
+                external_ref: None,
+                release_interval: 0,
+                start_override: None,
+                collectable_at_cliff_percentage: 0,
+                source: AllocationSource::AdminGrant,
+                template_id: None,
+            };
+            az_airdrop.recipients.insert(accounts.django, &recipient);
+            // when there are more addresses than the limit
+            // * it raises an error
+            assert_eq!(
+                az_airdrop.show_many(vec![accounts.eve; MAX_RECIPIENTS_LIST_LIMIT as usize + 1]),
+                Err(AzAirdropError::LimitExceeded {
+                    limit: MAX_RECIPIENTS_LIST_LIMIT.into(),
+                    requested: u128::from(MAX_RECIPIENTS_LIST_LIMIT) + 1,
+                })
+            );
+            // when addresses are within the limit
+            // * it returns each address's recipient in order, None when unknown
+            assert_eq!(
+                az_airdrop.show_many(vec![accounts.eve, accounts.django]),
+                Ok(vec![None, Some(recipient)])
+            );
+        }
+
+        #[ink::test]
+        fn test_statement() {
+            let (accounts, mut az_airdrop) = init();
+            let recipient_address: AccountId = accounts.django;
+            // when recipient does not exist
+            // * it raises an error
+            let result = az_airdrop.statement(recipient_address);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Recipient".to_string()))
+            );
+            // when recipient exists
+            let recipient: Recipient = Recipient {
+                allocation_id: 0,
+                total_amount: 120,
+                collected: 24,
+                collectable_at_tge_percentage: 20,
+                cliff_duration: 10,
+                vesting_duration: 120,
+                schedule_phases: None,
+                curve: Curve::Linear,
+                revocable: false,
+                external_ref: None,
+                release_interval: 0,
+                start_override: None,
+                collectable_at_cliff_percentage: 0,
+                source: AllocationSource::AdminGrant,
+                template_id: None,
+            };
+            az_airdrop.recipients.insert(recipient_address, &recipient);
+            let claim: Claim = Claim {
+                amount: 24,
+                timestamp: MOCK_START,
+            };
+            az_airdrop.claims.insert((recipient_address, 0), &claim);
+            az_airdrop.claims_count.insert(recipient_address, &1);
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(
+                MOCK_START + 10 + 30,
+            );
+            let statement: Statement = az_airdrop.statement(recipient_address).unwrap();
+            // * it returns the schedule and claim summary
+            assert_eq!(statement.recipient, recipient);
+            assert_eq!(statement.vesting_start, MOCK_START + 10);
+            assert_eq!(statement.vesting_end, MOCK_START + 10 + 120);
+            assert_eq!(statement.claims_count, 1);
+            assert_eq!(statement.last_claim, Some(claim));
+            assert_eq!(statement.collectable_now, 24);
+            // * it projects the upcoming period boundaries
+            assert_eq!(statement.projected_unlocks.len(), 9);
+            assert_eq!(
+                statement.projected_unlocks[0],
+                (MOCK_START + 10 + 40, 24 + 32)
+            );
+            assert_eq!(statement.projected_unlocks[8], (MOCK_START + 10 + 120, 120));
+            // * it matches collectable_now
+            az_airdrop.max_per_claims.insert(recipient_address, &5);
+            assert_eq!(
+                az_airdrop
+                    .statement(recipient_address)
+                    .unwrap()
+                    .collectable_now,
+                5
+            );
+            az_airdrop.claim_deadline = Some(MOCK_START + 10 + 30);
+            assert_eq!(
+                az_airdrop
+                    .statement(recipient_address)
+                    .unwrap()
+                    .collectable_now,
+                0
+            );
+            // * it returns the category and description hash of the first allocation
+            assert_eq!(statement.category, None);
+            assert_eq!(statement.description_hash, None);
+            az_airdrop.claim_deadline = None;
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(MOCK_START - 1);
+            az_airdrop.budgets.insert(accounts.bob, &3);
+            az_airdrop.total_reserved = 3;
+            az_airdrop.labels_set(0, "Team".to_string()).unwrap();
+            az_airdrop
+                .recipient_add(accounts.eve, 1, Some(Description::Label(0)), None, None)
+                .unwrap();
+            az_airdrop
+                .recipient_add(
+                    accounts.eve,
+                    1,
+                    Some(Description::Text("Top up".to_string())),
+                    None,
+                    None,
+                )
+                .unwrap();
+            let statement: Statement = az_airdrop.statement(accounts.eve).unwrap();
+            assert_eq!(statement.category, Some(0));
+            assert_eq!(statement.description_hash, None);
+            az_airdrop
+                .recipient_add(
+                    accounts.frank,
+                    1,
+                    Some(Description::Text("Seed round".to_string())),
+                    None,
+                    None,
+                )
+                .unwrap();
+            let mut description_hash: [u8; 32] = [0; 32];
+            ink::env::hash_bytes::<Blake2x256>(b"Seed round", &mut description_hash);
+            let statement: Statement = az_airdrop.statement(accounts.frank).unwrap();
+            assert_eq!(statement.category, None);
+            assert_eq!(
+                statement.description_hash,
+                Some(Hash::from(description_hash))
+            );
+        }
+
+        #[ink::test]
+        fn test_stats() {
+            let (accounts, mut az_airdrop) = init();
+            let mut recipient: Recipient = Recipient {
+                allocation_id: 0,
+                total_amount: 5,
+                collected: 0,
+                collectable_at_tge_percentage: 0,
+                cliff_duration: 0,
+                vesting_duration: 0,
+                schedule_phases: None,
+                curve: Curve::Linear,
+                revocable: false,
+                external_ref: None,
+                release_interval: 0,
+                start_override: None,
+                collectable_at_cliff_percentage: 0,
+                source: AllocationSource::AdminGrant,
+                template_id: None,
+            };
+            az_airdrop.insert_recipient(accounts.django, &recipient);
+            az_airdrop.insert_recipient(accounts.eve, &recipient);
+            az_airdrop.to_be_collected = 10;
+            // when nothing has been collected
+            // * it returns the allocated totals
+            assert_eq!(
+                az_airdrop.stats(),
+                Stats {
+                    total_allocated: 10,
+                    total_collected: 0,
+                    to_be_collected: 10,
+                    recipients_count: 2,
+                    fully_collected_count: 0,
+                }
+            );
+            // when recipients collect
+            // * it tracks the collected totals
+            recipient.collected = 5;
+            az_airdrop.insert_recipient(accounts.django, &recipient);
+            recipient.collected = 2;
+            az_airdrop.insert_recipient(accounts.eve, &recipient);
+            az_airdrop.to_be_collected = 3;
+            assert_eq!(
+                az_airdrop.stats(),
+                Stats {
+                    total_allocated: 10,
+                    total_collected: 7,
+                    to_be_collected: 3,
+                    recipients_count: 2,
+                    fully_collected_count: 1,
+                }
+            );
+            // when the claim deadline has passed
+            // * it leaves out what's still to be collected
+            az_airdrop.claim_deadline = Some(MOCK_START);
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(MOCK_START);
+            assert_eq!(
+                az_airdrop.stats(),
+                Stats {
+                    total_allocated: 7,
+                    total_collected: 7,
+                    to_be_collected: 0,
+                    recipients_count: 2,
+                    fully_collected_count: 1,
+                }
+            );
+        }
+
+        #[ink::test]
+        fn test_sub_admin_permissions() {
+            let (accounts, mut az_airdrop) = init();
+            // when address isn't a sub admin
+            // * it returns None
+            assert_eq!(az_airdrop.sub_admin_permissions(accounts.django), None);
+            // when address is a sub admin
+            // * it returns the permissions set when adding it
+            let permissions: SubAdminPermissions = SubAdminPermissions {
+                can_add: true,
+                can_subtract: false,
+                can_update_schedule: false,
+            };
+            az_airdrop
+                .sub_admins_add(accounts.django, permissions)
+                .unwrap();
+            assert_eq!(
+                az_airdrop.sub_admin_permissions(accounts.django),
+                Some(permissions)
+            );
+        }
+
+        #[ink::test]
+        fn test_vested_at() {
+            let (accounts, mut az_airdrop) = init();
+            let recipient_address: AccountId = accounts.django;
+            // when recipient does not exist
+            // * it raises an error
+            let result = az_airdrop.vested_at(recipient_address, MOCK_START);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Recipient".to_string()))
+            );
+            // when recipient exists
+            az_airdrop.recipients.insert(
+                recipient_address,
+                &Recipient {
+                    allocation_id: 0,
+                    total_amount: 100,
+                    collected: 100,
+                    collectable_at_tge_percentage: 20,
+                    cliff_duration: 10,
+                    vesting_duration: 100,
+                    schedule_phases: None,
+                    curve: Curve::Linear,
+                    revocable: false,
+                    external_ref: None,
+                    release_interval: 0,
+                    start_override: None,
+                    collectable_at_cliff_percentage: 0,
+                    source: AllocationSource::AdminGrant,
+                    template_id: None,
+                },
+            );
+            // * it ignores what has been collected
+            assert_eq!(
+                az_airdrop.vested_at(recipient_address, MOCK_START - 1),
+                Ok(0)
+            );
+            assert_eq!(az_airdrop.vested_at(recipient_address, MOCK_START), Ok(20));
+            assert_eq!(
+                az_airdrop.vested_at(recipient_address, MOCK_START + 10 + 50),
+                Ok(60)
+            );
+            assert_eq!(
+                az_airdrop.vested_at(recipient_address, MOCK_START + 10 + 100),
+                Ok(100)
+            );
+            // = when recipient has a release interval
+            let mut recipient: Recipient = az_airdrop.show(recipient_address).unwrap();
+            recipient.release_interval = 30;
+            az_airdrop.recipients.insert(recipient_address, &recipient);
+            // = * it only vests completed intervals
+            assert_eq!(
+                az_airdrop.vested_at(recipient_address, MOCK_START + 10 + 29),
+                Ok(20)
+            );
+            assert_eq!(
+                az_airdrop.vested_at(recipient_address, MOCK_START + 10 + 30),
+                Ok(44)
+            );
+            assert_eq!(
+                az_airdrop.vested_at(recipient_address, MOCK_START + 10 + 99),
+                Ok(92)
+            );
+            // = * it vests everything at the end of vesting
+            assert_eq!(
+                az_airdrop.vested_at(recipient_address, MOCK_START + 10 + 100),
+                Ok(100)
+            );
+        }
+
+        #[ink::test]
+        fn test_whitelisted_caller() {
+            let (accounts, mut az_airdrop) = init();
+            // when address isn't whitelisted
+            // * it returns false
+            assert!(!az_airdrop.whitelisted_caller(accounts.frank));
+            // when address is whitelisted
+            // * it returns true
+            az_airdrop.whitelisted_callers_add(accounts.frank).unwrap();
+            assert!(az_airdrop.whitelisted_caller(accounts.frank));
+        }
+
+        // === TEST HANDLES ===
+        #[ink::test]
+        fn test_recipient_add() {
+            let (accounts, mut az_airdrop) = init();
+            let amount: Balance = 5;
+
+            // when caller is not authorised
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            // * it raises an error
+            let mut result = az_airdrop.recipient_add(accounts.charlie, amount, None, None, None);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: None,
+                })
+            );
+            // when caller is authorised
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            az_airdrop
+                .sub_admins_add(accounts.charlie, ALL_PERMISSIONS)
+                .unwrap();
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            // = when airdrop has started
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(az_airdrop.start);
+            // = * it raises an error
+            result = az_airdrop.recipient_add(accounts.charlie, amount, None, None, None);
+            assert_eq!(result, Err(AzAirdropError::AirdropAlreadyStarted));
+            // = when airdrop has not started
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(
+                az_airdrop.start - 1,
+            );
+            // == when allocations are frozen
+            az_airdrop.allocations_frozen = true;
+            // == * it raises an error
+            result = az_airdrop.recipient_add(accounts.charlie, amount, None, None, None);
+            assert_eq!(result, Err(AzAirdropError::AllocationsFrozen));
+            az_airdrop.allocations_frozen = false;
+            // == when description is a label that does not exist
+            // == * it raises an error
+            result = az_airdrop.recipient_add(
+                accounts.charlie,
+                amount,
+                Some(Description::Label(0)),
+                None,
+                None,
+            );
+            assert_eq!(result, Err(AzAirdropError::NotFound("Label".to_string())));
+            // == when amount will cause overflow
+            az_airdrop.to_be_collected = Balance::MAX;
+            // == * it raises an error
+            result = az_airdrop.recipient_add(accounts.charlie, amount, None, None, None);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Overflow("to_be_collected".to_string()))
+            );
+            az_airdrop.to_be_collected = 0;
+            // == when caller has a reserved budget
+            az_airdrop.budgets.insert(accounts.charlie, &amount);
+            az_airdrop.total_reserved = amount;
+            // === when amount exceeds the budget
+            // === * it raises an error
+            result = az_airdrop.recipient_add(accounts.charlie, amount + 1, None, None, None);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::LimitExceeded {
+                    limit: amount,
+                    requested: amount + 1,
+                })
+            );
+            // === when amount is within the budget
+            az_airdrop
+                .recipient_add(accounts.charlie, amount - 1, None, None, None)
+                .unwrap();
+            // === * it draws down the budget without checking the balance
+            assert_eq!(az_airdrop.budget(accounts.charlie), 1);
+            assert_eq!(az_airdrop.total_reserved, 1);
+            assert_eq!(az_airdrop.to_be_collected, amount - 1);
+            // === * it assigns an allocation id to new recipients only
+            assert_eq!(az_airdrop.show(accounts.charlie).unwrap().allocation_id, 0);
+            assert_eq!(az_airdrop.allocations_count, 1);
+            // === * it records the allocation as a sub admin grant
+            assert_eq!(
+                az_airdrop.show(accounts.charlie).unwrap().source,
+                AllocationSource::SubAdminGrant
+            );
+            // === * it removes the budget once it is used up
+            az_airdrop
+                .recipient_add(accounts.charlie, 1, None, None, None)
+                .unwrap();
+            assert_eq!(az_airdrop.show(accounts.charlie).unwrap().allocation_id, 0);
+            assert_eq!(az_airdrop.allocations_count, 1);
+            assert_eq!(az_airdrop.budgets.get(accounts.charlie), None);
+            assert_eq!(az_airdrop.total_reserved, 0);
+            // == when template is given
+            az_airdrop.budgets.insert(accounts.charlie, &amount);
+            az_airdrop.total_reserved = amount;
+            // === when template does not exist
+            // === * it raises an error
+            result = az_airdrop.recipient_add(accounts.django, 1, None, Some(0), None);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Schedule template".to_string()))
+            );
+            // === when template exists
+            az_airdrop.schedule_templates.insert(
+                0,
+                &ScheduleTemplate {
+                    name: "Team".to_string(),
+                    collectable_at_tge_percentage: 10,
+                    cliff_duration: 1,
+                    vesting_duration: 2,
+                },
+            );
+            // ==== when recipient already exists
+            // ==== * it raises an error
+            result = az_airdrop.recipient_add(accounts.charlie, 1, None, Some(0), None);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Template can only be applied to new recipients".to_string(),
+                ))
+            );
+            // ==== when recipient is new
+            // ==== * it applies the template's schedule
+            let recipient: Recipient = az_airdrop
+                .recipient_add(accounts.django, 1, None, Some(0), None)
+                .unwrap();
+            assert_eq!(recipient.collectable_at_tge_percentage, 10);
+            assert_eq!(recipient.cliff_duration, 1);
+            assert_eq!(recipient.vesting_duration, 2);
+            assert_eq!(az_airdrop.show(accounts.django), Ok(recipient));
+            // ==== * it references the template instead of copying it
+            assert_eq!(
+                az_airdrop
+                    .recipients
+                    .get(accounts.django)
+                    .unwrap()
+                    .template_id,
+                Some(0)
+            );
+            // == when start_override is given
+            // === when recipient already exists
+            // === * it raises an error
+            result = az_airdrop.recipient_add(accounts.django, 1, None, None, Some(MOCK_START));
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Start override can only be applied to new recipients".to_string(),
+                ))
+            );
+            // === when start_override is before start
+            // === * it raises an error
+            result = az_airdrop.recipient_add(accounts.eve, 1, None, None, Some(MOCK_START - 1));
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "start_override must be greater than or equal to start".to_string(),
+                ))
+            );
+            // === when start_override is valid
+            // === * it anchors the recipient's vesting to it
+            let recipient: Recipient = az_airdrop
+                .recipient_add(accounts.eve, 1, None, None, Some(MOCK_START + 1))
+                .unwrap();
+            assert_eq!(recipient.start_override, Some(MOCK_START + 1));
+            assert_eq!(az_airdrop.show(accounts.eve), Ok(recipient));
+            // == when amount won't cause overflow and caller has no reserved budget
+            // THE REST NEEDS TO BE IN INK E2E TESTS, SEE BELOW.
+        }
+
+        #[ink::test]
+        fn test_recipient_add_batch() {
+            let (accounts, mut az_airdrop) = init();
+            let entries: Vec<(AccountId, Balance, Option<Description>)> = vec![
+                (accounts.django, 2, None),
+                (accounts.eve, 3, Some(Description::Text("Seed".to_string()))),
+                (accounts.django, 1, None),
+            ];
+            // when caller is not authorised
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let mut result = az_airdrop.recipient_add_batch(entries.clone());
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: None,
+                })
+            );
+            // when caller is authorised
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(az_airdrop.start - 1);
+            // = when allocations are frozen
+            // = * it raises an error
+            az_airdrop.allocations_frozen = true;
+            result = az_airdrop.recipient_add_batch(entries.clone());
+            assert_eq!(result, Err(AzAirdropError::AllocationsFrozen));
+            az_airdrop.allocations_frozen = false;
+            // = when the total amount will cause overflow
+            // = * it raises an error
+            az_airdrop.to_be_collected = Balance::MAX - 5;
+            result = az_airdrop.recipient_add_batch(entries.clone());
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Overflow("to_be_collected".to_string()))
+            );
+            az_airdrop.to_be_collected = 0;
+            // = when caller has a reserved budget
+            az_airdrop.budgets.insert(accounts.bob, &6);
+            az_airdrop.total_reserved = 6;
+            // == when the total amount exceeds the budget
+            // == * it raises an error
+            result = az_airdrop.recipient_add_batch(vec![(accounts.django, 7, None)]);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::LimitExceeded {
+                    limit: 6,
+                    requested: 7,
+                })
+            );
+            // == when the total amount is within the budget
+            // == * it adds every entry
+            result = az_airdrop.recipient_add_batch(entries);
+            let recipients: Vec<Recipient> = result.unwrap();
+            assert_eq!(recipients.len(), 3);
+            assert_eq!(az_airdrop.show(accounts.django).unwrap().total_amount, 3);
+            assert_eq!(az_airdrop.show(accounts.eve).unwrap().total_amount, 3);
+            assert_eq!(az_airdrop.allocations_count, 2);
+            // == * it draws down the budget once for the total
+            assert_eq!(az_airdrop.to_be_collected, 6);
+            assert_eq!(az_airdrop.budgets.get(accounts.bob), None);
+            assert_eq!(az_airdrop.total_reserved, 0);
+            // == when airdrop has started
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(az_airdrop.start);
+            // === when additions after start aren't allowed
+            // === * it raises an error
+            result = az_airdrop.recipient_add_batch(vec![(accounts.frank, 1, None)]);
+            assert_eq!(result, Err(AzAirdropError::AirdropAlreadyStarted));
+            // === when additions after start are allowed
+            az_airdrop.allow_additions_after_start = true;
+            // ==== when an entry is an existing recipient
+            // ==== * it raises an error
+            result = az_airdrop.recipient_add_batch(vec![(accounts.django, 1, None)]);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Only new recipients can be added after start".to_string()
+                ))
+            );
+            // ==== when entries are new recipients
+            // ==== * it adds them vesting from the block timestamp
+            az_airdrop.budgets.insert(accounts.bob, &1);
+            az_airdrop.total_reserved = 1;
+            result = az_airdrop.recipient_add_batch(vec![(accounts.frank, 1, None)]);
+            assert_eq!(result.unwrap()[0].start_override, Some(az_airdrop.start));
+            assert_eq!(
+                az_airdrop.show(accounts.frank).unwrap().start_override,
+                Some(az_airdrop.start)
+            );
+            // = when caller has no reserved budget
+            // THE REST NEEDS TO BE IN INK E2E TESTS
+        }
+
+        #[ink::test]
+        fn test_acquire_token_max() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let mut result = az_airdrop.acquire_token_max(accounts.charlie);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: None,
+                })
+            );
+            // when called by admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // = when airdrop has started
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(az_airdrop.start);
+            // = * it raises an error
+            result = az_airdrop.acquire_token_max(accounts.charlie);
+            assert_eq!(result, Err(AzAirdropError::AirdropAlreadyStarted));
+            // = when airdrop has not started
+            // THE REST NEEDS TO HAPPEN IN INTEGRATION TESTS
+        }
+
+        #[ink::test]
+        fn test_address_change_accept() {
+            let (accounts, mut az_airdrop) = init();
+            let recipient: Recipient = Recipient {
+                allocation_id: 3,
+                total_amount: 100,
+                collected: 10,
+                collectable_at_tge_percentage: 100,
+                cliff_duration: 0,
+                vesting_duration: 0,
+                schedule_phases: None,
+                curve: Curve::Linear,
+                revocable: false,
+                external_ref: None,
+                release_interval: 0,
+                start_override: None,
+                collectable_at_cliff_percentage: 0,
+                source: AllocationSource::AdminGrant,
+                template_id: None,
+            };
+            az_airdrop.insert_recipient(accounts.django, &recipient);
+            set_caller::<DefaultEnvironment>(accounts.eve);
+            // when there is no proposal for caller
+            // * it raises an error
+            let mut result = az_airdrop.address_change_accept(accounts.django);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound(
+                    "Address change proposal".to_string()
+                ))
+            );
+            // when there is a proposal for caller
+            az_airdrop
+                .address_change_proposals
+                .insert(accounts.django, &accounts.eve);
+            // = when caller has become a recipient since
+            // = * it raises an error
+            az_airdrop.recipients.insert(accounts.eve, &recipient);
+            result = az_airdrop.address_change_accept(accounts.django);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "New address is already a recipient".to_string()
+                ))
+            );
+            az_airdrop.recipients.remove(accounts.eve);
+            // = when caller is not a recipient
+            // = * it moves the allocation including collected to caller
+            result = az_airdrop.address_change_accept(accounts.django);
+            assert_eq!(result, Ok(recipient.clone()));
+            assert_eq!(az_airdrop.show(accounts.eve), Ok(recipient));
+            assert_eq!(
+                az_airdrop.show(accounts.django),
+                Err(AzAirdropError::NotFound("Recipient".to_string()))
+            );
+            // = * it removes the proposal
+            assert_eq!(
+                az_airdrop.address_change_proposals.get(accounts.django),
+                None
+            );
+        }
+
+        #[ink::test]
+        fn test_address_change_propose() {
+            let (accounts, mut az_airdrop) = init();
+            set_caller::<DefaultEnvironment>(accounts.django);
+            // when caller is not a recipient
+            // * it raises an error
+            let mut result = az_airdrop.address_change_propose(accounts.eve);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Recipient".to_string()))
+            );
+            // when caller is a recipient
+            let recipient: Recipient = Recipient {
+                allocation_id: 0,
+                total_amount: 100,
+                collected: 0,
+                collectable_at_tge_percentage: 100,
+                cliff_duration: 0,
+                vesting_duration: 0,
+                schedule_phases: None,
+                curve: Curve::Linear,
+                revocable: false,
+                external_ref: None,
+                release_interval: 0,
+                start_override: None,
+                collectable_at_cliff_percentage: 0,
+                source: AllocationSource::AdminGrant,
+                template_id: None,
+            };
+            az_airdrop.recipients.insert(accounts.django, &recipient);
+            // = when new address is caller
+            // = * it raises an error
+            result = az_airdrop.address_change_propose(accounts.django);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "New address must be different".to_string()
+                ))
+            );
+            // = when new address is already a recipient
+            // = * it raises an error
+            az_airdrop.recipients.insert(accounts.eve, &recipient);
+            result = az_airdrop.address_change_propose(accounts.eve);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "New address is already a recipient".to_string()
+                ))
+            );
+            // = when new address is not a recipient
+            // = * it records the proposal without moving anything
+            az_airdrop.address_change_propose(accounts.frank).unwrap();
+            assert_eq!(
+                az_airdrop.address_change_proposals.get(accounts.django),
+                Some(accounts.frank)
+            );
+            assert_eq!(az_airdrop.show(accounts.django), Ok(recipient));
+        }
+
+        #[ink::test]
+        fn test_claim_with_proof() {
+            let (accounts, mut az_airdrop) = init();
+            let amount: Balance = 5;
+            let django_leaf: MerkleHash = merkle::leaf(0, accounts.django, amount, 100, 0, 0);
+            let eve_leaf: MerkleHash = merkle::leaf(1, accounts.eve, amount, 100, 0, 0);
+            let frank_leaf: MerkleHash = merkle::leaf(2, accounts.frank, amount, 100, 0, 0);
+            let (first, second): (MerkleHash, MerkleHash) = if django_leaf <= eve_leaf {
+                (django_leaf, eve_leaf)
+            } else {
+                (eve_leaf, django_leaf)
+            };
+            let mut pair: Vec<u8> = first.to_vec();
+            pair.extend_from_slice(&second);
+            let mut django_eve: MerkleHash = MerkleHash::default();
+            ink::env::hash_bytes::<ink::env::hash::Blake2x256>(&pair, &mut django_eve);
+            let (first, second): (MerkleHash, MerkleHash) = if django_eve <= frank_leaf {
+                (django_eve, frank_leaf)
+            } else {
+                (frank_leaf, django_eve)
+            };
+            let mut pair: Vec<u8> = first.to_vec();
+            pair.extend_from_slice(&second);
+            let mut root: MerkleHash = MerkleHash::default();
+            ink::env::hash_bytes::<ink::env::hash::Blake2x256>(&pair, &mut root);
+            let django_proof: Vec<MerkleHash> = vec![eve_leaf, frank_leaf];
+            set_caller::<DefaultEnvironment>(accounts.django);
+            // when merkle root has not been set
+            // * it raises an error
+            let mut result =
+                az_airdrop.claim_with_proof(0, amount, 100, 0, 0, django_proof.clone());
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Merkle root".to_string()))
+            );
+            // when merkle root has been set
+            az_airdrop.merkle_root = Some(root);
+            // = when proof is invalid
+            // = * it raises an error
+            result = az_airdrop.claim_with_proof(0, amount + 1, 100, 0, 0, django_proof.clone());
+            assert_eq!(result, Err(AzAirdropError::InvalidProof));
+            result = az_airdrop.claim_with_proof(0, amount, 100, 0, 0, vec![frank_leaf]);
+            assert_eq!(result, Err(AzAirdropError::InvalidProof));
+            // = when schedule in the leaf differs
+            // = * it raises an error
+            result = az_airdrop.claim_with_proof(0, amount, 20, 0, 10, django_proof.clone());
+            assert_eq!(result, Err(AzAirdropError::InvalidProof));
+            // = when proof is valid
+            // = * it verifies
+            assert!(merkle::verify(root, django_leaf, &django_proof));
+            assert!(merkle::verify(root, frank_leaf, &[django_eve]));
+            // == when caller is already a recipient
+            // == * it raises an error
+            az_airdrop.recipients.insert(
+                accounts.django,
+                &Recipient {
+                    allocation_id: 0,
+                    total_amount: 1,
+                    collected: 0,
+                    collectable_at_tge_percentage: 100,
+                    cliff_duration: 0,
+                    vesting_duration: 0,
+                    schedule_phases: None,
+                    curve: Curve::Linear,
+                    revocable: false,
+                    external_ref: None,
+                    release_interval: 0,
+                    start_override: None,
+                    collectable_at_cliff_percentage: 0,
+                    source: AllocationSource::AdminGrant,
+                    template_id: None,
+                },
+            );
+            result = az_airdrop.claim_with_proof(0, amount, 100, 0, 0, django_proof.clone());
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Caller is already a recipient".to_string()
+                ))
+            );
+            az_airdrop.recipients.remove(accounts.django);
+            // == when allocations are frozen
+            // == * it raises an error
+            az_airdrop.allocations_frozen = true;
+            result = az_airdrop.claim_with_proof(0, amount, 100, 0, 0, django_proof.clone());
+            assert_eq!(result, Err(AzAirdropError::AllocationsFrozen));
+            az_airdrop.allocations_frozen = false;
+            // == when the leaf can be allocated
+            // == * it allocates the leaf's amount with the leaf's schedule
+            az_airdrop.budgets.insert(accounts.django, &amount);
+            az_airdrop.total_reserved = amount;
+            az_airdrop.merkle_unclaimed = 3 * amount;
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(az_airdrop.start - 1);
+            result = az_airdrop.claim_with_proof(0, amount, 100, 0, 0, django_proof);
+            assert_eq!(result, Err(AzAirdropError::AmountZero));
+            assert_eq!(
+                az_airdrop.show(accounts.django).unwrap().total_amount,
+                amount
+            );
+            assert_eq!(az_airdrop.to_be_collected, amount);
+            // == * it moves the leaf out of what the root committed
+            assert_eq!(az_airdrop.merkle_unclaimed, 2 * amount);
+            // == * it records the leaf as claimed under the current root
+            assert_eq!(az_airdrop.merkle_claims.get((0, 0)), Some(amount));
+            // == when the leaf has been claimed under the current root
+            // == * it collects without checking the proof
+            result = az_airdrop.claim_with_proof(0, amount, 100, 0, 0, vec![]);
+            assert_eq!(result, Err(AzAirdropError::AmountZero));
+            assert_eq!(
+                az_airdrop.show(accounts.django).unwrap().total_amount,
+                amount
+            );
+            // == when a MerkleClaim source template is set
+            // == * it references the template instead of the leaf's schedule
+            az_airdrop.schedule_templates.insert(
+                0,
+                &ScheduleTemplate {
+                    name: "Merkle".to_string(),
+                    collectable_at_tge_percentage: 10,
+                    cliff_duration: 5,
+                    vesting_duration: 50,
+                },
+            );
+            az_airdrop
+                .source_templates
+                .insert(AllocationSource::MerkleClaim, &0);
+            set_caller::<DefaultEnvironment>(accounts.frank);
+            az_airdrop.budgets.insert(accounts.frank, &amount);
+            az_airdrop.total_reserved = amount;
+            result = az_airdrop.claim_with_proof(2, amount, 100, 0, 0, vec![django_eve]);
+            assert_eq!(result, Err(AzAirdropError::AmountZero));
+            let recipient: Recipient = az_airdrop.show(accounts.frank).unwrap();
+            assert_eq!(recipient.template_id, Some(0));
+            assert_eq!(recipient.collectable_at_tge_percentage, 10);
+            set_caller::<DefaultEnvironment>(accounts.django);
+            // == when a rotated root corrects caller's amount
+            // == * it tops the allocation up by the difference
+            let corrected_leaf: MerkleHash =
+                merkle::leaf(0, accounts.django, amount + 3, 100, 0, 0);
+            az_airdrop.merkle_root = Some(corrected_leaf);
+            az_airdrop.merkle_root_version = 1;
+            az_airdrop.budgets.insert(accounts.django, &3);
+            az_airdrop.total_reserved = 3;
+            result = az_airdrop.claim_with_proof(0, amount + 3, 100, 0, 0, vec![]);
+            assert_eq!(result, Err(AzAirdropError::AmountZero));
+            assert_eq!(
+                az_airdrop.show(accounts.django).unwrap().total_amount,
+                amount + 3
+            );
+            assert_eq!(az_airdrop.to_be_collected, 2 * amount + 3);
+            assert_eq!(az_airdrop.merkle_claims.get((1, 0)), Some(amount + 3));
+            // == when the claimed allocation has moved to a new address
+            az_airdrop.address_change_propose(accounts.eve).unwrap();
+            set_caller::<DefaultEnvironment>(accounts.eve);
+            az_airdrop.address_change_accept(accounts.django).unwrap();
+            // === when the old address claims the leaf again
+            // === * it doesn't allocate it again
+            set_caller::<DefaultEnvironment>(accounts.django);
+            result = az_airdrop.claim_with_proof(0, amount + 3, 100, 0, 0, vec![]);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Recipient".to_string()))
+            );
+            assert!(!az_airdrop.recipients.contains(accounts.django));
+            assert_eq!(az_airdrop.to_be_collected, 2 * amount + 3);
+            // === when the new address claims the leaf
+            // === * it collects without allocating it again
+            set_caller::<DefaultEnvironment>(accounts.eve);
+            result = az_airdrop.claim_with_proof(0, amount + 3, 100, 0, 0, vec![]);
+            assert_eq!(result, Err(AzAirdropError::AmountZero));
+            assert_eq!(
+                az_airdrop.show(accounts.eve).unwrap().total_amount,
+                amount + 3
+            );
+            assert_eq!(az_airdrop.to_be_collected, 2 * amount + 3);
+            // THE REST NEEDS TO HAPPEN IN INTEGRATION TESTS
+        }
+
+        #[ink::test]
+        fn test_collect() {
+            let (accounts, mut az_airdrop) = init();
+            // when recipient with caller's address does not exist
+            // * it raises an error
+            let mut result = az_airdrop.collect();
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Recipient".to_string()))
+            );
+            // when recipient with caller's address exists
+            az_airdrop.recipients.insert(
+                accounts.bob,
+                &Recipient {
+                    allocation_id: 0,
+                    total_amount: 5,
+                    collected: 0,
+                    collectable_at_tge_percentage: 100,
+                    cliff_duration: 0,
+                    vesting_duration: 0,
+                    schedule_phases: None,
+                    curve: Curve::Linear,
+                    revocable: false,
+                    external_ref: None,
+                    release_interval: 0,
+                    start_override: None,
+                    collectable_at_cliff_percentage: 0,
+                    source: AllocationSource::AdminGrant,
+                    template_id: None,
+                },
+            );
+            // = when claims are disabled
+            // = * it raises an error
+            az_airdrop.claims_enabled = false;
+            result = az_airdrop.collect();
+            assert_eq!(result, Err(AzAirdropError::Paused));
+            az_airdrop.claims_enabled = true;
+            // = when collectable amount is zero
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(
+                az_airdrop.start - 1,
+            );
+            // = * it raises an error
+            result = az_airdrop.collect();
+            assert_eq!(result, Err(AzAirdropError::AmountZero));
+            // = when collectable amount is positive
+            // THE REST NEEDS TO HAPPEN IN INTEGRATION TESTS
+        }
+
+        #[ink::test]
+        fn test_collect_for() {
+            let (accounts, mut az_airdrop) = init();
+            let org_id: u32 = az_airdrop.organization_create(accounts.charlie).unwrap();
+            // when member is not part of an organization
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let mut result = az_airdrop.collect_for(accounts.django);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound(
+                    "Organization membership".to_string()
+                ))
+            );
+            // when member is part of an organization
+            az_airdrop
+                .organization_members_add(org_id, accounts.django)
+                .unwrap();
+            // = when called by non-owner
+            // = * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            result = az_airdrop.collect_for(accounts.django);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.bob,
+                    expected: Some(accounts.charlie),
+                })
+            );
+            // = when called by owner
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            // == when member has not opted in
+            // == * it raises an error
+            result = az_airdrop.collect_for(accounts.django);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Member has not opted in".to_string()
+                ))
+            );
+            // == when member has opted in
+            set_caller::<DefaultEnvironment>(accounts.django);
+            az_airdrop.collect_for_opt_in(true).unwrap();
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            // === when member does not have an allocation
+            // === * it raises an error
+            result = az_airdrop.collect_for(accounts.django);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Recipient".to_string()))
+            );
+            // === when member has an allocation
+            // THE REST NEEDS TO HAPPEN IN INTEGRATION TESTS
+        }
+
+        #[ink::test]
+        fn test_collect_for_opt_in() {
+            let (accounts, mut az_airdrop) = init();
+            // when opting in
+            // = when caller is not part of an organization
+            // = * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.django);
+            let result = az_airdrop.collect_for_opt_in(true);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound(
+                    "Organization membership".to_string()
+                ))
+            );
+            // = when caller is part of an organization
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            let org_id: u32 = az_airdrop.organization_create(accounts.charlie).unwrap();
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            az_airdrop
+                .organization_members_add(org_id, accounts.django)
+                .unwrap();
+            set_caller::<DefaultEnvironment>(accounts.django);
+            az_airdrop.collect_for_opt_in(true).unwrap();
+            // = * it records the opt in against the organization
+            assert_eq!(
+                az_airdrop.collect_for_opt_ins.get(accounts.django),
+                Some(org_id)
+            );
+            // when opting out
+            az_airdrop.collect_for_opt_in(false).unwrap();
+            // * it removes the opt in
+            assert_eq!(az_airdrop.collect_for_opt_ins.get(accounts.django), None);
+        }
+
+        #[ink::test]
+        fn test_collect_to() {
+            let (accounts, mut az_airdrop) = init();
+            // when recipient with caller's address does not exist
+            // * it raises an error
+            let mut result = az_airdrop.collect_to(accounts.eve);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Recipient".to_string()))
+            );
+            // when recipient with caller's address exists
+            az_airdrop.recipients.insert(
+                accounts.bob,
+                &Recipient {
+                    allocation_id: 0,
+                    total_amount: 5,
+                    collected: 0,
+                    collectable_at_tge_percentage: 100,
+                    cliff_duration: 0,
+                    vesting_duration: 0,
+                    schedule_phases: None,
+                    curve: Curve::Linear,
+                    revocable: false,
+                    external_ref: None,
+                    release_interval: 0,
+                    start_override: None,
+                    collectable_at_cliff_percentage: 0,
+                    source: AllocationSource::AdminGrant,
+                    template_id: None,
+                },
+            );
+            // = when in compliance mode
+            az_airdrop.compliance_mode = true;
+            // == when destination is not a registered payout address
+            // == * it raises an error
+            result = az_airdrop.collect_to(accounts.eve);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Payout address is not allowed".to_string(),
+                ))
+            );
+            // == when destination is an active payout address
+            az_airdrop
+                .payout_addresses
+                .insert((accounts.bob, accounts.eve), &0);
+            // THE REST NEEDS TO HAPPEN IN INTEGRATION TESTS
+        }
+
+        #[ink::test]
+        fn test_collect_with_memo() {
+            let (accounts, mut az_airdrop) = init();
+            // when memo is empty
+            // * it raises an error
+            let mut result = az_airdrop.collect_with_memo(vec![]);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(format!(
+                    "Memo must be between 1 and {MAX_MEMO_LENGTH} bytes"
+                )))
+            );
+            // when memo is too long
+            // * it raises an error
+            result = az_airdrop.collect_with_memo(vec![1; MAX_MEMO_LENGTH + 1]);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(format!(
+                    "Memo must be between 1 and {MAX_MEMO_LENGTH} bytes"
+                )))
+            );
+            // when memo is valid
+            // = when recipient with caller's address does not exist
+            // = * it raises an error
+            result = az_airdrop.collect_with_memo(vec![1; MAX_MEMO_LENGTH]);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Recipient".to_string()))
+            );
+            // = when recipient with caller's address exists
+            az_airdrop.recipients.insert(
+                accounts.bob,
+                &Recipient {
+                    allocation_id: 0,
+                    total_amount: 5,
+                    collected: 0,
+                    collectable_at_tge_percentage: 100,
+                    cliff_duration: 0,
+                    vesting_duration: 0,
+                    schedule_phases: None,
+                    curve: Curve::Linear,
+                    revocable: false,
+                    external_ref: None,
+                    release_interval: 0,
+                    start_override: None,
+                    collectable_at_cliff_percentage: 0,
+                    source: AllocationSource::AdminGrant,
+                    template_id: None,
+                },
+            );
+            // THE REST NEEDS TO HAPPEN IN INTEGRATION TESTS
+        }
+
+        #[ink::test]
+        fn test_distribute() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by non-admin or non-sub-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let mut result = az_airdrop.distribute(accounts.django);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: None,
+                })
+            );
+            // when called by an admin or sub-admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // = when recipient does not exist
+            // = * it raises an error
+            result = az_airdrop.distribute(accounts.django);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Recipient".to_string()))
+            );
+            // = when recipient exists
+            az_airdrop.recipients.insert(
+                accounts.django,
+                &Recipient {
+                    allocation_id: 0,
+                    total_amount: 5,
+                    collected: 0,
+                    collectable_at_tge_percentage: 100,
+                    cliff_duration: 0,
+                    vesting_duration: 0,
+                    schedule_phases: None,
+                    curve: Curve::Linear,
+                    revocable: false,
+                    external_ref: None,
+                    release_interval: 0,
+                    start_override: None,
+                    collectable_at_cliff_percentage: 0,
+                    source: AllocationSource::AdminGrant,
+                    template_id: None,
+                },
+            );
+            // == when collectable amount is zero
+            // == * it raises an error
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(az_airdrop.start - 1);
+            result = az_airdrop.distribute(accounts.django);
+            assert_eq!(result, Err(AzAirdropError::AmountZero));
+            // == when collectable amount is positive
+            // THE REST NEEDS TO HAPPEN IN INTEGRATION TESTS
+        }
+
+        #[ink::test]
+        fn test_distribute_batch() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by non-admin or non-sub-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let result = az_airdrop.distribute_batch(vec![accounts.django]);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: None,
+                })
+            );
+            // when called by an admin or sub-admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            az_airdrop.recipients.insert(
+                accounts.django,
+                &Recipient {
+                    allocation_id: 0,
+                    total_amount: 5,
+                    collected: 0,
+                    collectable_at_tge_percentage: 100,
+                    cliff_duration: 0,
+                    vesting_duration: 0,
+                    schedule_phases: None,
+                    curve: Curve::Linear,
+                    revocable: false,
+                    external_ref: None,
+                    release_interval: 0,
+                    start_override: None,
+                    collectable_at_cliff_percentage: 0,
+                    source: AllocationSource::AdminGrant,
+                    template_id: None,
+                },
+            );
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(az_airdrop.start - 1);
+            // = when there are more addresses than the limit
+            // = * it raises an error
+            assert_eq!(
+                az_airdrop.distribute_batch(vec![
+                    accounts.django;
+                    MAX_RECIPIENTS_LIST_LIMIT as usize + 1
+                ]),
+                Err(AzAirdropError::LimitExceeded {
+                    limit: MAX_RECIPIENTS_LIST_LIMIT.into(),
+                    requested: u128::from(MAX_RECIPIENTS_LIST_LIMIT) + 1,
+                })
+            );
+            // = when addresses are within the limit
+            // = * it returns a result for each address
+            assert_eq!(
+                az_airdrop.distribute_batch(vec![accounts.django, accounts.eve]),
+                Ok(vec![
+                    Err(AzAirdropError::AmountZero),
+                    Err(AzAirdropError::NotFound("Recipient".to_string())),
+                ])
+            );
+            // = when an address has a positive collectable amount
+            // THE REST NEEDS TO HAPPEN IN INTEGRATION TESTS
+        }
+
+        #[ink::test]
+        fn test_distribute_range() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by non-admin or non-sub-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let result = az_airdrop.distribute_range(0, 10);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: None,
+                })
+            );
+            // when called by an admin or sub-admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            let addresses: Vec<AccountId> = vec![accounts.django, accounts.eve, accounts.frank];
+            for address in addresses.iter() {
+                az_airdrop.insert_recipient(
+                    *address,
+                    &Recipient {
+                        allocation_id: 0,
+                        total_amount: 5,
+                        collected: 0,
+                        collectable_at_tge_percentage: 100,
+                        cliff_duration: 0,
+                        vesting_duration: 0,
+                        schedule_phases: None,
+                        curve: Curve::Linear,
+                        revocable: false,
+                        external_ref: None,
+                        release_interval: 0,
+                        start_override: None,
+                        collectable_at_cliff_percentage: 0,
+                        source: AllocationSource::AdminGrant,
+                        template_id: None,
+                    },
+                );
+            }
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(az_airdrop.start - 1);
+            let amount_is_zero = || -> Result<Balance> { Err(AzAirdropError::AmountZero) };
+            // = when distribution seed is not set
+            // = * it walks the range in index order
+            assert_eq!(
+                az_airdrop.distribute_range(1, 10),
+                Ok(vec![
+                    (accounts.eve, amount_is_zero()),
+                    (accounts.frank, amount_is_zero()),
+                ])
+            );
+            // = when distribution seed is set
+            // = * it walks the range in the seed's order
+            az_airdrop.update_distribution_seed(Some([1; 32])).unwrap();
+            assert_eq!(
+                az_airdrop.distribute_range(0, 10),
+                Ok(vec![
+                    (accounts.frank, amount_is_zero()),
+                    (accounts.eve, amount_is_zero()),
+                    (accounts.django, amount_is_zero()),
+                ])
+            );
+            // = when an address has a positive collectable amount
+            // THE REST NEEDS TO HAPPEN IN INTEGRATION TESTS
+        }
+
+        #[ink::test]
+        fn test_escrow_deposit() {
+            let (_accounts, mut az_airdrop) = init();
+            // when escrow deadline is not set
+            // * it raises an error
+            let mut result = az_airdrop.escrow_deposit(1);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Escrow deadline".to_string()))
+            );
+            // when escrow deadline is set
+            az_airdrop.escrow_deadline = Some(MOCK_START);
+            // = when escrow deadline has passed
+            // = * it raises an error
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(MOCK_START);
+            result = az_airdrop.escrow_deposit(1);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Escrow deadline has passed".to_string()
+                ))
+            );
+            // = when escrow deadline has not passed
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(MOCK_START - 1);
+            // == when escrow is finalized
+            // == * it raises an error
+            az_airdrop.escrow_finalized = true;
+            result = az_airdrop.escrow_deposit(1);
+            assert_eq!(result, Err(AzAirdropError::EscrowFinalized));
+            az_airdrop.escrow_finalized = false;
+            // == when amount is zero
+            // == * it raises an error
+            result = az_airdrop.escrow_deposit(0);
+            assert_eq!(result, Err(AzAirdropError::AmountZero));
+            // == when amount is positive
+            // THE REST NEEDS TO HAPPEN IN INTEGRATION TESTS
+        }
+
+        #[ink::test]
+        fn test_finalize() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let mut result = az_airdrop.finalize();
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+            // when called by admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // = when escrow deadline is not set
+            // = * it raises an error
+            result = az_airdrop.finalize();
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Escrow deadline".to_string()))
+            );
+            // = when escrow is open
+            // = * it finalizes the escrow
+            az_airdrop.escrow_deadline = Some(MOCK_START);
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(MOCK_START - 1);
+            az_airdrop.finalize().unwrap();
+            assert!(az_airdrop.config().escrow_finalized);
+            // = when escrow is finalized
+            // = * it raises an error
+            result = az_airdrop.finalize();
+            assert_eq!(result, Err(AzAirdropError::EscrowFinalized));
+        }
+
+        #[ink::test]
+        fn test_harden_schedule() {
+            let (accounts, mut az_airdrop) = init();
+            set_caller::<DefaultEnvironment>(accounts.django);
+            // when caller is not a recipient
+            // * it raises an error
+            let mut result = az_airdrop.harden_schedule(1, 1);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Recipient".to_string()))
+            );
+            // when caller is a recipient
+            let mut recipient: Recipient = Recipient {
+                allocation_id: 0,
+                total_amount: 100,
+                collected: 0,
+                collectable_at_tge_percentage: 100,
+                cliff_duration: 0,
+                vesting_duration: 0,
+                schedule_phases: None,
+                curve: Curve::Linear,
+                revocable: false,
+                external_ref: None,
+                release_interval: 0,
+                start_override: None,
+                collectable_at_cliff_percentage: 0,
+                source: AllocationSource::AdminGrant,
+                template_id: None,
+            };
+            az_airdrop.recipients.insert(accounts.django, &recipient);
+            // = when nothing is extended
+            // = * it raises an error
+            result = az_airdrop.harden_schedule(0, 0);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "extra_cliff or extra_vesting must be greater than 0".to_string()
+                ))
+            );
+            // = when the result breaks the schedule rules
+            // = * it raises an error
+            result = az_airdrop.harden_schedule(1, 0);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "cliff_duration and vesting_duration must be 0 when collectable_tge_percentage is 100".to_string()
+                ))
+            );
+            // = when recipient has schedule phases
+            // = * it raises an error
+            recipient.schedule_phases = Some(vec![SchedulePhase::Instant { at: 0, bps: 10_000 }]);
+            az_airdrop.recipients.insert(accounts.django, &recipient);
+            result = az_airdrop.harden_schedule(1, 1);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Schedules with phases can't be hardened".to_string()
+                ))
+            );
+            // = when the result is valid
+            recipient.schedule_phases = None;
+            recipient.collectable_at_tge_percentage = 20;
+            recipient.cliff_duration = 10;
+            recipient.vesting_duration = 100;
+            az_airdrop.recipients.insert(accounts.django, &recipient);
+            // = * it extends the durations
+            result = az_airdrop.harden_schedule(5, 50);
+            let hardened: Recipient = result.unwrap();
+            assert_eq!(hardened.cliff_duration, 15);
+            assert_eq!(hardened.vesting_duration, 150);
+            assert_eq!(az_airdrop.show(accounts.django), Ok(hardened));
+            // = * it vests later than before
+            assert_eq!(
+                az_airdrop.vested_at(accounts.django, MOCK_START + 10 + 100),
+                Ok(20 + 80 * 95 / 150)
+            );
+        }
+
+        #[ink::test]
+        fn test_invalidate_merkle_root() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let result = az_airdrop.invalidate_merkle_root();
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+            // when called by admin
+            // * it removes the current and pending roots
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            az_airdrop.merkle_root = Some([1; 32]);
+            az_airdrop.pending_merkle_root = Some(([2; 32], MOCK_START));
+            az_airdrop.merkle_unclaimed = 10;
+            az_airdrop.pending_merkle_unclaimed = 15;
+            az_airdrop.invalidate_merkle_root().unwrap();
+            assert_eq!(az_airdrop.config().merkle_root, None);
+            assert_eq!(az_airdrop.config().pending_merkle_root, None);
+            // * it releases what the roots committed
+            assert_eq!(az_airdrop.merkle_committed(), 0);
+        }
+
+        #[ink::test]
+        fn test_labels_remove() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by admin
+            // = when label does not exist
+            // = * it raises an error
+            let mut result = az_airdrop.labels_remove(0);
+            assert_eq!(result, Err(AzAirdropError::NotFound("Label".to_string())));
+            // = when label exists
+            az_airdrop.labels_set(0, "Seed".to_string()).unwrap();
+            az_airdrop.labels_remove(0).unwrap();
+            // = * it removes the label and its reverse lookup
+            assert_eq!(az_airdrop.labels.get(0), None);
+            assert_eq!(az_airdrop.label_ids.get("Seed".to_string()), None);
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            result = az_airdrop.labels_remove(0);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+        }
+
+        #[ink::test]
+        fn test_labels_set() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by admin
+            // = when label text is not used
+            az_airdrop.labels_set(0, "Seed".to_string()).unwrap();
+            // = * it sets the label
+            assert_eq!(az_airdrop.label(0), Ok("Seed".to_string()));
+            // = * descriptions are normalized to the label id
+            assert_eq!(
+                az_airdrop.normalize_description(Some(Description::Text("Seed".to_string()))),
+                Ok((Some(0), None))
+            );
+            assert_eq!(
+                az_airdrop.normalize_description(Some(Description::Text("Other".to_string()))),
+                Ok((None, Some("Other".to_string())))
+            );
+            assert_eq!(
+                az_airdrop.normalize_description(Some(Description::Label(0))),
+                Ok((Some(0), None))
+            );
+            // = when label text is used by another label
+            // = * it raises an error
+            let mut result = az_airdrop.labels_set(1, "Seed".to_string());
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Label already exists".to_string()
+                ))
+            );
+            // = when replacing the text of an existing label
+            az_airdrop.labels_set(0, "Private".to_string()).unwrap();
+            // = * it removes the previous reverse lookup
+            assert_eq!(az_airdrop.label_ids.get("Seed".to_string()), None);
+            assert_eq!(az_airdrop.label_ids.get("Private".to_string()), Some(0));
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            result = az_airdrop.labels_set(1, "Public".to_string());
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+        }
+
+        #[ink::test]
+        fn test_organization_create() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let result = az_airdrop.organization_create(accounts.charlie);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+            // when called by admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // * it creates the organization with an incrementing id
+            assert_eq!(az_airdrop.organization_create(accounts.charlie).unwrap(), 0);
+            assert_eq!(az_airdrop.organization_create(accounts.django).unwrap(), 1);
+            assert_eq!(
+                az_airdrop.organization_show(1).unwrap(),
+                Organization {
+                    owner: accounts.django,
+                    members: BoundedVec::default(),
+                }
+            );
+        }
+
+        #[ink::test]
+        fn test_organization_members_add() {
+            let (accounts, mut az_airdrop) = init();
+            let org_id: u32 = az_airdrop.organization_create(accounts.charlie).unwrap();
+            // when called by non-owner
+            // * it raises an error
+            let mut result = az_airdrop.organization_members_add(org_id, accounts.django);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.bob,
+                    expected: Some(accounts.charlie),
+                })
+            );
+            // when called by owner
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            // = when address is not a member of an organization
+            // = * it adds the address to the organization
+            result = az_airdrop.organization_members_add(org_id, accounts.django);
+            assert_eq!(result, Ok(vec![accounts.django]));
+            assert_eq!(
+                az_airdrop.organization_memberships.get(accounts.django),
+                Some(org_id)
+            );
+            // = when address is already a member of an organization
+            // = * it raises an error
+            result = az_airdrop.organization_members_add(org_id, accounts.django);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Already a member of an organization".to_string()
+                ))
+            );
+            // = when max_organization_members has been reached
+            az_airdrop.max_organization_members = 1;
+            // = * it raises an error
+            result = az_airdrop.organization_members_add(org_id, accounts.eve);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::CapacityExceeded(
+                    "Organization members".to_string()
+                ))
+            );
+        }
+
+        #[ink::test]
+        fn test_organization_members_remove() {
+            let (accounts, mut az_airdrop) = init();
+            let org_id: u32 = az_airdrop.organization_create(accounts.charlie).unwrap();
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            // when called by owner
+            // = when address is not a member of the organization
+            // = * it raises an error
+            let mut result = az_airdrop.organization_members_remove(org_id, accounts.django);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Not a member of organization".to_string()
+                ))
+            );
+            // = when address is a member of the organization
+            az_airdrop
+                .organization_members_add(org_id, accounts.django)
+                .unwrap();
+            set_caller::<DefaultEnvironment>(accounts.django);
+            az_airdrop.collect_for_opt_in(true).unwrap();
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            result = az_airdrop.organization_members_remove(org_id, accounts.django);
+            // = * it removes the address from the organization and its opt in
+            assert_eq!(result, Ok(vec![]));
+            assert_eq!(
+                az_airdrop.organization_memberships.get(accounts.django),
+                None
+            );
+            assert_eq!(az_airdrop.collect_for_opt_ins.get(accounts.django), None);
+            // when called by non-owner
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            result = az_airdrop.organization_members_remove(org_id, accounts.django);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.bob,
+                    expected: Some(accounts.charlie),
+                })
+            );
+        }
+
+        #[ink::test]
+        fn test_organization_update_owner() {
+            let (accounts, mut az_airdrop) = init();
+            let org_id: u32 = az_airdrop.organization_create(accounts.charlie).unwrap();
+            // when called by non-owner
+            // * it raises an error
+            let result = az_airdrop.organization_update_owner(org_id, accounts.django);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.bob,
+                    expected: Some(accounts.charlie),
+                })
+            );
+            // when called by owner
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            az_airdrop
+                .organization_update_owner(org_id, accounts.django)
+                .unwrap();
+            // * it updates the owner
+            assert_eq!(
+                az_airdrop.organization_show(org_id).unwrap().owner,
+                accounts.django
+            );
+        }
+
+        #[ink::test]
+        fn test_register_observer() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let result = az_airdrop.register_observer(Some(accounts.django));
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+            // when called by admin
+            // * it updates observer
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            az_airdrop.register_observer(Some(accounts.django)).unwrap();
+            assert_eq!(az_airdrop.config().observer, Some(accounts.django));
+            az_airdrop.register_observer(None).unwrap();
+            assert_eq!(az_airdrop.config().observer, None);
+        }
+
+        #[ink::test]
+        fn test_register_payout_address() {
+            let (accounts, mut az_airdrop) = init();
+            set_caller::<DefaultEnvironment>(accounts.django);
+            // when caller is not a recipient
+            // * it raises an error
+            let mut result = az_airdrop.register_payout_address(accounts.eve);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Recipient".to_string()))
+            );
+            // when caller is a recipient
+            az_airdrop.recipients.insert(
+                accounts.django,
+                &Recipient {
+                    allocation_id: 0,
+                    total_amount: 100,
+                    collected: 0,
+                    collectable_at_tge_percentage: 100,
+                    cliff_duration: 0,
+                    vesting_duration: 0,
+                    schedule_phases: None,
+                    curve: Curve::Linear,
+                    revocable: false,
+                    external_ref: None,
+                    release_interval: 0,
+                    start_override: None,
+                    collectable_at_cliff_percentage: 0,
+                    source: AllocationSource::AdminGrant,
+                    template_id: None,
+                },
+            );
+            // * it registers the address to become active after the cooldown
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(MOCK_START);
+            result = az_airdrop.register_payout_address(accounts.eve);
+            assert_eq!(result, Ok(MOCK_START + PAYOUT_ADDRESS_COOLDOWN));
+            // = when address is already registered
+            // = * it raises an error
+            result = az_airdrop.register_payout_address(accounts.eve);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Payout address is already registered".to_string()
+                ))
+            );
+        }
+
+        #[ink::test]
+        fn test_rescue_native() {
+            let (accounts, mut az_airdrop) = init();
+            let contract_address: AccountId = ink::env::test::callee::<DefaultEnvironment>();
+            ink::env::test::set_account_balance::<DefaultEnvironment>(contract_address, 100);
+            ink::env::test::set_account_balance::<DefaultEnvironment>(accounts.eve, 0);
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let result = az_airdrop.rescue_native(accounts.charlie, 1);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+            // when called by admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            az_airdrop.update_native_reserve(40).unwrap();
+            // = when amount dips into the native reserve
+            // = * it raises an error
+            let result = az_airdrop.rescue_native(accounts.eve, 61);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::LimitExceeded {
+                    limit: 60,
+                    requested: 61,
+                })
+            );
+            // = when amount is available
+            // = * it transfers the amount
+            az_airdrop.rescue_native(accounts.eve, 60).unwrap();
+            assert_eq!(
+                ink::env::test::get_account_balance::<DefaultEnvironment>(accounts.eve),
+                Ok(60)
+            );
+            assert_eq!(
+                ink::env::test::get_account_balance::<DefaultEnvironment>(contract_address),
+                Ok(40)
+            );
+        }
+
+        #[ink::test]
+        fn test_rescue_token() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let result = az_airdrop.rescue_token(accounts.eve, accounts.charlie);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+            // when called by admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // = when token is the airdrop token
+            // = * it raises an error
+            let result = az_airdrop.rescue_token(mock_token(), accounts.bob);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Airdrop token can't be rescued".to_string()
+                ))
+            );
+            // = when token is another token
+            // THE REST NEEDS TO HAPPEN IN INTEGRATION TESTS
+        }
+
+        #[ink::test]
+        fn test_reserve_budget() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let mut result = az_airdrop.reserve_budget(accounts.charlie, 5);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+            // when called by admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // = when new total_reserved would overflow
+            az_airdrop.total_reserved = Balance::MAX;
+            // = * it raises an error
+            result = az_airdrop.reserve_budget(accounts.charlie, 1);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Overflow("total_reserved".to_string()))
+            );
+            // = when new total_reserved won't overflow
+            // THE REST NEEDS TO HAPPEN IN INTEGRATION TESTS
+        }
+
+        #[ink::test]
+        fn test_return_spare_token() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by admin
+            // THIS NEEDS TO HAPPEN IN INTEGRATION TESTS
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let result = az_airdrop.return_spare_tokens();
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: None,
+                })
+            );
+        }
+
+        #[ink::test]
+        fn test_retrofit_to_template() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let mut result = az_airdrop.retrofit_to_template(0, 0, 10);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+            // when called by admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // = when template does not exist
+            // = * it raises an error
+            result = az_airdrop.retrofit_to_template(0, 0, 10);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Schedule template".to_string()))
+            );
+            // = when template exists
+            az_airdrop.schedule_templates.insert(
+                0,
+                &ScheduleTemplate {
+                    name: "Team".to_string(),
+                    collectable_at_tge_percentage: 10,
+                    cliff_duration: 5,
+                    vesting_duration: 50,
+                },
+            );
+            az_airdrop.insert_recipient(
+                accounts.django,
+                &Recipient {
+                    allocation_id: 0,
+                    total_amount: 10,
+                    collected: 0,
+                    collectable_at_tge_percentage: 0,
+                    cliff_duration: 0,
+                    vesting_duration: 100,
+                    schedule_phases: None,
+                    curve: Curve::Linear,
+                    revocable: false,
+                    external_ref: None,
+                    release_interval: 0,
+                    start_override: None,
+                    collectable_at_cliff_percentage: 0,
+                    source: AllocationSource::AdminGrant,
+                    template_id: None,
+                },
+            );
+            az_airdrop.insert_recipient(
+                accounts.eve,
+                &Recipient {
+                    allocation_id: 0,
+                    total_amount: 10,
+                    collected: 0,
+                    collectable_at_tge_percentage: 10,
+                    cliff_duration: 5,
+                    vesting_duration: 50,
+                    schedule_phases: None,
+                    curve: Curve::Linear,
+                    revocable: false,
+                    external_ref: None,
+                    release_interval: 0,
+                    start_override: None,
+                    collectable_at_cliff_percentage: 0,
+                    source: AllocationSource::AdminGrant,
+                    template_id: Some(0),
+                },
+            );
+            az_airdrop.insert_recipient(
+                accounts.frank,
+                &Recipient {
+                    allocation_id: 0,
+                    total_amount: 10,
+                    collected: 0,
+                    collectable_at_tge_percentage: 0,
+                    cliff_duration: 0,
+                    vesting_duration: 100,
+                    schedule_phases: Some(vec![SchedulePhase::Instant { at: 0, bps: 10_000 }]),
+                    curve: Curve::Linear,
+                    revocable: false,
+                    external_ref: None,
+                    release_interval: 0,
+                    start_override: None,
+                    collectable_at_cliff_percentage: 0,
+                    source: AllocationSource::AdminGrant,
+                    template_id: None,
+                },
+            );
+            // == when airdrop has started
+            // == * it raises an error
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(MOCK_START);
+            result = az_airdrop.retrofit_to_template(0, 0, 10);
+            assert_eq!(result, Err(AzAirdropError::AirdropAlreadyStarted));
+            // == when airdrop has not started
+            // == * it points the remaining recipients at the template
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(MOCK_START - 1);
+            result = az_airdrop.retrofit_to_template(0, 0, 10);
+            assert_eq!(result, Ok(vec![accounts.django]));
+            let recipient: Recipient = az_airdrop.show(accounts.django).unwrap();
+            assert_eq!(recipient.template_id, Some(0));
+            assert_eq!(az_airdrop.schedule_template_recipients.get(0), Some(2));
+            assert_eq!(recipient.collectable_at_tge_percentage, 10);
+            assert_eq!(recipient.cliff_duration, 5);
+            assert_eq!(recipient.vesting_duration, 50);
+            // == * it leaves recipients with schedule phases alone
+            assert_eq!(
+                az_airdrop.show(accounts.frank).unwrap().vesting_duration,
+                100
+            );
+            // == * it has nothing left to rewrite when run again
+            assert_eq!(
+                az_airdrop.retrofit_to_template_preview(0, 0, 10),
+                Ok(vec![])
+            );
+        }
+
+        #[ink::test]
+        fn test_revoke_all_allowances() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let result = az_airdrop.revoke_all_allowances(accounts.django);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+            // when called by admin
+            // THE REST NEEDS TO HAPPEN IN INTEGRATION TESTS
+        }
+
+        #[ink::test]
+        fn test_role_grant() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let result = az_airdrop.role_grant(role_registry::PAUSER, accounts.charlie);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+            // when called by admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // = when role is recipient manager or default admin
+            // = * it raises an error
+            for role in [
+                role_registry::RECIPIENT_MANAGER,
+                role_registry::DEFAULT_ADMIN,
+            ] {
+                let result = az_airdrop.role_grant(role, accounts.charlie);
+                assert_eq!(
+                    result,
+                    Err(AzAirdropError::UnprocessableEntity(
+                        "Role can't be granted".to_string()
+                    ))
+                );
+            }
+            // = when role is pauser
+            // = * it lets the account pause claims
+            az_airdrop
+                .role_grant(role_registry::PAUSER, accounts.charlie)
+                .unwrap();
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            az_airdrop.update_claims_enabled(false).unwrap();
+            assert!(!az_airdrop.config().claims_enabled);
+            // = * it doesn't let the account manage recipients
+            let result = az_airdrop.recipient_add(accounts.django, 5, None, None, None);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: None,
+                })
+            );
+        }
+
+        #[ink::test]
+        fn test_role_revoke() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let result = az_airdrop.role_revoke(role_registry::TREASURER, accounts.charlie);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+            // when called by admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // = when account doesn't have the role
+            // = * it raises an error
+            let result = az_airdrop.role_revoke(role_registry::TREASURER, accounts.charlie);
+            assert_eq!(result, Err(AzAirdropError::NotFound("Role".to_string())));
+            // = when account has the role
+            // = * it removes the role
+            az_airdrop
+                .role_grant(role_registry::TREASURER, accounts.charlie)
+                .unwrap();
+            az_airdrop
+                .role_revoke(role_registry::TREASURER, accounts.charlie)
+                .unwrap();
+            assert!(!az_airdrop.has_role(role_registry::TREASURER, accounts.charlie));
+        }
+
+        #[ink::test]
+        fn test_rotate_merkle_root() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let result = az_airdrop.rotate_merkle_root([1; 32], 10);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+            // when called by admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(MOCK_START);
+            // = when there is no current root
+            // = * it applies the root immediately
+            assert_eq!(az_airdrop.rotate_merkle_root([1; 32], 10), Ok(MOCK_START));
+            assert_eq!(az_airdrop.config().merkle_root, Some([1; 32]));
+            assert_eq!(az_airdrop.config().merkle_root_version, 1);
+            // = * it commits the root's total
+            assert_eq!(az_airdrop.config().merkle_unclaimed, 10);
+            assert_eq!(az_airdrop.merkle_committed(), 10);
+            // = when there is a current root
+            // = * it applies the root after the delay
+            let applies_from: Timestamp = MOCK_START + MERKLE_ROOT_ROTATION_DELAY;
+            assert_eq!(az_airdrop.rotate_merkle_root([2; 32], 15), Ok(applies_from));
+            assert_eq!(
+                az_airdrop.config().pending_merkle_root,
+                Some(([2; 32], applies_from))
+            );
+            // = * it commits the larger of the current and pending totals until then
+            assert_eq!(az_airdrop.merkle_committed(), 15);
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(applies_from - 1);
+            az_airdrop.apply_pending_merkle_root();
+            assert_eq!(az_airdrop.config().merkle_root, Some([1; 32]));
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(applies_from);
+            // = * queries show the root as applied once the delay has passed
+            assert_eq!(az_airdrop.config().merkle_root, Some([2; 32]));
+            assert_eq!(az_airdrop.config().merkle_root_version, 2);
+            assert_eq!(az_airdrop.config().pending_merkle_root, None);
+            assert_eq!(az_airdrop.config().merkle_unclaimed, 15);
+            az_airdrop.apply_pending_merkle_root();
+            assert_eq!(az_airdrop.config().merkle_root, Some([2; 32]));
+            assert_eq!(az_airdrop.config().merkle_root_version, 2);
+            assert_eq!(az_airdrop.config().pending_merkle_root, None);
+            assert_eq!(az_airdrop.config().merkle_unclaimed, 15);
+            assert_eq!(az_airdrop.config().pending_merkle_unclaimed, 0);
+        }
+
+        #[ink::test]
+        fn test_schedule_template_add() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let mut result = az_airdrop.schedule_template_add("Team".to_string(), 0, 0, 0);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+            // when called by admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // = when schedule is invalid
+            // = * it raises an error
+            result = az_airdrop.schedule_template_add("Team".to_string(), 101, 0, 0);
+            assert_eq!(result, Err(AzAirdropError::InvalidTgePercentage));
+            // = when schedule is valid
+            // = * it stores the template under the next id
+            assert_eq!(
+                az_airdrop.schedule_template_add("Team".to_string(), 0, 1, 2),
+                Ok(0)
+            );
+            assert_eq!(
+                az_airdrop.schedule_template_add("Advisor".to_string(), 10, 1, 2),
+                Ok(1)
+            );
+            assert_eq!(
+                az_airdrop.schedule_template(1),
+                Ok(ScheduleTemplate {
+                    name: "Advisor".to_string(),
+                    collectable_at_tge_percentage: 10,
+                    cliff_duration: 1,
+                    vesting_duration: 2,
+                })
+            );
+        }
+
+        #[ink::test]
+        fn test_schedule_template_remove() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let mut result = az_airdrop.schedule_template_remove(0);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+            // when called by admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // = when template does not exist
+            // = * it raises an error
+            result = az_airdrop.schedule_template_remove(0);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Schedule template".to_string()))
+            );
+            // = when template exists
+            az_airdrop
+                .schedule_template_add("Team".to_string(), 0, 1, 2)
+                .unwrap();
+            // == when a recipient references the template
+            // == * it raises an error
+            az_airdrop.insert_recipient(
+                accounts.django,
+                &Recipient {
+                    allocation_id: 0,
+                    total_amount: 10,
+                    collected: 0,
+                    collectable_at_tge_percentage: 0,
+                    cliff_duration: 0,
+                    vesting_duration: 0,
+                    schedule_phases: None,
+                    curve: Curve::Linear,
+                    revocable: false,
+                    external_ref: None,
+                    release_interval: 0,
+                    start_override: None,
+                    collectable_at_cliff_percentage: 0,
+                    source: AllocationSource::AdminGrant,
+                    template_id: Some(0),
+                },
+            );
+            result = az_airdrop.schedule_template_remove(0);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Schedule template is in use".to_string()
+                ))
+            );
+            // == when no recipient references the template
+            // == * it removes the template
+            az_airdrop.remove_recipient(accounts.django);
+            az_airdrop.schedule_template_remove(0).unwrap();
+            assert_eq!(
+                az_airdrop.schedule_template(0),
+                Err(AzAirdropError::NotFound("Schedule template".to_string()))
+            );
+            // = * it doesn't reuse the id
+            assert_eq!(
+                az_airdrop.schedule_template_add("Team".to_string(), 0, 1, 2),
+                Ok(1)
+            );
+        }
+
+        #[ink::test]
+        fn test_schedule_template_update() {
+            let (accounts, mut az_airdrop) = init();
+            let template: ScheduleTemplate = ScheduleTemplate {
+                name: "Community".to_string(),
+                collectable_at_tge_percentage: 50,
+                cliff_duration: 0,
+                vesting_duration: 10,
+            };
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let mut result = az_airdrop.schedule_template_update(0, template.clone());
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+            // when called by admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // = when template does not exist
+            // = * it raises an error
+            result = az_airdrop.schedule_template_update(0, template.clone());
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Schedule template".to_string()))
+            );
+            // = when template exists
+            // = * it updates the template
+            az_airdrop
+                .schedule_template_add("Team".to_string(), 0, 1, 2)
+                .unwrap();
+            az_airdrop
+                .schedule_template_update(0, template.clone())
+                .unwrap();
+            assert_eq!(az_airdrop.schedule_template(0), Ok(template.clone()));
+            // == when a recipient references the template
+            az_airdrop.insert_recipient(
+                accounts.django,
+                &Recipient {
+                    allocation_id: 0,
+                    total_amount: 10,
+                    collected: 0,
+                    collectable_at_tge_percentage: 0,
+                    cliff_duration: 0,
+                    vesting_duration: 0,
+                    schedule_phases: None,
+                    curve: Curve::Linear,
+                    revocable: false,
+                    external_ref: None,
+                    release_interval: 0,
+                    start_override: None,
+                    collectable_at_cliff_percentage: 0,
+                    source: AllocationSource::AdminGrant,
+                    template_id: Some(0),
+                },
+            );
+            // == * it raises an error
+            result = az_airdrop.schedule_template_update(0, template.clone());
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Schedule template is in use".to_string()
+                ))
+            );
+            // == * it leaves the template and the recipient's schedule unchanged
+            assert_eq!(az_airdrop.schedule_template(0), Ok(template));
+            assert_eq!(
+                az_airdrop
+                    .show(accounts.django)
+                    .unwrap()
+                    .collectable_at_tge_percentage,
+                50
+            );
+        }
+
+        #[ink::test]
+        fn test_set_notification_pref() {
+            let (accounts, mut az_airdrop) = init();
+            set_caller::<DefaultEnvironment>(accounts.django);
+            // when caller is not a recipient
+            // * it raises an error
+            let result = az_airdrop.set_notification_pref(1);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Recipient".to_string()))
+            );
+            // when caller is a recipient
+            az_airdrop.recipients.insert(
+                accounts.django,
+                &Recipient {
+                    allocation_id: 0,
+                    total_amount: 100,
+                    collected: 0,
+                    collectable_at_tge_percentage: 100,
+                    cliff_duration: 0,
+                    vesting_duration: 0,
+                    schedule_phases: None,
+                    curve: Curve::Linear,
+                    revocable: false,
+                    external_ref: None,
+                    release_interval: 0,
+                    start_override: None,
+                    collectable_at_cliff_percentage: 0,
+                    source: AllocationSource::AdminGrant,
+                    template_id: None,
+                },
+            );
+            // * it stores the flags
+            az_airdrop.set_notification_pref(5).unwrap();
+            assert_eq!(az_airdrop.notification_pref(accounts.django), 5);
+            // = when flags are 0
+            // = * it removes the preference
+            az_airdrop.set_notification_pref(0).unwrap();
+            assert_eq!(az_airdrop.notification_prefs.get(accounts.django), None);
+        }
+
+        #[ink::test]
+        fn test_sub_admins_add() {
+            let (accounts, mut az_airdrop) = init();
+            let new_sub_admin: AccountId = accounts.django;
+            // when called by admin
+            // = when address is not a sub admin
+            let mut result = az_airdrop.sub_admins_add(new_sub_admin, ALL_PERMISSIONS);
+            result.unwrap();
+            // = * it adds the address to sub_admins_vec
+            assert_eq!(
+                az_airdrop.sub_admins_as_vec.get_or_default().into_inner(),
+                vec![accounts.django]
+            );
+            // = * it adds the address to sub_admins_mapping
+            assert_eq!(
+                az_airdrop.sub_admins_mapping.get(new_sub_admin).is_some(),
+                true
+            );
+            // = when sub admin is missing a permission
+            // = * it can't call messages needing that permission
+            az_airdrop
+                .sub_admins_add(
+                    accounts.frank,
+                    SubAdminPermissions {
+                        can_add: false,
+                        can_subtract: true,
+                        can_update_schedule: false,
+                    },
+                )
+                .unwrap();
+            set_caller::<DefaultEnvironment>(accounts.frank);
+            assert_eq!(
+                az_airdrop.update_recipient(accounts.eve, None, None, None, None, None, None),
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.frank,
+                    expected: None,
+                })
+            );
+            // = * it can call messages with that permission
+            assert_eq!(
+                az_airdrop.recipient_subtract(accounts.eve, 1, None),
+                Err(AzAirdropError::NotFound("Recipient".to_string()))
+            );
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            az_airdrop.sub_admins_remove(accounts.frank).unwrap();
+            // = when already a sub admin
+            result = az_airdrop.sub_admins_add(new_sub_admin, ALL_PERMISSIONS);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Already a sub admin".to_string()
+                ))
+            );
+            // = * it raises an error
+            // = when max_sub_admins has been reached
+            az_airdrop.max_sub_admins = 1;
+            // = * it raises an error
+            result = az_airdrop.sub_admins_add(accounts.eve, ALL_PERMISSIONS);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::CapacityExceeded("Sub admins".to_string()))
+            );
+            // when called by non admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            result = az_airdrop.sub_admins_add(new_sub_admin, ALL_PERMISSIONS);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+        }
+
+        #[ink::test]
+        fn test_sub_admins_remove() {
+            let (accounts, mut az_airdrop) = init();
+            let sub_admin_to_remove: AccountId = accounts.django;
+            // when called by admin
+            // = when address is not a sub admin
+            let mut result = az_airdrop.sub_admins_remove(sub_admin_to_remove);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Not a sub admin".to_string()
+                ))
+            );
+            // = when address is a sub admin
+            az_airdrop
+                .sub_admins_add(sub_admin_to_remove, ALL_PERMISSIONS)
+                .unwrap();
+            result = az_airdrop.sub_admins_remove(sub_admin_to_remove);
+            result.unwrap();
+            // = * it removes the address from sub_admins_vec
+            assert_eq!(az_airdrop.sub_admins_as_vec.get_or_default().len(), 0);
+            // = * it remove the address from sub_admins_mapping
+            assert_eq!(
+                az_airdrop
+                    .sub_admins_mapping
+                    .get(sub_admin_to_remove)
+                    .is_some(),
+                false
+            );
+            // = * it raises an error
+            // when called by non admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            result = az_airdrop.sub_admins_remove(sub_admin_to_remove);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+        }
+
+        #[ink::test]
+        fn test_recipient_lost_key_cancel() {
+            let (accounts, mut az_airdrop) = init();
+            let recipient_address: AccountId = accounts.django;
+            // when called by someone other than admin or the recipient
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let mut result = az_airdrop.recipient_lost_key_cancel(recipient_address);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+            // when called by the recipient
+            set_caller::<DefaultEnvironment>(recipient_address);
+            // = when there is no proposal
+            // = * it raises an error
+            result = az_airdrop.recipient_lost_key_cancel(recipient_address);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Lost key proposal".to_string()))
+            );
+            // = when there is a proposal
+            // = * it removes the proposal
+            az_airdrop
+                .lost_key_proposals
+                .insert(recipient_address, &(accounts.eve, MOCK_START));
+            az_airdrop
+                .recipient_lost_key_cancel(recipient_address)
+                .unwrap();
+            assert_eq!(az_airdrop.lost_key_proposal(recipient_address), None);
+            // when called by admin
+            // * it removes the proposal
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            az_airdrop
+                .lost_key_proposals
+                .insert(recipient_address, &(accounts.eve, MOCK_START));
+            az_airdrop
+                .recipient_lost_key_cancel(recipient_address)
+                .unwrap();
+            assert_eq!(az_airdrop.lost_key_proposal(recipient_address), None);
+        }
+
+        #[ink::test]
+        fn test_recipient_lost_key_propose() {
+            let (accounts, mut az_airdrop) = init();
+            let recipient_address: AccountId = accounts.django;
+            let new_address: AccountId = accounts.eve;
+            let recipient: Recipient = Recipient {
+                allocation_id: 3,
+                total_amount: 100,
+                collected: 40,
+                collectable_at_tge_percentage: 100,
+                cliff_duration: 0,
+                vesting_duration: 0,
+                schedule_phases: None,
+                curve: Curve::Linear,
+                revocable: false,
+                external_ref: None,
+                release_interval: 0,
+                start_override: None,
+                collectable_at_cliff_percentage: 0,
+                source: AllocationSource::AdminGrant,
+                template_id: None,
+            };
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let mut result = az_airdrop.recipient_lost_key_propose(recipient_address, new_address);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+            // when called by admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // = when recipient does not exist
+            // = * it raises an error
+            result = az_airdrop.recipient_lost_key_propose(recipient_address, new_address);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Recipient".to_string()))
+            );
+            // = when recipient exists
+            az_airdrop.recipients.insert(recipient_address, &recipient);
+            // == when new address is already a recipient
+            // == * it raises an error
+            az_airdrop.recipients.insert(new_address, &recipient);
+            result = az_airdrop.recipient_lost_key_propose(recipient_address, new_address);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "New address is already a recipient".to_string()
+                ))
+            );
+            // == when new address is not a recipient
+            // == * it records the proposal, even though the recipient has collected
+            az_airdrop.recipients.remove(new_address);
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(MOCK_START);
+            result = az_airdrop.recipient_lost_key_propose(recipient_address, new_address);
+            assert_eq!(result, Ok(MOCK_START));
+            assert_eq!(
+                az_airdrop.lost_key_proposal(recipient_address),
+                Some((new_address, MOCK_START))
+            );
+        }
+
+        #[ink::test]
+        fn test_recipient_lost_key_reassign() {
+            let (accounts, mut az_airdrop) = init();
+            let recipient_address: AccountId = accounts.django;
+            let new_address: AccountId = accounts.eve;
+            let recipient: Recipient = Recipient {
+                allocation_id: 3,
+                total_amount: 100,
+                collected: 40,
+                collectable_at_tge_percentage: 100,
+                cliff_duration: 0,
+                vesting_duration: 0,
+                schedule_phases: None,
+                curve: Curve::Linear,
+                revocable: false,
+                external_ref: None,
+                release_interval: 0,
+                start_override: None,
+                collectable_at_cliff_percentage: 0,
+                source: AllocationSource::AdminGrant,
+                template_id: None,
+            };
+            az_airdrop.recipients.insert(recipient_address, &recipient);
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let mut result = az_airdrop.recipient_lost_key_reassign(recipient_address);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+            // when called by admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // = when there is no proposal
+            // = * it raises an error
+            result = az_airdrop.recipient_lost_key_reassign(recipient_address);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Lost key proposal".to_string()))
+            );
+            // = when there is a proposal
+            az_airdrop
+                .lost_key_proposals
+                .insert(recipient_address, &(new_address, MOCK_START));
+            // == when reassign delay has not passed
+            // == * it raises an error
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(
+                MOCK_START + LOST_KEY_REASSIGN_DELAY - 1,
+            );
+            result = az_airdrop.recipient_lost_key_reassign(recipient_address);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Reassign delay has not passed".to_string()
+                ))
+            );
+            // == when reassign delay has passed
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(
+                MOCK_START + LOST_KEY_REASSIGN_DELAY,
+            );
+            // === when new address has become a recipient
+            // === * it raises an error
+            az_airdrop.recipients.insert(new_address, &recipient);
+            result = az_airdrop.recipient_lost_key_reassign(recipient_address);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "New address is already a recipient".to_string()
+                ))
+            );
+            // === when new address is not a recipient
+            // === * it moves the allocation, collected included, to the new address
+            az_airdrop.recipients.remove(new_address);
+            result = az_airdrop.recipient_lost_key_reassign(recipient_address);
+            assert_eq!(result, Ok(recipient.clone()));
+            assert_eq!(az_airdrop.show(new_address), Ok(recipient));
+            assert_eq!(
+                az_airdrop.show(recipient_address),
+                Err(AzAirdropError::NotFound("Recipient".to_string()))
+            );
+            // === * it removes the proposal
+            assert_eq!(az_airdrop.lost_key_proposal(recipient_address), None);
+        }
+
+        #[ink::test]
+        fn test_recipient_mark_unreachable() {
+            let (accounts, mut az_airdrop) = init();
+            let recipient_address: AccountId = accounts.django;
+            let matured_at: Timestamp = MOCK_START + UNREACHABLE_AFTER;
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let mut result = az_airdrop.recipient_mark_unreachable(recipient_address);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+            // when called by admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // = when recipient does not exist
+            // = * it raises an error
+            result = az_airdrop.recipient_mark_unreachable(recipient_address);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Recipient".to_string()))
+            );
+            // = when recipient exists
+            let mut recipient: Recipient = Recipient {
+                allocation_id: 0,
+                total_amount: 100,
+                collected: 0,
+                collectable_at_tge_percentage: 0,
+                cliff_duration: 0,
+                vesting_duration: 10,
+                schedule_phases: None,
+                curve: Curve::Linear,
+                revocable: false,
+                external_ref: None,
+                release_interval: 0,
+                start_override: None,
+                collectable_at_cliff_percentage: 0,
+                source: AllocationSource::AdminGrant,
+                template_id: None,
+            };
+            az_airdrop.recipients.insert(recipient_address, &recipient);
+            // == when allocation has not been fully vested for long enough
+            // == * it raises an error
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(matured_at + 9);
+            result = az_airdrop.recipient_mark_unreachable(recipient_address);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Recipient has not been matured and unclaimed for long enough".to_string()
+                ))
+            );
+            // == when allocation has been fully vested for long enough
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(matured_at + 10);
+            // === when recipient has collected
+            // === * it raises an error
+            recipient.collected = 1;
+            az_airdrop.recipients.insert(recipient_address, &recipient);
+            result = az_airdrop.recipient_mark_unreachable(recipient_address);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Recipient has not been matured and unclaimed for long enough".to_string()
+                ))
+            );
+            // === when recipient has not collected
+            // === * it marks the recipient as unreachable
+            recipient.collected = 0;
+            az_airdrop.recipients.insert(recipient_address, &recipient);
+            result = az_airdrop.recipient_mark_unreachable(recipient_address);
+            assert_eq!(result, Ok(matured_at + 10));
+            assert_eq!(
+                az_airdrop.unreachable_mark(recipient_address),
+                Some(matured_at + 10)
+            );
+            // ==== when recipient is already marked
+            // ==== * it raises an error
+            result = az_airdrop.recipient_mark_unreachable(recipient_address);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Recipient is already marked as unreachable".to_string()
+                ))
+            );
+        }
+
+        #[ink::test]
+        fn test_recipient_reassign() {
+            let (accounts, mut az_airdrop) = init();
+            let recipient_address: AccountId = accounts.django;
+            let new_address: AccountId = accounts.eve;
+            let recipient: Recipient = Recipient {
+                allocation_id: 3,
+                total_amount: 100,
+                collected: 0,
+                collectable_at_tge_percentage: 100,
+                cliff_duration: 0,
+                vesting_duration: 0,
+                schedule_phases: None,
+                curve: Curve::Linear,
+                revocable: false,
+                external_ref: None,
+                release_interval: 0,
+                start_override: None,
+                collectable_at_cliff_percentage: 0,
+                source: AllocationSource::AdminGrant,
+                template_id: None,
+            };
+            az_airdrop.recipients.insert(recipient_address, &recipient);
+            az_airdrop.notification_prefs.insert(recipient_address, &3);
+            az_airdrop.max_per_claims.insert(recipient_address, &50);
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let mut result = az_airdrop.recipient_reassign(recipient_address, new_address);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+            // when called by admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // = when recipient has not been marked as unreachable
+            // = * it raises an error
+            result = az_airdrop.recipient_reassign(recipient_address, new_address);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Unreachable mark".to_string()))
+            );
+            // = when recipient has been marked as unreachable
+            az_airdrop
+                .unreachable_marks
+                .insert(recipient_address, &MOCK_START);
+            // == when reassign delay has not passed
+            // == * it raises an error
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(
+                MOCK_START + UNREACHABLE_REASSIGN_DELAY - 1,
+            );
+            result = az_airdrop.recipient_reassign(recipient_address, new_address);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Reassign delay has not passed".to_string()
+                ))
+            );
+            // == when reassign delay has passed
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(
+                MOCK_START + UNREACHABLE_REASSIGN_DELAY,
+            );
+            // === when new address is already a recipient
+            // === * it raises an error
+            az_airdrop.recipients.insert(new_address, &recipient);
+            result = az_airdrop.recipient_reassign(recipient_address, new_address);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "New address is already a recipient".to_string()
+                ))
+            );
+            // === when new address is not a recipient
+            // === * it moves the allocation to the new address
+            az_airdrop.recipients.remove(new_address);
+            result = az_airdrop.recipient_reassign(recipient_address, new_address);
+            assert_eq!(result, Ok(recipient.clone()));
+            assert_eq!(az_airdrop.show(new_address), Ok(recipient));
+            assert_eq!(
+                az_airdrop.show(recipient_address),
+                Err(AzAirdropError::NotFound("Recipient".to_string()))
+            );
+            // === * it removes the mark
+            assert_eq!(az_airdrop.unreachable_mark(recipient_address), None);
+            // === * it moves the notification preference to the new address
+            assert_eq!(az_airdrop.notification_pref(new_address), 3);
+            assert_eq!(az_airdrop.notification_pref(recipient_address), 0);
+            // === * it moves the max per claim to the new address
+            assert_eq!(az_airdrop.max_per_claim(new_address), Some(50));
+            assert_eq!(az_airdrop.max_per_claim(recipient_address), None);
+        }
+
+        #[ink::test]
+        fn test_recipient_remove() {
+            let (accounts, mut az_airdrop) = init();
+            let amount: Balance = 5;
+            let recipient_address: AccountId = accounts.django;
+            // when called by non-admin or non-sub-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let mut result = az_airdrop.recipient_remove(recipient_address);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: None,
+                })
+            );
+            // when called by an admin or sub-admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // = when airdrop has started
+            // = * it raises an error
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(az_airdrop.start);
+            result = az_airdrop.recipient_remove(recipient_address);
+            assert_eq!(result, Err(AzAirdropError::AirdropAlreadyStarted));
+            // = when airdrop has not started
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(az_airdrop.start - 1);
+            // == when recipient does not exist
+            // == * it raises an error
+            result = az_airdrop.recipient_remove(recipient_address);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Recipient".to_string()))
+            );
+            // == when recipient exists
+            az_airdrop.recipients.insert(
+                recipient_address,
+                &Recipient {
+                    allocation_id: 0,
+                    total_amount: amount,
+                    collected: 0,
+                    collectable_at_tge_percentage: 0,
+                    cliff_duration: 0,
+                    vesting_duration: 0,
+                    schedule_phases: None,
+                    curve: Curve::Linear,
+                    revocable: false,
+                    external_ref: None,
+                    release_interval: 0,
+                    start_override: None,
+                    collectable_at_cliff_percentage: 0,
+                    source: AllocationSource::AdminGrant,
+                    template_id: None,
+                },
+            );
+            az_airdrop.max_per_claims.insert(recipient_address, &1);
+            az_airdrop
+                .address_change_proposals
+                .insert(recipient_address, &accounts.eve);
+            az_airdrop.to_be_collected = amount + 1;
+            // == * it removes the recipient's entries
+            az_airdrop.recipient_remove(recipient_address).unwrap();
+            assert!(!az_airdrop.recipients.contains(recipient_address));
+            assert!(!az_airdrop.max_per_claims.contains(recipient_address));
+            assert!(!az_airdrop
+                .address_change_proposals
+                .contains(recipient_address));
+            // == * it reduces to_be_collected by the total_amount
+            assert_eq!(az_airdrop.to_be_collected, 1);
+            // == * its address change proposal can't be accepted once the address is added again
+            az_airdrop.insert_recipient(
+                recipient_address,
+                &Recipient {
+                    allocation_id: 1,
+                    total_amount: amount,
+                    collected: 0,
+                    collectable_at_tge_percentage: 0,
+                    cliff_duration: 0,
+                    vesting_duration: 0,
+                    schedule_phases: None,
+                    curve: Curve::Linear,
+                    revocable: false,
+                    external_ref: None,
+                    release_interval: 0,
+                    start_override: None,
+                    collectable_at_cliff_percentage: 0,
+                    source: AllocationSource::AdminGrant,
+                    template_id: None,
+                },
+            );
+            set_caller::<DefaultEnvironment>(accounts.eve);
+            assert_eq!(
+                az_airdrop.address_change_accept(recipient_address),
+                Err(AzAirdropError::NotFound(
+                    "Address change proposal".to_string()
+                ))
+            );
+            assert!(az_airdrop.recipients.contains(recipient_address));
+        }
+
+        #[ink::test]
+        fn test_recipient_revoke() {
+            let (accounts, mut az_airdrop) = init();
+            let recipient_address: AccountId = accounts.django;
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let mut result = az_airdrop.recipient_revoke(recipient_address);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+            // when called by admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // = when airdrop has not started
+            // = * it raises an error
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(MOCK_START - 1);
+            result = az_airdrop.recipient_revoke(recipient_address);
+            assert_eq!(result, Err(AzAirdropError::AirdropNotStarted));
+            // = when airdrop has started
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(MOCK_START + 5);
+            // == when recipient does not exist
+            // == * it raises an error
+            result = az_airdrop.recipient_revoke(recipient_address);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Recipient".to_string()))
+            );
+            // == when recipient is not revocable
+            // == * it raises an error
+            let mut recipient: Recipient = Recipient {
+                allocation_id: 0,
+                total_amount: 100,
+                collected: 0,
+                collectable_at_tge_percentage: 100,
+                cliff_duration: 0,
+                vesting_duration: 0,
+                schedule_phases: None,
+                curve: Curve::Linear,
+                revocable: false,
+                external_ref: None,
+                release_interval: 0,
+                start_override: None,
+                collectable_at_cliff_percentage: 0,
+                source: AllocationSource::AdminGrant,
+                template_id: None,
+            };
+            az_airdrop.recipients.insert(recipient_address, &recipient);
+            result = az_airdrop.recipient_revoke(recipient_address);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Recipient is not revocable".to_string()
+                ))
+            );
+            // == when recipient is revocable
+            recipient.revocable = true;
+            az_airdrop.recipients.insert(recipient_address, &recipient);
+            // === when everything has vested
+            // === * it raises an error
+            result = az_airdrop.recipient_revoke(recipient_address);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Recipient has nothing left to vest".to_string()
+                ))
+            );
+            // === when there is an unvested amount
+            // THE REST NEEDS TO HAPPEN IN INTEGRATION TESTS
+        }
+
+        #[ink::test]
+        fn test_recipient_subtract() {
+            let (accounts, mut az_airdrop) = init();
+            let amount: Balance = 5;
+            let recipient_address: AccountId = accounts.django;
+            // when called by an admin or sub-admin
+            // = when airdrop has started
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(az_airdrop.start);
+            // = * it raises an error
+            let mut result = az_airdrop.recipient_subtract(recipient_address, amount, None);
+            assert_eq!(result, Err(AzAirdropError::AirdropAlreadyStarted));
+            // = when airdrop has not started
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(
+                az_airdrop.start - 1,
+            );
+            // == when schedule is frozen
+            // == * it raises an error
+            az_airdrop.schedule_freeze_window = 1;
+            result = az_airdrop.recipient_subtract(recipient_address, amount, None);
+            assert_eq!(result, Err(AzAirdropError::ScheduleFrozen));
+            az_airdrop.schedule_freeze_window = 0;
+            // == when recipient does not exist
+            // == * it raises an error
+            result = az_airdrop.recipient_subtract(recipient_address, amount, None);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Recipient".to_string()))
+            );
+            // == when recipient exists
+            az_airdrop.recipients.insert(
+                recipient_address,
+                &Recipient {
+                    allocation_id: 0,
+                    total_amount: amount,
+                    collected: 0,
+                    collectable_at_tge_percentage: 0,
+                    cliff_duration: 0,
+                    vesting_duration: 0,
+                    schedule_phases: None,
+                    curve: Curve::Linear,
+                    revocable: false,
+                    external_ref: None,
+                    release_interval: 0,
+                    start_override: None,
+                    collectable_at_cliff_percentage: 0,
+                    source: AllocationSource::AdminGrant,
+                    template_id: None,
+                },
+            );
+            // === when amount is greater than the recipient's total amount
+            // === * it returns an error
+            result = az_airdrop.recipient_subtract(recipient_address, amount + 1, None);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Amount is greater than recipient's total amount".to_string()
+                ))
+            );
+            // === when amount is less than or equal to the recipient's total amount
+            az_airdrop.to_be_collected += amount;
+            // === * it reduces the total_amount by the amount
+            az_airdrop
+                .recipient_subtract(recipient_address, amount - 1, None)
+                .unwrap();
+            let recipient: Recipient = az_airdrop.recipients.get(recipient_address).unwrap();
+            assert_eq!(recipient.total_amount, 1);
+            // when called by non-admin or non-sub-admin
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            // * it raises an error
+            result = az_airdrop.recipient_subtract(recipient_address, amount, None);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: None,
+                })
+            );
+            // === * it reduces the total_amount
+            assert_eq!(az_airdrop.to_be_collected, 1);
+        }
+
+        #[ink::test]
+        fn test_sweep_unclaimed() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let mut result = az_airdrop.sweep_unclaimed();
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: None,
+                })
+            );
+            // when called by admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // = when there is no claim deadline
+            // = * it raises an error
+            result = az_airdrop.sweep_unclaimed();
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Claim deadline".to_string()))
+            );
+            // = when there is a claim deadline
+            az_airdrop.claim_deadline = Some(MOCK_START + 10);
+            // == when claim deadline has not passed
+            // == * it raises an error
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(MOCK_START + 9);
+            result = az_airdrop.sweep_unclaimed();
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Claim deadline has not passed".to_string()
+                ))
+            );
+            // == when claim deadline has passed
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(MOCK_START + 10);
+            // === when nothing is left to collect
+            // === * it raises an error
+            result = az_airdrop.sweep_unclaimed();
+            assert_eq!(result, Err(AzAirdropError::AmountZero));
+            // === when there is something left to collect
+            // THE REST NEEDS TO HAPPEN IN INTEGRATION TESTS
+        }
+
+        #[ink::test]
+        fn test_sweep_unclaimed_range() {
+            let (accounts, mut az_airdrop) = init();
+            let mut recipient: Recipient = Recipient {
+                allocation_id: 0,
+                total_amount: 5,
+                collected: 2,
+                collectable_at_tge_percentage: 0,
+                cliff_duration: 0,
+                vesting_duration: 0,
+                schedule_phases: None,
+                curve: Curve::Linear,
+                revocable: false,
+                external_ref: None,
+                release_interval: 0,
+                start_override: None,
+                collectable_at_cliff_percentage: 0,
+                source: AllocationSource::AdminGrant,
+                template_id: None,
+            };
+            az_airdrop.insert_recipient(accounts.django, &recipient);
+            recipient.collected = 5;
+            az_airdrop.insert_recipient(accounts.eve, &recipient);
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let mut result = az_airdrop.sweep_unclaimed_range(0, 10);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: None,
+                })
+            );
+            // when called by admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // = when unclaimed tokens have not been swept
+            // = * it raises an error
+            result = az_airdrop.sweep_unclaimed_range(0, 10);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Unclaimed tokens have not been swept".to_string()
+                ))
+            );
+            // = when unclaimed tokens have been swept
+            // = * it zeroes the remaining balances in range
+            az_airdrop.unclaimed_swept = true;
+            result = az_airdrop.sweep_unclaimed_range(0, 10);
+            assert_eq!(result, Ok(1));
+            assert_eq!(az_airdrop.show(accounts.django).unwrap().total_amount, 2);
+            assert_eq!(az_airdrop.show(accounts.eve).unwrap().total_amount, 5);
+            // = * it leaves nothing to zero on a second pass
+            result = az_airdrop.sweep_unclaimed_range(0, 10);
+            assert_eq!(result, Ok(0));
+        }
+
+        #[ink::test]
+        fn test_terminate() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let result = az_airdrop.terminate();
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+            // when called by admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // = when there are tokens to be collected
+            // = * it raises an error
+            az_airdrop.to_be_collected = 1;
+            let result = az_airdrop.terminate();
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Recipients have tokens to collect".to_string()
+                ))
+            );
+            az_airdrop.to_be_collected = 0;
+            // = when tokens are reserved
+            // = * it raises an error
+            az_airdrop.total_reserved = 1;
+            let result = az_airdrop.terminate();
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Tokens are reserved or escrowed".to_string()
+                ))
+            );
+            az_airdrop.total_reserved = 0;
+            // = when merkle leaves are unclaimed
+            // = * it raises an error
+            az_airdrop.merkle_unclaimed = 1;
+            let result = az_airdrop.terminate();
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Tokens are reserved or escrowed".to_string()
+                ))
+            );
+            // = when nothing is owed
+            // THE REST NEEDS TO HAPPEN IN INTEGRATION TESTS
+        }
+
+        #[ink::test]
+        fn test_unregister_payout_address() {
+            let (accounts, mut az_airdrop) = init();
+            set_caller::<DefaultEnvironment>(accounts.django);
+            // when address is not registered
+            // * it raises an error
+            let mut result = az_airdrop.unregister_payout_address(accounts.eve);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Payout address".to_string()))
+            );
+            // when address is registered
+            // * it removes the address
+            az_airdrop
+                .payout_addresses
+                .insert((accounts.django, accounts.eve), &MOCK_START);
+            result = az_airdrop.unregister_payout_address(accounts.eve);
+            assert_eq!(result, Ok(()));
+            assert!(!az_airdrop
+                .payout_addresses
+                .contains((accounts.django, accounts.eve)));
+        }
+
+        #[ink::test]
+        fn test_update_admin_ops_enabled() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let result = az_airdrop.update_admin_ops_enabled(false);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: None,
+                })
+            );
+            // when called by admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // * it updates admin_ops_enabled
+            az_airdrop.update_admin_ops_enabled(false).unwrap();
+            assert!(!az_airdrop.config().admin_ops_enabled);
+            // = when admin ops are disabled
+            // = * it stops admin and sub-admin changes
+            assert_eq!(
+                az_airdrop.update_clawback(None),
+                Err(AzAirdropError::Paused)
+            );
+            assert_eq!(
+                az_airdrop.recipient_remove(accounts.django),
+                Err(AzAirdropError::Paused)
+            );
+            // = * it leaves the mode switches available
+            az_airdrop.update_claims_enabled(false).unwrap();
+            az_airdrop.update_admin_ops_enabled(true).unwrap();
+            assert!(az_airdrop.config().admin_ops_enabled);
+        }
+
+        #[ink::test]
+        fn test_update_allocations_frozen() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by admin
+            // * it updates allocations_frozen
+            az_airdrop.update_allocations_frozen(true).unwrap();
+            assert!(az_airdrop.config().allocations_frozen);
+            az_airdrop.update_allocations_frozen(false).unwrap();
+            assert!(!az_airdrop.config().allocations_frozen);
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let result = az_airdrop.update_allocations_frozen(true);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+        }
+
+        #[ink::test]
+        fn test_update_allow_additions_after_start() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let result = az_airdrop.update_allow_additions_after_start(true);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+            // when called by admin
+            // * it updates allow_additions_after_start
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            az_airdrop.update_allow_additions_after_start(true).unwrap();
+            assert!(az_airdrop.config().allow_additions_after_start);
+            // = when recipient_add is called after start
+            az_airdrop.budgets.insert(accounts.bob, &10);
+            az_airdrop.total_reserved = 10;
+            az_airdrop
+                .recipient_add(accounts.django, 1, None, None, None)
+                .unwrap();
+            let added_at: Timestamp = MOCK_START + 5;
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(added_at);
+            // == when recipient already exists
+            // == * it raises an error
+            let result = az_airdrop.recipient_add(accounts.django, 1, None, None, None);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Only new recipients can be added after start".to_string()
+                ))
+            );
+            // == when start_override is before the addition
+            // == * it raises an error
+            let result = az_airdrop.recipient_add(accounts.eve, 1, None, None, Some(added_at - 1));
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "start_override can't be before the addition".to_string()
+                ))
+            );
+            // == when recipient is new
+            // == * it anchors vesting to the addition
+            let recipient: Recipient = az_airdrop
+                .recipient_add(accounts.eve, 1, None, None, None)
+                .unwrap();
+            assert_eq!(recipient.start_override, Some(added_at));
+            assert_eq!(
+                az_airdrop.show(accounts.eve).unwrap().start_override,
+                Some(added_at)
+            );
+            // = when allow_additions_after_start is unset
+            // = * recipient_add raises an error after start
+            az_airdrop
+                .update_allow_additions_after_start(false)
+                .unwrap();
+            let result = az_airdrop.recipient_add(accounts.frank, 1, None, None, None);
+            assert_eq!(result, Err(AzAirdropError::AirdropAlreadyStarted));
+        }
+
+        #[ink::test]
+        fn test_update_claim_deadline() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let mut result = az_airdrop.update_claim_deadline(Some(MOCK_START + 10));
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+            // when called by admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // = when claim deadline is not after start
+            // = * it raises an error
+            result = az_airdrop.update_claim_deadline(Some(MOCK_START));
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Claim deadline must be after now, start and claims_enabled_at".to_string()
+                ))
+            );
+            // = when claim deadline is after now, start and claims_enabled_at
+            // = * it updates claim_deadline
+            az_airdrop
+                .update_claim_deadline(Some(MOCK_START + 10))
+                .unwrap();
+            assert_eq!(az_airdrop.config().claim_deadline, Some(MOCK_START + 10));
+            // == * collectable amounts are zero from the deadline on
+            az_airdrop.recipients.insert(
+                accounts.django,
+                &Recipient {
+                    allocation_id: 0,
+                    total_amount: 100,
+                    collected: 0,
+                    collectable_at_tge_percentage: 100,
+                    cliff_duration: 0,
+                    vesting_duration: 0,
+                    schedule_phases: None,
+                    curve: Curve::Linear,
+                    revocable: false,
+                    external_ref: None,
+                    release_interval: 0,
+                    start_override: None,
+                    collectable_at_cliff_percentage: 0,
+                    source: AllocationSource::AdminGrant,
+                    template_id: None,
+                },
+            );
+            assert_eq!(
+                az_airdrop.collectable_amount(accounts.django, MOCK_START + 9),
+                Ok(100)
+            );
+            assert_eq!(
+                az_airdrop.collectable_amount(accounts.django, MOCK_START + 10),
+                Ok(0)
+            );
+            // = when claim deadline has passed
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(MOCK_START + 10);
+            // == * collect raises an error
+            set_caller::<DefaultEnvironment>(accounts.django);
+            result = az_airdrop.collect().map(|_| ());
+            assert_eq!(result, Err(AzAirdropError::ClaimDeadlinePassed));
+            // == * it can't be changed
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            result = az_airdrop.update_claim_deadline(None);
+            assert_eq!(result, Err(AzAirdropError::ClaimDeadlinePassed));
+        }
+
+        #[ink::test]
+        fn test_update_claimed_days_retention() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let mut result = az_airdrop.update_claimed_days_retention(30);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+            // when called by admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // = when claimed_days_retention is 0
+            // = * it raises an error
+            result = az_airdrop.update_claimed_days_retention(0);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Claimed days retention must be greater than 0".to_string()
+                ))
+            );
+            // = when claimed_days_retention is greater than 0
+            // = * it updates claimed_days_retention
+            az_airdrop.update_claimed_days_retention(30).unwrap();
+            assert_eq!(az_airdrop.config().claimed_days_retention, 30);
+        }
+
+        #[ink::test]
+        fn test_update_claims_enabled() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let result = az_airdrop.update_claims_enabled(false);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: None,
+                })
+            );
+            // when called by admin
+            // * it updates claims_enabled
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            az_airdrop.update_claims_enabled(false).unwrap();
+            assert!(!az_airdrop.config().claims_enabled);
+        }
+
+        #[ink::test]
+        fn test_update_claims_enabled_at() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let mut result = az_airdrop.update_claims_enabled_at(Some(MOCK_START));
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+            // when called by admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // = when claims_enabled_at is before start
+            // = * it raises an error
+            result = az_airdrop.update_claims_enabled_at(Some(MOCK_START - 1));
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "claims_enabled_at must be greater than or equal to start".to_string()
+                ))
+            );
+            // = when claims_enabled_at is at or after start
+            // = * it updates claims_enabled_at
+            az_airdrop
+                .update_claims_enabled_at(Some(MOCK_START + 10))
+                .unwrap();
+            assert_eq!(az_airdrop.config().claims_enabled_at, Some(MOCK_START + 10));
+            // == when collecting before claims_enabled_at
+            // == * it raises an error
+            az_airdrop.recipients.insert(
+                accounts.django,
+                &Recipient {
+                    allocation_id: 0,
+                    total_amount: 100,
+                    collected: 0,
+                    collectable_at_tge_percentage: 100,
+                    cliff_duration: 0,
+                    vesting_duration: 0,
+                    schedule_phases: None,
+                    curve: Curve::Linear,
+                    revocable: false,
+                    external_ref: None,
+                    release_interval: 0,
+                    start_override: None,
+                    collectable_at_cliff_percentage: 0,
+                    source: AllocationSource::AdminGrant,
+                    template_id: None,
+                },
+            );
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(MOCK_START + 9);
+            set_caller::<DefaultEnvironment>(accounts.django);
+            let collect_result = az_airdrop.collect();
+            assert_eq!(
+                collect_result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Claims are not enabled yet".to_string()
+                ))
+            );
+            // == * it keeps accruing from start
+            assert_eq!(
+                az_airdrop.collectable_amount(accounts.django, MOCK_START + 9),
+                Ok(100)
+            );
+            // = when claims_enabled_at is None
+            // = * it updates claims_enabled_at
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            az_airdrop.update_claims_enabled_at(None).unwrap();
+            assert_eq!(az_airdrop.config().claims_enabled_at, None);
+        }
+
+        #[ink::test]
+        fn test_update_clawback() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by admin
+            // * it updates clawback
+            az_airdrop.update_clawback(Some(accounts.django)).unwrap();
+            assert_eq!(az_airdrop.config().clawback, Some(accounts.django));
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let result = az_airdrop.update_clawback(None);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+        }
+
+        #[ink::test]
+        fn test_update_compliance_mode() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by admin
+            // * it updates compliance_mode
+            az_airdrop.update_compliance_mode(true).unwrap();
+            assert!(az_airdrop.config().compliance_mode);
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let result = az_airdrop.update_compliance_mode(false);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+        }
+
+        #[ink::test]
+        fn test_update_config() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by admin
+            // = when new admin is provided
+            let config: Config = az_airdrop
+                .update_config(Some(accounts.django), None, None, None, None, None, None)
+                .unwrap();
+            // = * it updates the admin
+            assert_eq!(config.admin, accounts.django);
+            // = * it returns the updated config
+            assert_eq!(config, az_airdrop.config());
+            set_caller::<DefaultEnvironment>(accounts.django);
+            // = when new start is provided
+            // == when new start is before or equal to current time stamp
+            let current_timestamp: Timestamp = 5;
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(current_timestamp);
+            let result = az_airdrop.update_config(
+                None,
+                Some(current_timestamp),
+                None,
+                None,
+                None,
+                None,
+                None,
+            );
+            // == * it raises an error
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "New start time must be in the future".to_string()
+                ))
+            );
+            // == when new start is after current time stamp
+            // === when to_be_collected is positive
+            az_airdrop.to_be_collected = 1;
+            // === * it raises an error
+            let result = az_airdrop.update_config(
+                None,
+                Some(current_timestamp + 1),
+                None,
+                None,
+                None,
+                None,
+                None,
+            );
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "to_be_collected must be zero when changing start time".to_string()
+                ))
+            );
+            // === when to_be_collected is zero
+            az_airdrop.to_be_collected = 0;
+            // === * it updates the start time
+            az_airdrop
+                .update_config(
+                    None,
+                    Some(current_timestamp + 1),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                )
+                .unwrap();
+            let mut config: Config = az_airdrop.config();
+            assert_eq!(config.start, current_timestamp + 1);
+            // === when new start is at or after the claim deadline
+            // === * it raises an error
+            az_airdrop.claim_deadline = Some(current_timestamp + 3);
+            let result = az_airdrop.update_config(
+                None,
+                Some(current_timestamp + 3),
+                None,
+                None,
+                None,
+                None,
+                None,
+            );
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Claim deadline must be after start".to_string()
+                ))
+            );
+            az_airdrop.claim_deadline = None;
+            // = when new default_collectable_at_tge_percentage is provided
+            // == when airdrop calculation variable combination is invalid
+            // == * it raises an error
+            let result = az_airdrop.update_config(None, None, Some(50), None, None, None, None);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "vesting_duration must be greater than 0 when collectable_tge_percentage is not 100"
+                        .to_string(),
+                ))
+            );
+            // == when combination of start, cliff_duration and vesting_duration exceeds Timestamp max
+            let result = az_airdrop.update_config(
+                None,
+                None,
+                Some(50),
+                Some((Timestamp::MAX / 2) - az_airdrop.start + 2),
+                Some(Timestamp::MAX / 2),
+                None,
+                None,
+            );
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Combination of start, cliff_duration and vesting_duration exceeds limit"
+                        .to_string(),
+                ))
+            );
+            // == when airdrop calculation variable combination is valid
+            config = az_airdrop
+                .update_config(None, None, Some(50), Some(50), Some(50), None, None)
+                .unwrap();
+            // == * it updates the default_collectable_at_tge_percentage
+            assert_eq!(config.default_collectable_at_tge_percentage, 50);
+            assert_eq!(config.default_cliff_duration, 50);
+            assert_eq!(config.default_vesting_duration, 50);
+            // No need to test the other default fields as test above does that
+            // = when new max_sub_admins and max_organization_members are provided
+            config = az_airdrop
+                .update_config(None, None, None, None, None, Some(1), Some(2))
+                .unwrap();
+            // = * it updates the max lengths
+            assert_eq!(config.max_sub_admins, 1);
+            assert_eq!(config.max_organization_members, 2);
+            // when called by non-admin
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            // * it raises an error
+            let result = az_airdrop.update_config(None, None, None, None, None, None, None);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.django),
+                })
+            );
+        }
+
+        #[ink::test]
+        fn test_update_escrow_deadline() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let mut result = az_airdrop.update_escrow_deadline(Some(MOCK_START));
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+            // when called by admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // = when there are no deposits
+            // = * it updates escrow_deadline
+            az_airdrop.update_escrow_deadline(Some(MOCK_START)).unwrap();
+            assert_eq!(az_airdrop.config().escrow_deadline, Some(MOCK_START));
+            // = when there are deposits
+            // = * it raises an error
+            az_airdrop.total_escrowed = 1;
+            result = az_airdrop.update_escrow_deadline(None);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Escrow has deposits".to_string()
+                ))
+            );
+            // = when escrow is finalized
+            // = * it raises an error
+            az_airdrop.escrow_finalized = true;
+            result = az_airdrop.update_escrow_deadline(None);
+            assert_eq!(result, Err(AzAirdropError::EscrowFinalized));
+        }
+
+        #[ink::test]
+        fn test_update_event_verbosity() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by admin
+            // * it updates event_verbosity
+            az_airdrop
+                .update_event_verbosity(EventVerbosity::Minimal)
+                .unwrap();
+            assert_eq!(az_airdrop.config().event_verbosity, EventVerbosity::Minimal);
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let result = az_airdrop.update_event_verbosity(EventVerbosity::Full);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+        }
+
+        #[ink::test]
+        fn test_update_funder() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by admin
+            // * it updates the funder
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(MOCK_START);
+            az_airdrop.update_funder(Some(accounts.django)).unwrap();
+            assert_eq!(az_airdrop.config().funder, Some(accounts.django));
+            // * it records the admin action
+            assert_eq!(az_airdrop.last_admin_action_at, Some(MOCK_START));
+            az_airdrop.update_funder(None).unwrap();
+            assert_eq!(az_airdrop.config().funder, None);
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let result = az_airdrop.update_funder(Some(accounts.charlie));
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+        }
+
+        #[ink::test]
+        fn test_update_max_per_claim() {
+            let (accounts, mut az_airdrop) = init();
+            set_caller::<DefaultEnvironment>(accounts.django);
+            // when caller is not a recipient
+            // * it raises an error
+            let mut result = az_airdrop.update_max_per_claim(Some(5));
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Recipient".to_string()))
+            );
+            // when caller is a recipient
+            az_airdrop.recipients.insert(
+                accounts.django,
+                &Recipient {
+                    allocation_id: 0,
+                    total_amount: 100,
+                    collected: 0,
+                    collectable_at_tge_percentage: 100,
+                    cliff_duration: 0,
+                    vesting_duration: 0,
+                    schedule_phases: None,
+                    curve: Curve::Linear,
+                    revocable: false,
+                    external_ref: None,
+                    release_interval: 0,
+                    start_override: None,
+                    collectable_at_cliff_percentage: 0,
+                    source: AllocationSource::AdminGrant,
+                    template_id: None,
+                },
+            );
+            // = when max_per_claim is zero
+            // = * it raises an error
+            result = az_airdrop.update_max_per_claim(Some(0));
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Max per claim must be greater than 0".to_string()
+                ))
+            );
+            // = when max_per_claim is positive
+            // = * it sets max_per_claim
+            az_airdrop.update_max_per_claim(Some(5)).unwrap();
+            assert_eq!(az_airdrop.max_per_claim(accounts.django), Some(5));
+            // = when max_per_claim is None
+            // = * it removes the limit
+            az_airdrop.update_max_per_claim(None).unwrap();
+            assert_eq!(az_airdrop.max_per_claim(accounts.django), None);
+        }
+
+        #[ink::test]
+        fn test_update_max_recipients() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let mut result = az_airdrop.update_max_recipients(Some(1));
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+            // when called by admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // Leaves room for the delay before start
+            az_airdrop.start = MOCK_START + MAX_RECIPIENTS_UPDATE_DELAY;
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(MOCK_START - 1);
+            // Failed calls aren't reverted in unit tests, so the budget covers each attempt
+            az_airdrop.budgets.insert(accounts.bob, &3);
+            az_airdrop.total_reserved = 3;
+            az_airdrop
+                .recipient_add(accounts.django, 1, None, None, None)
+                .unwrap();
+            // = when max recipients is below the recipients count
+            // = * it raises an error
+            result = az_airdrop.update_max_recipients(Some(0));
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Max recipients must be greater than or equal to recipients count".to_string()
+                ))
+            );
+            // = when max recipients is valid
+            // = * it queues max recipients
+            assert_eq!(
+                az_airdrop.update_max_recipients(Some(1)),
+                Ok(MOCK_START - 1 + MAX_RECIPIENTS_UPDATE_DELAY)
+            );
+            assert_eq!(az_airdrop.config().max_recipients, None);
+            assert_eq!(
+                az_airdrop.config().pending_max_recipients,
+                Some((Some(1), MOCK_START - 1 + MAX_RECIPIENTS_UPDATE_DELAY))
+            );
+            // = * it applies max recipients after the delay
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(
+                MOCK_START - 1 + MAX_RECIPIENTS_UPDATE_DELAY,
+            );
+            assert_eq!(az_airdrop.config().max_recipients, Some(1));
+            assert_eq!(az_airdrop.config().pending_max_recipients, None);
+            // == when a new recipient would exceed it
+            // == * it raises an error
+            assert_eq!(
+                az_airdrop.recipient_add(accounts.eve, 1, None, None, None),
+                Err(AzAirdropError::RecipientLimitReached(1))
+            );
+            // == when adding to an existing recipient
+            // == * it doesn't count as a new recipient
+            az_airdrop
+                .recipient_add(accounts.django, 1, None, None, None)
+                .unwrap();
+        }
+
+        #[ink::test]
+        fn test_update_native_reserve() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let result = az_airdrop.update_native_reserve(1);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+            // when called by admin
+            // * it updates native_reserve
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            az_airdrop.update_native_reserve(1).unwrap();
+            assert_eq!(az_airdrop.config().native_reserve, 1);
+        }
+
+        #[ink::test]
+        fn test_update_recipient() {
+            let (accounts, mut az_airdrop) = init();
+            let recipient: AccountId = accounts.django;
+            // when called by an admin or sub-admin
+            // = when airdrop has started
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(az_airdrop.start);
+            // = * it raises an error
+            let mut result =
+                az_airdrop.update_recipient(recipient, None, None, None, None, None, None);
+            assert_eq!(result, Err(AzAirdropError::AirdropAlreadyStarted));
+            // = when airdrop has not started
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(
+                az_airdrop.start - 1,
+            );
+            // == when schedule is frozen
+            // == * it raises an error
+            az_airdrop.schedule_freeze_window = 1;
+            result = az_airdrop.update_recipient(recipient, None, None, None, None, None, None);
+            assert_eq!(result, Err(AzAirdropError::ScheduleFrozen));
+            az_airdrop.schedule_freeze_window = 0;
+            // == when recipient does not exist
+            // == * it raises an error
+            result = az_airdrop.update_recipient(recipient, None, None, None, None, None, None);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Recipient".to_string(),))
+            );
+            // == when recipient exists
+            az_airdrop.recipients.insert(
+                recipient,
+                &Recipient {
+                    allocation_id: 0,
+                    total_amount: 5,
+                    collected: 0,
+                    collectable_at_tge_percentage: 0,
+                    cliff_duration: 0,
+                    vesting_duration: 0,
+                    schedule_phases: None,
+                    curve: Curve::Linear,
+                    revocable: false,
+                    external_ref: None,
+                    release_interval: 0,
+                    start_override: None,
+                    collectable_at_cliff_percentage: 0,
+                    source: AllocationSource::AdminGrant,
+                    template_id: None,
+                },
+            );
+            // == * it updates the provided fields
+            az_airdrop
+                .update_recipient(recipient, Some(5), Some(5), Some(5), None, None, None)
+                .unwrap();
+            let updated_recipient: Recipient = az_airdrop.recipients.get(recipient).unwrap();
+            assert_eq!(
+                updated_recipient,
+                Recipient {
+                    allocation_id: 0,
+                    total_amount: 5,
+                    collected: 0,
+                    collectable_at_tge_percentage: 5,
+                    cliff_duration: 5,
+                    vesting_duration: 5,
+                    schedule_phases: None,
+                    curve: Curve::Linear,
+                    revocable: false,
+                    external_ref: None,
+                    release_interval: 0,
+                    start_override: None,
+                    collectable_at_cliff_percentage: 0,
+                    source: AllocationSource::AdminGrant,
+                    template_id: None,
+                }
+            );
+            // === when recipient's collectable_at_tge_percentage is greater than 100
+            // === * it raises an error
+            result =
+                az_airdrop.update_recipient(recipient, Some(101), None, None, None, None, None);
+            assert_eq!(result, Err(AzAirdropError::InvalidTgePercentage));
+            // === when recipient's collectable_at_tge_percentage is 100
+            // ==== when cliff_duration or vesting_duration is positive
+            // ==== * it raises an error
+            result = az_airdrop.update_recipient(
+                recipient,
+                Some(100),
+                Some(1),
+                Some(0),
+                None,
+                None,
+                None,
+            );
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "cliff_duration and vesting_duration must be 0 when collectable_tge_percentage is 100".to_string()
+                ))
+            );
+            result = az_airdrop.update_recipient(
+                recipient,
+                Some(100),
+                Some(0),
+                Some(1),
+                None,
+                None,
+                None,
+            );
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "cliff_duration and vesting_duration must be 0 when collectable_tge_percentage is 100".to_string()
+                ))
+            );
+            // === when recipient's collectable_at_tge_percentage is less than 100
+            // ==== when vesting_duration is zero
+            // ==== * it raises an error
+            result =
+                az_airdrop.update_recipient(recipient, Some(0), None, Some(0), None, None, None);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "vesting_duration must be greater than 0 when collectable_tge_percentage is not 100".to_string()
+                ))
+            );
+
+            // === when curve exponent is zero or too large
+            // === * it raises an error
+            result = az_airdrop.update_recipient(
+                recipient,
+                None,
+                None,
+                None,
+                Some(Curve::CustomExponent(0)),
+                None,
+                None,
+            );
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Curve exponent must be between 1 and 10".to_string()
+                ))
+            );
+            result = az_airdrop.update_recipient(
+                recipient,
+                None,
+                None,
+                None,
+                Some(Curve::CustomExponent(11)),
+                None,
+                None,
+            );
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Curve exponent must be between 1 and 10".to_string()
+                ))
+            );
+            // === when curve is valid
+            az_airdrop
+                .update_recipient(
+                    recipient,
+                    Some(0),
+                    Some(0),
+                    Some(10),
+                    Some(Curve::Quadratic),
+                    None,
+                    None,
+                )
+                .unwrap();
+            // === * it backloads vesting
+            assert_eq!(az_airdrop.vested_at(recipient, MOCK_START + 5), Ok(1));
+            az_airdrop
+                .update_recipient(
+                    recipient,
+                    None,
+                    None,
+                    None,
+                    Some(Curve::CustomExponent(3)),
+                    None,
+                    None,
+                )
+                .unwrap();
+            assert_eq!(az_airdrop.vested_at(recipient, MOCK_START + 5), Ok(0));
+            assert_eq!(az_airdrop.vested_at(recipient, MOCK_START + 10), Ok(5));
+            // === when start_override is before start
+            // === * it raises an error
+            result = az_airdrop.update_recipient(
+                recipient,
+                None,
+                None,
+                None,
+                None,
+                Some(MOCK_START - 1),
+                None,
+            );
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "start_override must be greater than or equal to start".to_string()
+                ))
+            );
+            // === when start_override is valid
+            az_airdrop
+                .update_recipient(
+                    recipient,
+                    None,
+                    None,
+                    None,
+                    Some(Curve::Linear),
+                    Some(MOCK_START + 100),
+                    None,
+                )
+                .unwrap();
+            // === * it vests from start_override instead of start
+            assert_eq!(az_airdrop.vested_at(recipient, MOCK_START + 10), Ok(0));
+            assert_eq!(az_airdrop.vested_at(recipient, MOCK_START + 105), Ok(2));
+            assert_eq!(az_airdrop.vested_at(recipient, MOCK_START + 110), Ok(5));
+            // === when tge and cliff percentages sum to more than 100
+            // === * it raises an error
+            result =
+                az_airdrop.update_recipient(recipient, Some(60), None, None, None, None, Some(50));
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "collectable_at_tge_percentage and collectable_at_cliff_percentage must sum to 100 or less".to_string()
+                ))
+            );
+            // === when tge and cliff percentages sum to 100 and there is vesting
+            // === * it raises an error
+            result = az_airdrop.update_recipient(
+                recipient,
+                Some(20),
+                None,
+                Some(10),
+                None,
+                None,
+                Some(80),
+            );
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "vesting_duration must be 0 when tge and cliff percentages sum to 100"
+                        .to_string()
+                ))
+            );
+            // === when cliff percentage is valid
+            az_airdrop
+                .update_recipient(
+                    recipient,
+                    Some(20),
+                    Some(10),
+                    Some(10),
+                    None,
+                    None,
+                    Some(40),
+                )
+                .unwrap();
+            // === * it unlocks the cliff percentage when the cliff ends
+            assert_eq!(az_airdrop.vested_at(recipient, MOCK_START + 109), Ok(1));
+            assert_eq!(az_airdrop.vested_at(recipient, MOCK_START + 110), Ok(3));
+            // === * it vests the rest over vesting_duration
+            assert_eq!(az_airdrop.vested_at(recipient, MOCK_START + 115), Ok(4));
+            assert_eq!(az_airdrop.vested_at(recipient, MOCK_START + 120), Ok(5));
+            // === when nothing is left to vest after the cliff
+            az_airdrop
+                .update_recipient(recipient, None, None, Some(0), None, None, Some(80))
+                .unwrap();
+            // === * it unlocks everything when the cliff ends
+            assert_eq!(az_airdrop.vested_at(recipient, MOCK_START + 110), Ok(5));
+
+            // when called by non-admin or non-sub-admin
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            // * it raises an error
+            result = az_airdrop.update_recipient(recipient, None, None, None, None, None, None);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: None,
+                })
+            );
+        }
+
+        #[ink::test]
+        fn test_update_recipient_batch() {
+            let (accounts, mut az_airdrop) = init();
+            let recipient: Recipient = Recipient {
+                allocation_id: 0,
+                total_amount: 5,
+                collected: 0,
+                collectable_at_tge_percentage: 100,
+                cliff_duration: 0,
+                vesting_duration: 0,
+                schedule_phases: None,
+                curve: Curve::Linear,
+                revocable: false,
+                external_ref: None,
+                release_interval: 0,
+                start_override: None,
+                collectable_at_cliff_percentage: 0,
+                source: AllocationSource::AdminGrant,
+                template_id: None,
+            };
+            az_airdrop.recipients.insert(accounts.django, &recipient);
+            az_airdrop.recipients.insert(accounts.eve, &recipient);
+            let patch: RecipientPatch = RecipientPatch {
+                collectable_at_tge_percentage: Some(20),
+                cliff_duration: None,
+                vesting_duration: Some(10),
+                curve: None,
+                start_override: None,
+                collectable_at_cliff_percentage: None,
+            };
+            let addresses: Vec<AccountId> = vec![accounts.django, accounts.frank, accounts.eve];
+            // when called by non-admin or non-sub-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let mut result = az_airdrop.update_recipient_batch(addresses.clone(), patch.clone());
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: None,
+                })
+            );
+            // when called by an admin or sub-admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // = when airdrop has started
+            // = * it raises an error
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(az_airdrop.start);
+            result = az_airdrop.update_recipient_batch(addresses.clone(), patch.clone());
+            assert_eq!(result, Err(AzAirdropError::AirdropAlreadyStarted));
+            // = when airdrop has not started
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(az_airdrop.start - 1);
+            // == when curve is invalid
+            // == * it raises an error
+            result = az_airdrop.update_recipient_batch(
+                addresses.clone(),
+                RecipientPatch {
+                    curve: Some(Curve::CustomExponent(0)),
+                    ..patch.clone()
+                },
+            );
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Curve exponent must be between 1 and 10".to_string()
+                ))
+            );
+            // == when curve is valid
+            // == * it returns a result per address and updates the valid ones
+            let updated_recipient: Recipient = Recipient {
+                collectable_at_tge_percentage: 20,
+                vesting_duration: 10,
+                ..recipient
+            };
+            result = az_airdrop.update_recipient_batch(addresses, patch);
+            assert_eq!(
+                result,
+                Ok(vec![
+                    Ok(updated_recipient.clone()),
+                    Err(AzAirdropError::NotFound("Recipient".to_string())),
+                    Ok(updated_recipient.clone()),
+                ])
+            );
+            assert_eq!(az_airdrop.show(accounts.eve), Ok(updated_recipient));
         }
 
-        // === TESTS ===
-        // === TEST CONSTRUCTOR ===
         #[ink::test]
-        fn test_new() {
-            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+        fn test_update_recipient_external_ref() {
+            let (accounts, mut az_airdrop) = init();
+            let external_ref: [u8; 16] = [1; 16];
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let mut result =
+                az_airdrop.update_recipient_external_ref(accounts.django, Some(external_ref));
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+            // when called by admin
             set_caller::<DefaultEnvironment>(accounts.bob);
-            let result = AzAirdrop::new(mock_token(), MOCK_START, 0, 0, 0);
-            assert!(result.is_err());
+            // = when recipient does not exist
+            // = * it raises an error
+            result = az_airdrop.update_recipient_external_ref(accounts.django, Some(external_ref));
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Recipient".to_string()))
+            );
+            // = when recipient exists
+            let recipient: Recipient = Recipient {
+                allocation_id: 0,
+                total_amount: 5,
+                collected: 0,
+                collectable_at_tge_percentage: 0,
+                cliff_duration: 0,
+                vesting_duration: 0,
+                schedule_phases: None,
+                curve: Curve::Linear,
+                revocable: false,
+                external_ref: None,
+                release_interval: 0,
+                start_override: None,
+                collectable_at_cliff_percentage: 0,
+                source: AllocationSource::AdminGrant,
+                template_id: None,
+            };
+            az_airdrop.insert_recipient(accounts.django, &recipient);
+            az_airdrop.insert_recipient(accounts.eve, &recipient);
+            // == * it updates external_ref
+            az_airdrop
+                .update_recipient_external_ref(accounts.django, Some(external_ref))
+                .unwrap();
+            assert_eq!(
+                az_airdrop.find_by_external_ref(external_ref).unwrap().0,
+                accounts.django
+            );
+            // == when external ref is used by another recipient
+            // == * it raises an error
+            result = az_airdrop.update_recipient_external_ref(accounts.eve, Some(external_ref));
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "External ref is already in use".to_string()
+                ))
+            );
+            // == when external ref is removed
+            // == * it frees the external ref
+            az_airdrop
+                .update_recipient_external_ref(accounts.django, None)
+                .unwrap();
+            az_airdrop
+                .update_recipient_external_ref(accounts.eve, Some(external_ref))
+                .unwrap();
+            assert_eq!(
+                az_airdrop.find_by_external_ref(external_ref).unwrap().0,
+                accounts.eve
+            );
         }
 
-        // === TEST QUERIES ===
         #[ink::test]
-        fn test_collectable_amount() {
+        fn test_update_recipient_release_interval() {
             let (accounts, mut az_airdrop) = init();
             let recipient_address: AccountId = accounts.django;
+            // when called by non-admin or non-sub-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let mut result = az_airdrop.update_recipient_release_interval(recipient_address, 30);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: None,
+                })
+            );
+            // when called by an admin or sub-admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // = when airdrop has started
+            // = * it raises an error
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(MOCK_START);
+            result = az_airdrop.update_recipient_release_interval(recipient_address, 30);
+            assert_eq!(result, Err(AzAirdropError::AirdropAlreadyStarted));
+            // = when airdrop has not started
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(MOCK_START - 1);
+            // == when recipient does not exist
+            // == * it raises an error
+            result = az_airdrop.update_recipient_release_interval(recipient_address, 30);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Recipient".to_string()))
+            );
+            // == when recipient exists
             let mut recipient: Recipient = Recipient {
+                allocation_id: 0,
                 total_amount: 100,
                 collected: 0,
-                collectable_at_tge_percentage: 100,
+                collectable_at_tge_percentage: 0,
                 cliff_duration: 0,
-                vesting_duration: 0,
+                vesting_duration: 100,
+                schedule_phases: Some(vec![SchedulePhase::Instant { at: 0, bps: 10_000 }]),
+                curve: Curve::Linear,
+                revocable: false,
+                external_ref: None,
+                release_interval: 0,
+                start_override: None,
+                collectable_at_cliff_percentage: 0,
+                source: AllocationSource::AdminGrant,
+                template_id: None,
             };
-            // when recipient does not exist
-            // * it returns an error
-            let mut result = az_airdrop.collectable_amount(recipient_address, 0);
+            az_airdrop.recipients.insert(recipient_address, &recipient);
+            // === when recipient has schedule phases
+            // === * it raises an error
+            result = az_airdrop.update_recipient_release_interval(recipient_address, 30);
             assert_eq!(
                 result,
-                Err(AzAirdropError::NotFound("Recipient".to_string(),))
+                Err(AzAirdropError::UnprocessableEntity(
+                    "release_interval doesn't apply to schedule phases".to_string()
+                ))
             );
-            // when recipient exists
+            // === when recipient does not have schedule phases
+            recipient.schedule_phases = None;
             az_airdrop.recipients.insert(recipient_address, &recipient);
-            // = when provided timestamp is before the start time
-            // = * it returns zero
-            result = az_airdrop.collectable_amount(recipient_address, MOCK_START - 1);
-            let mut result_unwrapped: Balance = result.unwrap();
-            assert_eq!(result_unwrapped, 0);
-            // = when provided timestamp is greater than or equal to start time
-            // == when collectable_at_tge_percentage is positive
-            // === when collectable_at_tge_percentagne is 100
-            // === * it returns the total_amount
-            result = az_airdrop.collectable_amount(recipient_address, MOCK_START);
-            result_unwrapped = result.unwrap();
-            assert_eq!(result_unwrapped, recipient.total_amount);
-            // === when collectable_at_tge_percentage is 20
-            // ==== when vesting time has not been reached
-            // ==== * it returns 20
-            recipient = az_airdrop
-                .update_recipient(recipient_address, Some(20), Some(1), Some(100))
-                .unwrap();
-            result = az_airdrop.collectable_amount(recipient_address, MOCK_START);
-            result_unwrapped = result.unwrap();
-            assert_eq!(result_unwrapped, 20);
-            result = az_airdrop.collectable_amount(recipient_address, MOCK_START + 1);
-            result_unwrapped = result.unwrap();
-            assert_eq!(result_unwrapped, 20);
-            // ==== when partial vesting time has been reached
-            result = az_airdrop
-                .collectable_amount(recipient_address, MOCK_START + recipient.cliff_duration + 2);
-            // ==== * it returns the partial amount
-            result_unwrapped = result.unwrap();
-            assert_eq!(result_unwrapped, 20 + (2 * 80 / 100));
-            // ==== when total vesting time has been reached
-            result = az_airdrop.collectable_amount(
-                recipient_address,
-                MOCK_START + recipient.cliff_duration + recipient.vesting_duration * 1_000_000,
+            // ==== when release_interval is longer than vesting_duration
+            // ==== * it raises an error
+            result = az_airdrop.update_recipient_release_interval(recipient_address, 101);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "release_interval must be less than or equal to vesting_duration".to_string()
+                ))
             );
-            // ==== * it returns the total amount
-            result_unwrapped = result.unwrap();
-            assert_eq!(result_unwrapped, recipient.total_amount);
-            // ==== * it factors in recipient.collected
-            recipient.collected = 20;
-            az_airdrop.recipients.insert(recipient_address, &recipient);
-            result = az_airdrop.collectable_amount(
-                recipient_address,
-                MOCK_START + recipient.cliff_duration + recipient.vesting_duration,
+            // ==== when release_interval is within vesting_duration
+            // ==== * it updates release_interval
+            az_airdrop
+                .update_recipient_release_interval(recipient_address, 30)
+                .unwrap();
+            assert_eq!(
+                az_airdrop.show(recipient_address).unwrap().release_interval,
+                30
             );
-            result_unwrapped = result.unwrap();
-            assert_eq!(result_unwrapped, recipient.total_amount - 20);
         }
 
         #[ink::test]
-        fn test_config() {
-            let (accounts, az_airdrop) = init();
-            let config = az_airdrop.config();
-            // * it returns the config
-            assert_eq!(config.token, mock_token());
-            assert_eq!(config.admin, accounts.bob);
+        fn test_update_recipient_revocable() {
+            let (accounts, mut az_airdrop) = init();
+            let recipient_address: AccountId = accounts.django;
+            // when called by non-admin or non-sub-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let mut result = az_airdrop.update_recipient_revocable(recipient_address, true);
             assert_eq!(
-                config.sub_admins,
-                az_airdrop.sub_admins_as_vec.get_or_default()
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: None,
+                })
             );
-            assert_eq!(config.start, MOCK_START);
-            assert_eq!(config.default_collectable_at_tge_percentage, 100);
-            assert_eq!(config.default_cliff_duration, 0);
-            assert_eq!(config.default_vesting_duration, 0);
+            // when called by an admin or sub-admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // = when airdrop has started
+            // = * it raises an error
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(MOCK_START);
+            result = az_airdrop.update_recipient_revocable(recipient_address, true);
+            assert_eq!(result, Err(AzAirdropError::AirdropAlreadyStarted));
+            // = when airdrop has not started
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(MOCK_START - 1);
+            // == when recipient does not exist
+            // == * it raises an error
+            result = az_airdrop.update_recipient_revocable(recipient_address, true);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Recipient".to_string()))
+            );
+            // == when recipient exists
+            // == * it updates revocable
+            az_airdrop.recipients.insert(
+                recipient_address,
+                &Recipient {
+                    allocation_id: 0,
+                    total_amount: 100,
+                    collected: 0,
+                    collectable_at_tge_percentage: 100,
+                    cliff_duration: 0,
+                    vesting_duration: 0,
+                    schedule_phases: None,
+                    curve: Curve::Linear,
+                    revocable: false,
+                    external_ref: None,
+                    release_interval: 0,
+                    start_override: None,
+                    collectable_at_cliff_percentage: 0,
+                    source: AllocationSource::AdminGrant,
+                    template_id: None,
+                },
+            );
+            result = az_airdrop.update_recipient_revocable(recipient_address, true);
+            assert!(result.unwrap().revocable);
         }
 
-        // === TEST HANDLES ===
         #[ink::test]
-        fn test_recipient_add() {
+        fn test_update_recipient_schedule_phases() {
             let (accounts, mut az_airdrop) = init();
-            let amount: Balance = 5;
-
-            // when caller is not authorised
-            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let recipient_address: AccountId = accounts.django;
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(
+                az_airdrop.start - 1,
+            );
+            let schedule_phases: Vec<SchedulePhase> = vec![
+                SchedulePhase::Instant { at: 0, bps: 1_000 },
+                SchedulePhase::Linear {
+                    duration: 60,
+                    bps: 4_000,
+                },
+                SchedulePhase::Instant { at: 90, bps: 2_000 },
+                SchedulePhase::Linear {
+                    duration: 30,
+                    bps: 3_000,
+                },
+            ];
+            // when recipient does not exist
             // * it raises an error
-            let mut result = az_airdrop.recipient_add(accounts.charlie, amount, None);
-            assert_eq!(result, Err(AzAirdropError::Unauthorised));
-            // when caller is authorised
-            set_caller::<DefaultEnvironment>(accounts.bob);
-            az_airdrop.sub_admins_add(accounts.charlie).unwrap();
-            set_caller::<DefaultEnvironment>(accounts.charlie);
-            // = when airdrop has started
-            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(az_airdrop.start);
+            let mut result = az_airdrop
+                .update_recipient_schedule_phases(recipient_address, Some(schedule_phases.clone()));
+            assert_eq!(
+                result,
+                Err(AzAirdropError::NotFound("Recipient".to_string()))
+            );
+            // when recipient exists
+            az_airdrop.recipients.insert(
+                recipient_address,
+                &Recipient {
+                    allocation_id: 0,
+                    total_amount: 1_000,
+                    collected: 0,
+                    collectable_at_tge_percentage: 100,
+                    cliff_duration: 0,
+                    vesting_duration: 0,
+                    schedule_phases: None,
+                    curve: Curve::Linear,
+                    revocable: false,
+                    external_ref: None,
+                    release_interval: 0,
+                    start_override: None,
+                    collectable_at_cliff_percentage: 0,
+                    source: AllocationSource::AdminGrant,
+                    template_id: None,
+                },
+            );
+            // = when phases don't sum to 100%
+            // = * it raises an error
+            result = az_airdrop.update_recipient_schedule_phases(
+                recipient_address,
+                Some(vec![SchedulePhase::Instant { at: 0, bps: 9_999 }]),
+            );
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Schedule phases must sum to 100%".to_string()
+                ))
+            );
+            // = when an instant phase is before the previous phase ends
             // = * it raises an error
-            result = az_airdrop.recipient_add(accounts.charlie, amount, None);
+            result = az_airdrop.update_recipient_schedule_phases(
+                recipient_address,
+                Some(vec![
+                    SchedulePhase::Linear {
+                        duration: 10,
+                        bps: 5_000,
+                    },
+                    SchedulePhase::Instant { at: 9, bps: 5_000 },
+                ]),
+            );
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Instant phase can't be before the previous phase ends".to_string()
+                ))
+            );
+            // = when phases are valid
+            az_airdrop
+                .update_recipient_schedule_phases(recipient_address, Some(schedule_phases))
+                .unwrap();
+            // = * the phases are used to calculate the vested amount
+            assert_eq!(az_airdrop.vested_at(recipient_address, MOCK_START), Ok(100));
+            assert_eq!(
+                az_airdrop.vested_at(recipient_address, MOCK_START + 30),
+                Ok(100 + 200)
+            );
+            assert_eq!(
+                az_airdrop.vested_at(recipient_address, MOCK_START + 89),
+                Ok(100 + 400)
+            );
+            assert_eq!(
+                az_airdrop.vested_at(recipient_address, MOCK_START + 90),
+                Ok(100 + 400 + 200)
+            );
+            assert_eq!(
+                az_airdrop.vested_at(recipient_address, MOCK_START + 105),
+                Ok(100 + 400 + 200 + 150)
+            );
             assert_eq!(
-                result,
-                Err(AzAirdropError::UnprocessableEntity(
-                    "Airdrop has started".to_string(),
-                ))
+                az_airdrop.vested_at(recipient_address, MOCK_START + 120),
+                Ok(1_000)
             );
-            // = when airdrop has not started
-            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(
-                az_airdrop.start - 1,
+            // = when phases are removed
+            az_airdrop
+                .update_recipient_schedule_phases(recipient_address, None)
+                .unwrap();
+            // = * it goes back to the tge, cliff and vesting fields
+            assert_eq!(
+                az_airdrop.vested_at(recipient_address, MOCK_START),
+                Ok(1_000)
             );
-            // == when amount will cause overflow
-            az_airdrop.to_be_collected = Balance::MAX;
-            // == * it raises an error
-            result = az_airdrop.recipient_add(accounts.charlie, amount, None);
+            // when called by non-admin or non-sub-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            result = az_airdrop.update_recipient_schedule_phases(recipient_address, None);
             assert_eq!(
                 result,
-                Err(AzAirdropError::UnprocessableEntity(
-                    "Amount will cause to_be_collected to overflow".to_string(),
-                ))
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: None,
+                })
             );
-            // == when amount won't cause overflow
-            // THE REST NEEDS TO BE IN INK E2E TESTS, SEE BELOW.
         }
 
         #[ink::test]
-        fn test_collect() {
+        fn test_update_recipient_tranches() {
             let (accounts, mut az_airdrop) = init();
-            // when recipient with caller's address does not exist
-            // * it raises an error
-            let mut result = az_airdrop.collect();
-            assert_eq!(
-                result,
-                Err(AzAirdropError::NotFound("Recipient".to_string()))
+            let recipient_address: AccountId = accounts.django;
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(
+                az_airdrop.start - 1,
             );
-            // when recipient with caller's address exists
             az_airdrop.recipients.insert(
-                accounts.bob,
+                recipient_address,
                 &Recipient {
-                    total_amount: 5,
+                    allocation_id: 0,
+                    total_amount: 1_000,
                     collected: 0,
                     collectable_at_tge_percentage: 100,
                     cliff_duration: 0,
                     vesting_duration: 0,
+                    schedule_phases: None,
+                    curve: Curve::Linear,
+                    revocable: false,
+                    external_ref: None,
+                    release_interval: 0,
+                    start_override: None,
+                    collectable_at_cliff_percentage: 0,
+                    source: AllocationSource::AdminGrant,
+                    template_id: None,
                 },
             );
-            // = when collectable amount is zero
-            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(
-                az_airdrop.start - 1,
+            // when percentages don't sum to 100
+            // * it raises an error
+            let result = az_airdrop
+                .update_recipient_tranches(recipient_address, vec![(0, 10), (30, 20), (60, 30)]);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::UnprocessableEntity(
+                    "Schedule phases must sum to 100%".to_string()
+                ))
             );
-            // = * it raises an error
-            result = az_airdrop.collect();
+            // when tranches are out of order
+            // * it raises an error
+            let result =
+                az_airdrop.update_recipient_tranches(recipient_address, vec![(30, 50), (0, 50)]);
             assert_eq!(
                 result,
                 Err(AzAirdropError::UnprocessableEntity(
-                    "Amount is zero".to_string(),
+                    "Instant phase can't be before the previous phase ends".to_string()
                 ))
             );
-            // = when collectable amount is positive
-            // THE REST NEEDS TO HAPPEN IN INTEGRATION TESTS
+            // when tranches are valid
+            az_airdrop
+                .update_recipient_tranches(
+                    recipient_address,
+                    vec![(0, 10), (30, 20), (60, 30), (90, 40)],
+                )
+                .unwrap();
+            // * it stores them as instant phases
+            assert_eq!(
+                az_airdrop
+                    .show(recipient_address)
+                    .unwrap()
+                    .schedule_phases
+                    .unwrap()[1],
+                SchedulePhase::Instant { at: 30, bps: 2_000 }
+            );
+            // * it sums the tranches that have been reached
+            assert_eq!(az_airdrop.vested_at(recipient_address, MOCK_START), Ok(100));
+            assert_eq!(
+                az_airdrop.vested_at(recipient_address, MOCK_START + 59),
+                Ok(300)
+            );
+            assert_eq!(
+                az_airdrop.vested_at(recipient_address, MOCK_START + 60),
+                Ok(600)
+            );
+            assert_eq!(
+                az_airdrop.vested_at(recipient_address, MOCK_START + 90),
+                Ok(1_000)
+            );
         }
 
         #[ink::test]
-        fn test_return_spare_token() {
+        fn test_update_role_registry() {
             let (accounts, mut az_airdrop) = init();
             // when called by admin
-            // THIS NEEDS TO HAPPEN IN INTEGRATION TESTS
+            // * it updates role_registry
+            az_airdrop
+                .update_role_registry(Some(accounts.django))
+                .unwrap();
+            assert_eq!(az_airdrop.config().role_registry, Some(accounts.django));
             // when called by non-admin
             // * it raises an error
             set_caller::<DefaultEnvironment>(accounts.charlie);
-            let result = az_airdrop.return_spare_tokens();
-            assert_eq!(result, Err(AzAirdropError::Unauthorised));
-        }
-
-        #[ink::test]
-        fn test_sub_admins_add() {
-            let (accounts, mut az_airdrop) = init();
-            let new_sub_admin: AccountId = accounts.django;
-            // when called by admin
-            // = when address is not a sub admin
-            let mut result = az_airdrop.sub_admins_add(new_sub_admin);
-            result.unwrap();
-            // = * it adds the address to sub_admins_vec
-            assert_eq!(
-                az_airdrop.sub_admins_as_vec.get_or_default(),
-                vec![accounts.django]
-            );
-            // = * it adds the address to sub_admins_mapping
-            assert_eq!(
-                az_airdrop.sub_admins_mapping.get(new_sub_admin).is_some(),
-                true
-            );
-            // = when already a sub admin
-            result = az_airdrop.sub_admins_add(new_sub_admin);
+            let result = az_airdrop.update_role_registry(None);
             assert_eq!(
                 result,
-                Err(AzAirdropError::UnprocessableEntity(
-                    "Already a sub admin".to_string()
-                ))
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
             );
-            // = * it raises an error
-            // when called by non admin
-            // * it raises an error
-            set_caller::<DefaultEnvironment>(accounts.charlie);
-            result = az_airdrop.sub_admins_add(new_sub_admin);
-            assert_eq!(result, Err(AzAirdropError::Unauthorised));
         }
 
         #[ink::test]
-        fn test_sub_admins_remove() {
+        fn test_update_schedule_freeze_window() {
             let (accounts, mut az_airdrop) = init();
-            let sub_admin_to_remove: AccountId = accounts.django;
-            // when called by admin
-            // = when address is not a sub admin
-            let mut result = az_airdrop.sub_admins_remove(sub_admin_to_remove);
+            let window: Timestamp = 10;
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let mut result = az_airdrop.update_schedule_freeze_window(window);
             assert_eq!(
                 result,
-                Err(AzAirdropError::UnprocessableEntity(
-                    "Not a sub admin".to_string()
-                ))
-            );
-            // = when address is a sub admin
-            az_airdrop.sub_admins_add(sub_admin_to_remove).unwrap();
-            result = az_airdrop.sub_admins_remove(sub_admin_to_remove);
-            result.unwrap();
-            // = * it removes the address from sub_admins_vec
-            assert_eq!(az_airdrop.sub_admins_as_vec.get_or_default().len(), 0);
-            // = * it remove the address from sub_admins_mapping
-            assert_eq!(
-                az_airdrop
-                    .sub_admins_mapping
-                    .get(sub_admin_to_remove)
-                    .is_some(),
-                false
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
             );
+            // when called by admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // = when schedule is not frozen
+            // = * it updates schedule_freeze_window
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(MOCK_START - window - 1);
+            az_airdrop.update_schedule_freeze_window(window).unwrap();
+            assert_eq!(az_airdrop.config().schedule_freeze_window, window);
+            // = when schedule is frozen
             // = * it raises an error
-            // when called by non admin
-            // * it raises an error
-            set_caller::<DefaultEnvironment>(accounts.charlie);
-            result = az_airdrop.sub_admins_remove(sub_admin_to_remove);
-            assert_eq!(result, Err(AzAirdropError::Unauthorised));
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(MOCK_START - window);
+            result = az_airdrop.update_schedule_freeze_window(0);
+            assert_eq!(result, Err(AzAirdropError::ScheduleFrozen));
         }
 
         #[ink::test]
-        fn test_recipient_subtract() {
+        fn test_update_source_template() {
             let (accounts, mut az_airdrop) = init();
-            let amount: Balance = 5;
-            let recipient_address: AccountId = accounts.django;
-            // when called by an admin or sub-admin
-            // = when airdrop has started
-            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(az_airdrop.start);
-            // = * it raises an error
-            let mut result = az_airdrop.recipient_subtract(recipient_address, amount, None);
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let mut result =
+                az_airdrop.update_source_template(AllocationSource::SubAdminGrant, Some(0));
             assert_eq!(
                 result,
-                Err(AzAirdropError::UnprocessableEntity(
-                    "Airdrop has started".to_string(),
-                ))
-            );
-            // = when airdrop has not started
-            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(
-                az_airdrop.start - 1,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
             );
-            // == when recipient does not exist
-            // == * it raises an error
-            result = az_airdrop.recipient_subtract(recipient_address, amount, None);
+            // when called by admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // = when template does not exist
+            // = * it raises an error
+            result = az_airdrop.update_source_template(AllocationSource::SubAdminGrant, Some(0));
             assert_eq!(
                 result,
-                Err(AzAirdropError::NotFound("Recipient".to_string()))
-            );
-            // == when recipient exists
-            az_airdrop.recipients.insert(
-                recipient_address,
-                &Recipient {
-                    total_amount: amount,
-                    collected: 0,
-                    collectable_at_tge_percentage: 0,
-                    cliff_duration: 0,
-                    vesting_duration: 0,
-                },
+                Err(AzAirdropError::NotFound("Schedule template".to_string()))
             );
-            // === when amount is greater than the recipient's total amount
-            // === * it returns an error
-            result = az_airdrop.recipient_subtract(recipient_address, amount + 1, None);
+            // = when template exists
+            az_airdrop
+                .schedule_template_add("Sale".to_string(), 10, 1, 2)
+                .unwrap();
+            // = * it sets the source's template
+            az_airdrop
+                .update_source_template(AllocationSource::SubAdminGrant, Some(0))
+                .unwrap();
             assert_eq!(
-                result,
-                Err(AzAirdropError::UnprocessableEntity(
-                    "Amount is greater than recipient's total amount".to_string()
-                ))
+                az_airdrop.source_template(AllocationSource::SubAdminGrant),
+                Some(0)
             );
-            // === when amount is less than or equal to the recipient's total amount
-            az_airdrop.to_be_collected += amount;
-            // === * it reduces the total_amount by the amount
+            // = * recipients created through the source reference the template
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(MOCK_START - 1);
+            az_airdrop.budgets.insert(accounts.charlie, &2);
+            az_airdrop.total_reserved = 2;
             az_airdrop
-                .recipient_subtract(recipient_address, amount - 1, None)
+                .sub_admins_add(accounts.charlie, ALL_PERMISSIONS)
                 .unwrap();
-            let recipient: Recipient = az_airdrop.recipients.get(recipient_address).unwrap();
-            assert_eq!(recipient.total_amount, 1);
-            // when called by non-admin or non-sub-admin
             set_caller::<DefaultEnvironment>(accounts.charlie);
-            // * it raises an error
-            result = az_airdrop.recipient_subtract(recipient_address, amount, None);
-            assert_eq!(result, Err(AzAirdropError::Unauthorised));
-            // === * it reduces the total_amount
-            assert_eq!(az_airdrop.to_be_collected, 1);
+            let recipient: Recipient = az_airdrop
+                .recipient_add(accounts.django, 1, None, None, None)
+                .unwrap();
+            assert_eq!(recipient.template_id, Some(0));
+            assert_eq!(recipient.collectable_at_tge_percentage, 10);
+            assert_eq!(recipient.cliff_duration, 1);
+            assert_eq!(recipient.vesting_duration, 2);
+            // = * recipients created through other sources get the contract defaults
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            az_airdrop.budgets.insert(accounts.bob, &1);
+            az_airdrop.total_reserved += 1;
+            let recipient: Recipient = az_airdrop
+                .recipient_add(accounts.eve, 1, None, None, None)
+                .unwrap();
+            assert_eq!(
+                recipient.collectable_at_tge_percentage,
+                az_airdrop.default_collectable_at_tge_percentage
+            );
+            // = when template is None
+            // = * it removes the source's template
+            az_airdrop
+                .update_source_template(AllocationSource::SubAdminGrant, None)
+                .unwrap();
+            assert_eq!(
+                az_airdrop.source_template(AllocationSource::SubAdminGrant),
+                None
+            );
         }
 
         #[ink::test]
-        fn test_update_config() {
+        fn test_update_whitelisted_callers_only() {
             let (accounts, mut az_airdrop) = init();
-            // when called by admin
-            // = when new admin is provided
-            az_airdrop
-                .update_config(Some(accounts.django), None, None, None, None)
-                .unwrap();
-            // = * it updates the admin
-            let config: Config = az_airdrop.config();
-            assert_eq!(config.admin, accounts.django);
-            set_caller::<DefaultEnvironment>(accounts.django);
-            // = when new start is provided
-            // == when new start is before or equal to current time stamp
-            let current_timestamp: Timestamp = 5;
-            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(current_timestamp);
-            let result = az_airdrop.update_config(None, Some(current_timestamp), None, None, None);
-            // == * it raises an error
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let result = az_airdrop.update_whitelisted_callers_only(true);
             assert_eq!(
                 result,
-                Err(AzAirdropError::UnprocessableEntity(
-                    "New start time must be in the future".to_string()
-                ))
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
             );
-            // == when new start is after current time stamp
-            // === when to_be_collected is positive
-            az_airdrop.to_be_collected = 1;
-            // === * it raises an error
-            let result =
-                az_airdrop.update_config(None, Some(current_timestamp + 1), None, None, None);
+            // when called by admin
+            // * it updates whitelisted_callers_only
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            az_airdrop.update_whitelisted_callers_only(true).unwrap();
+            assert!(az_airdrop.config().whitelisted_callers_only);
+            // = when recipient_add is called by a key
+            // = * it raises an error
+            let result = az_airdrop.recipient_add(accounts.django, 1, None, None, None);
             assert_eq!(
                 result,
-                Err(AzAirdropError::UnprocessableEntity(
-                    "to_be_collected must be zero when changing start time".to_string()
-                ))
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.bob,
+                    expected: None,
+                })
             );
-            // === when to_be_collected is zero
-            az_airdrop.to_be_collected = 0;
-            // === * it updates the start time
+            // = when recipient_add is called by a whitelisted address that isn't a contract
+            // = * it raises an error
             az_airdrop
-                .update_config(None, Some(current_timestamp + 1), None, None, None)
+                .sub_admins_add(accounts.frank, ALL_PERMISSIONS)
                 .unwrap();
-            let mut config: Config = az_airdrop.config();
-            assert_eq!(config.start, current_timestamp + 1);
-            // = when new default_collectable_at_tge_percentage is provided
-            // == when airdrop calculation variable combination is invalid
-            // == * it raises an error
-            let result = az_airdrop.update_config(None, None, Some(50), None, None);
-            assert_eq!(
-                result,
-                Err(AzAirdropError::UnprocessableEntity(
-                    "vesting_duration must be greater than 0 when collectable_tge_percentage is not 100"
-                        .to_string(),
-                ))
-            );
-            // == when combination of start, cliff_duration and vesting_duration exceeds Timestamp max
-            let result = az_airdrop.update_config(
-                None,
-                None,
-                Some(50),
-                Some((Timestamp::MAX / 2) - az_airdrop.start + 2),
-                Some(Timestamp::MAX / 2),
-            );
+            az_airdrop.whitelisted_callers_add(accounts.frank).unwrap();
+            az_airdrop.budgets.insert(accounts.frank, &5);
+            az_airdrop.total_reserved = 5;
+            set_caller::<DefaultEnvironment>(accounts.frank);
+            let result = az_airdrop.recipient_add_batch(vec![(accounts.django, 1, None)]);
             assert_eq!(
                 result,
-                Err(AzAirdropError::UnprocessableEntity(
-                    "Combination of start, cliff_duration and vesting_duration exceeds limit"
-                        .to_string(),
-                ))
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.frank,
+                    expected: None,
+                })
             );
-            // == when airdrop calculation variable combination is valid
+            // = when recipient_add is called by a whitelisted contract
+            // = * it adds the recipient
+            ink::env::test::set_contract::<DefaultEnvironment>(accounts.frank);
             az_airdrop
-                .update_config(None, None, Some(50), Some(50), Some(50))
+                .recipient_add(accounts.django, 1, None, None, None)
                 .unwrap();
-            // == * it updates the default_collectable_at_tge_percentage
-            config = az_airdrop.config();
-            assert_eq!(config.default_collectable_at_tge_percentage, 50);
-            assert_eq!(config.default_cliff_duration, 50);
-            assert_eq!(config.default_vesting_duration, 50);
-            // No need to test the other default fields as test above does that
-            // when called by non-admin
-            set_caller::<DefaultEnvironment>(accounts.charlie);
-            // * it raises an error
-            let result = az_airdrop.update_config(None, None, None, None, None);
-            assert_eq!(result, Err(AzAirdropError::Unauthorised));
+            assert_eq!(az_airdrop.show(accounts.django).unwrap().total_amount, 1);
+            // = * it records the allocation as a sale
+            assert_eq!(
+                az_airdrop.show(accounts.django).unwrap().source,
+                AllocationSource::SalePurchase
+            );
+            // when recipient_add is called by a sub admin that isn't whitelisted
+            // * it records the allocation as a sub admin grant
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            az_airdrop.update_whitelisted_callers_only(false).unwrap();
+            az_airdrop
+                .whitelisted_callers_remove(accounts.frank)
+                .unwrap();
+            set_caller::<DefaultEnvironment>(accounts.frank);
+            az_airdrop
+                .recipient_add(accounts.eve, 1, None, None, None)
+                .unwrap();
+            assert_eq!(
+                az_airdrop.show(accounts.eve).unwrap().source,
+                AllocationSource::SubAdminGrant
+            );
         }
 
         #[ink::test]
-        fn test_update_recipient() {
+        fn test_whitelisted_callers_add() {
             let (accounts, mut az_airdrop) = init();
-            let recipient: AccountId = accounts.django;
-            // when called by an admin or sub-admin
-            // = when airdrop has started
-            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(az_airdrop.start);
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let result = az_airdrop.whitelisted_callers_add(accounts.frank);
+            assert_eq!(
+                result,
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
+            );
+            // when called by admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // = when address isn't whitelisted
+            // = * it whitelists the address
+            az_airdrop.whitelisted_callers_add(accounts.frank).unwrap();
+            assert!(az_airdrop.whitelisted_callers.contains(accounts.frank));
+            // = when address is already whitelisted
             // = * it raises an error
-            let mut result = az_airdrop.update_recipient(recipient, None, None, None);
+            let result = az_airdrop.whitelisted_callers_add(accounts.frank);
             assert_eq!(
                 result,
                 Err(AzAirdropError::UnprocessableEntity(
-                    "Airdrop has started".to_string(),
+                    "Already whitelisted".to_string()
                 ))
             );
-            // = when airdrop has not started
-            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(
-                az_airdrop.start - 1,
-            );
-            // == when recipient does not exist
-            // == * it raises an error
-            result = az_airdrop.update_recipient(recipient, None, None, None);
+        }
+
+        #[ink::test]
+        fn test_whitelisted_callers_remove() {
+            let (accounts, mut az_airdrop) = init();
+            // when called by non-admin
+            // * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let result = az_airdrop.whitelisted_callers_remove(accounts.frank);
             assert_eq!(
                 result,
-                Err(AzAirdropError::NotFound("Recipient".to_string(),))
-            );
-            // == when recipient exists
-            az_airdrop.recipients.insert(
-                recipient,
-                &Recipient {
-                    total_amount: 5,
-                    collected: 0,
-                    collectable_at_tge_percentage: 0,
-                    cliff_duration: 0,
-                    vesting_duration: 0,
-                },
-            );
-            // == * it updates the provided fields
-            az_airdrop
-                .update_recipient(recipient, Some(5), Some(5), Some(5))
-                .unwrap();
-            let updated_recipient: Recipient = az_airdrop.recipients.get(recipient).unwrap();
-            assert_eq!(
-                updated_recipient,
-                Recipient {
-                    total_amount: 5,
-                    collected: 0,
-                    collectable_at_tge_percentage: 5,
-                    cliff_duration: 5,
-                    vesting_duration: 5
-                }
+                Err(AzAirdropError::Unauthorised {
+                    caller: accounts.charlie,
+                    expected: Some(accounts.bob),
+                })
             );
-            // === when recipient's collectable_at_tge_percentage is greater than 100
-            // === * it raises an error
-            result = az_airdrop.update_recipient(recipient, Some(101), None, None);
+            // when called by admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // = when address isn't whitelisted
+            // = * it raises an error
+            let result = az_airdrop.whitelisted_callers_remove(accounts.frank);
             assert_eq!(
                 result,
                 Err(AzAirdropError::UnprocessableEntity(
-                    "collectable_at_tge_percentage must be less than or equal to 100".to_string()
+                    "Not whitelisted".to_string()
                 ))
             );
-            // === when recipient's collectable_at_tge_percentage is 100
-            // ==== when cliff_duration or vesting_duration is positive
-            // ==== * it raises an error
-            result = az_airdrop.update_recipient(recipient, Some(100), Some(1), Some(0));
+            // = when address is whitelisted
+            // = * it removes the address
+            az_airdrop.whitelisted_callers_add(accounts.frank).unwrap();
+            az_airdrop
+                .whitelisted_callers_remove(accounts.frank)
+                .unwrap();
+            assert!(!az_airdrop.whitelisted_callers.contains(accounts.frank));
+        }
+
+        #[ink::test]
+        fn test_withdraw_escrow() {
+            let (_accounts, mut az_airdrop) = init();
+            // when escrow deadline is not set
+            // * it raises an error
+            let mut result = az_airdrop.withdraw_escrow();
             assert_eq!(
                 result,
-                Err(AzAirdropError::UnprocessableEntity(
-                    "cliff_duration and vesting_duration must be 0 when collectable_tge_percentage is 100".to_string()
-                ))
+                Err(AzAirdropError::NotFound("Escrow deadline".to_string()))
             );
-            result = az_airdrop.update_recipient(recipient, Some(100), Some(0), Some(1));
+            // when escrow deadline is set
+            az_airdrop.escrow_deadline = Some(MOCK_START);
+            // = when escrow deadline has not passed
+            // = * it raises an error
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(MOCK_START - 1);
+            result = az_airdrop.withdraw_escrow();
             assert_eq!(
                 result,
                 Err(AzAirdropError::UnprocessableEntity(
-                    "cliff_duration and vesting_duration must be 0 when collectable_tge_percentage is 100".to_string()
+                    "Escrow can't be withdrawn".to_string()
                 ))
             );
-            // === when recipient's collectable_at_tge_percentage is less than 100
-            // ==== when vesting_duration is zero
-            // ==== * it raises an error
-            result = az_airdrop.update_recipient(recipient, Some(0), None, Some(0));
+            // = when escrow deadline has passed
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(MOCK_START);
+            // == when escrow is finalized
+            // == * it raises an error
+            az_airdrop.escrow_finalized = true;
+            result = az_airdrop.withdraw_escrow();
             assert_eq!(
                 result,
                 Err(AzAirdropError::UnprocessableEntity(
-                    "vesting_duration must be greater than 0 when collectable_tge_percentage is not 100".to_string()
+                    "Escrow can't be withdrawn".to_string()
                 ))
             );
-
-            // when called by non-admin or non-sub-admin
-            set_caller::<DefaultEnvironment>(accounts.charlie);
-            // * it raises an error
-            result = az_airdrop.update_recipient(recipient, None, None, None);
-            assert_eq!(result, Err(AzAirdropError::Unauthorised));
+            az_airdrop.escrow_finalized = false;
+            // == when caller has not deposited
+            // == * it raises an error
+            result = az_airdrop.withdraw_escrow();
+            assert_eq!(result, Err(AzAirdropError::AmountZero));
+            // == when caller has deposited
+            // THE REST NEEDS TO HAPPEN IN INTEGRATION TESTS
         }
     }
 
@@ -1102,6 +10941,97 @@ mod az_airdrop {
         }
 
         // === TEST HANDLES ===
+        #[ink_e2e::test]
+        async fn test_acquire_token_max(mut client: ::ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let alice_account_id: AccountId = account_id(ink_e2e::alice());
+
+            // Instantiate token
+            let token_constructor = ButtonRef::new(
+                MOCK_AMOUNT,
+                Some("DIBS".to_string()),
+                Some("DIBS".to_string()),
+                12,
+            );
+            let token_id: AccountId = client
+                .instantiate("az_button", &ink_e2e::alice(), token_constructor, 0, None)
+                .await
+                .expect("Token instantiate failed")
+                .account_id;
+
+            // Instantiate airdrop smart contract
+            let airdrop_constructor =
+                AzAirdropRef::new(token_id, MOCK_START, 20, 0, 31_556_952_000);
+            let airdrop_id: AccountId = client
+                .instantiate(
+                    "az_airdrop",
+                    &ink_e2e::alice(),
+                    airdrop_constructor,
+                    0,
+                    None,
+                )
+                .await
+                .expect("Airdrop instantiate failed")
+                .account_id;
+
+            // when called by an admin
+            // = when allowance is zero
+            // = * it raises an error
+            let acquire_token_max_message = build_message::<AzAirdropRef>(airdrop_id)
+                .call(|airdrop| airdrop.acquire_token_max(alice_account_id));
+            let result = client
+                .call_dry_run(&ink_e2e::alice(), &acquire_token_max_message, 0, None)
+                .await
+                .return_value();
+            assert_eq!(result, Err(AzAirdropError::AmountZero));
+            // = when allowance is positive
+            let approve_message =
+                build_message::<ButtonRef>(token_id).call(|button| button.approve(airdrop_id, 5));
+            client
+                .call(&ink_e2e::alice(), approve_message, 0, None)
+                .await
+                .unwrap();
+            // == when the balance covers everything owed
+            // == * it raises an error
+            let acquire_token_max_message = build_message::<AzAirdropRef>(airdrop_id)
+                .call(|airdrop| airdrop.acquire_token_max(alice_account_id));
+            let result = client
+                .call_dry_run(&ink_e2e::alice(), &acquire_token_max_message, 0, None)
+                .await
+                .return_value();
+            assert_eq!(result, Err(AzAirdropError::AmountZero));
+            // == when the balance is short of what is owed
+            let rotate_merkle_root_message = build_message::<AzAirdropRef>(airdrop_id)
+                .call(|airdrop| airdrop.rotate_merkle_root([0; 32], 3));
+            client
+                .call(&ink_e2e::alice(), rotate_merkle_root_message, 0, None)
+                .await
+                .unwrap();
+            let acquire_token_max_message = build_message::<AzAirdropRef>(airdrop_id)
+                .call(|airdrop| airdrop.acquire_token_max(alice_account_id));
+            client
+                .call(&ink_e2e::alice(), acquire_token_max_message, 0, None)
+                .await
+                .unwrap();
+            // == * it transfers the allowance, capped at the shortfall, to the smart contract
+            let balance_message =
+                build_message::<ButtonRef>(token_id).call(|button| button.balance_of(airdrop_id));
+            let result = client
+                .call_dry_run(&ink_e2e::alice(), &balance_message, 0, None)
+                .await
+                .return_value();
+            assert_eq!(result, 3);
+            // == * it records the amount in the funding ledger
+            let funded_by_message = build_message::<AzAirdropRef>(airdrop_id)
+                .call(|airdrop| airdrop.funded_by(alice_account_id));
+            let result = client
+                .call_dry_run(&ink_e2e::alice(), &funded_by_message, 0, None)
+                .await
+                .return_value();
+            assert_eq!(result, 3);
+
+            Ok(())
+        }
+
         // This is just to test when cheque has a token address associated with it
         #[ink_e2e::test]
         async fn test_recipient_add(mut client: ::ink_e2e::Client<C, E>) -> E2EResult<()> {
@@ -1148,17 +11078,12 @@ mod az_airdrop {
             // == when smart contract does not have the balance to cover amount
             // == * it raises an error
             let recipient_add_message = build_message::<AzAirdropRef>(airdrop_id)
-                .call(|airdrop| airdrop.recipient_add(bob_account_id, 1, None));
+                .call(|airdrop| airdrop.recipient_add(bob_account_id, 1, None, None, None));
             let result = client
                 .call_dry_run(&ink_e2e::alice(), &recipient_add_message, 0, None)
                 .await
                 .return_value();
-            assert_eq!(
-                result,
-                Err(AzAirdropError::UnprocessableEntity(
-                    "Insufficient balance".to_string()
-                ))
-            );
+            assert_eq!(result, Err(AzAirdropError::InsufficientContractBalance));
             // == when smart contract has the balance to cover amount
             let transfer_message = build_message::<ButtonRef>(token_id)
                 .call(|button| button.transfer(airdrop_id, 1, vec![]));
@@ -1172,7 +11097,7 @@ mod az_airdrop {
             assert!(transfer_result.is_ok());
             // == * it adds to the recipient's total_amount and sets details with defaults if not provided and new
             let recipient_add_message = build_message::<AzAirdropRef>(airdrop_id)
-                .call(|airdrop| airdrop.recipient_add(bob_account_id, 1, None));
+                .call(|airdrop| airdrop.recipient_add(bob_account_id, 1, None, None, None));
             client
                 .call(&ink_e2e::alice(), recipient_add_message, 0, None)
                 .await
@@ -1203,6 +11128,194 @@ mod az_airdrop {
             Ok(())
         }
 
+        // Guards the batch handles against per entry authorisation and config loads creeping back in
+        #[ink_e2e::test]
+        async fn test_recipient_add_batch(mut client: ::ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let addresses: Vec<AccountId> = vec![
+                account_id(ink_e2e::bob()),
+                account_id(ink_e2e::charlie()),
+                account_id(ink_e2e::dave()),
+                account_id(ink_e2e::eve()),
+                account_id(ink_e2e::ferdie()),
+            ];
+            let entries_count: u64 = addresses.len() as u64;
+
+            // Instantiate token
+            let token_constructor = ButtonRef::new(
+                MOCK_AMOUNT,
+                Some("DIBS".to_string()),
+                Some("DIBS".to_string()),
+                12,
+            );
+            let token_id: AccountId = client
+                .instantiate("az_button", &ink_e2e::alice(), token_constructor, 0, None)
+                .await
+                .expect("Token instantiate failed")
+                .account_id;
+
+            // Instantiate airdrop smart contract
+            let airdrop_constructor =
+                AzAirdropRef::new(token_id, MOCK_START, 20, 0, 31_556_952_000);
+            let airdrop_id: AccountId = client
+                .instantiate(
+                    "az_airdrop",
+                    &ink_e2e::alice(),
+                    airdrop_constructor,
+                    0,
+                    None,
+                )
+                .await
+                .expect("Airdrop instantiate failed")
+                .account_id;
+            let transfer_message = build_message::<ButtonRef>(token_id)
+                .call(|button| button.transfer(airdrop_id, MOCK_AMOUNT, vec![]));
+            client
+                .call(&ink_e2e::alice(), transfer_message, 0, None)
+                .await
+                .unwrap();
+
+            // when adding recipients
+            let recipient_add_message = build_message::<AzAirdropRef>(airdrop_id)
+                .call(|airdrop| airdrop.recipient_add(addresses[0], 1, None, None, None));
+            let single_gas: u64 = client
+                .call_dry_run(&ink_e2e::alice(), &recipient_add_message, 0, None)
+                .await
+                .exec_result
+                .gas_consumed
+                .ref_time();
+            let first_entry: Vec<(AccountId, Balance, Option<Description>)> =
+                vec![(addresses[0], 1, None)];
+            let recipient_add_batch_message = build_message::<AzAirdropRef>(airdrop_id)
+                .call(|airdrop| airdrop.recipient_add_batch(first_entry.clone()));
+            let batch_of_one_gas: u64 = client
+                .call_dry_run(&ink_e2e::alice(), &recipient_add_batch_message, 0, None)
+                .await
+                .exec_result
+                .gas_consumed
+                .ref_time();
+            let entries: Vec<(AccountId, Balance, Option<Description>)> = addresses
+                .iter()
+                .map(|address| (*address, 1, None))
+                .collect();
+            let recipient_add_batch_message = build_message::<AzAirdropRef>(airdrop_id)
+                .call(|airdrop| airdrop.recipient_add_batch(entries.clone()));
+            let batch_gas: u64 = client
+                .call_dry_run(&ink_e2e::alice(), &recipient_add_batch_message, 0, None)
+                .await
+                .exec_result
+                .gas_consumed
+                .ref_time();
+            // * it costs less in a batch than in separate calls
+            assert!(batch_gas < entries_count * single_gas);
+            // * it costs less for each extra entry than for a separate call
+            assert!((batch_gas - batch_of_one_gas) / (entries_count - 1) < single_gas);
+            client
+                .call(&ink_e2e::alice(), recipient_add_batch_message, 0, None)
+                .await
+                .unwrap();
+
+            // when updating recipients
+            let update_recipient_message =
+                build_message::<AzAirdropRef>(airdrop_id).call(|airdrop| {
+                    airdrop.update_recipient(addresses[0], Some(10), None, None, None, None, None)
+                });
+            let single_gas: u64 = client
+                .call_dry_run(&ink_e2e::alice(), &update_recipient_message, 0, None)
+                .await
+                .exec_result
+                .gas_consumed
+                .ref_time();
+            let patch: RecipientPatch = RecipientPatch {
+                collectable_at_tge_percentage: Some(10),
+                cliff_duration: None,
+                vesting_duration: None,
+                curve: None,
+                start_override: None,
+                collectable_at_cliff_percentage: None,
+            };
+            let update_recipient_batch_message = build_message::<AzAirdropRef>(airdrop_id)
+                .call(|airdrop| airdrop.update_recipient_batch(vec![addresses[0]], patch.clone()));
+            let batch_of_one_gas: u64 = client
+                .call_dry_run(&ink_e2e::alice(), &update_recipient_batch_message, 0, None)
+                .await
+                .exec_result
+                .gas_consumed
+                .ref_time();
+            let update_recipient_batch_message = build_message::<AzAirdropRef>(airdrop_id)
+                .call(|airdrop| airdrop.update_recipient_batch(addresses.clone(), patch.clone()));
+            let batch_gas: u64 = client
+                .call_dry_run(&ink_e2e::alice(), &update_recipient_batch_message, 0, None)
+                .await
+                .exec_result
+                .gas_consumed
+                .ref_time();
+            // * it costs less in a batch than in separate calls
+            assert!(batch_gas < entries_count * single_gas);
+            // * it costs less for each extra entry than for a separate call
+            assert!((batch_gas - batch_of_one_gas) / (entries_count - 1) < single_gas);
+
+            Ok(())
+        }
+
+        #[ink_e2e::test]
+        async fn test_snapshot(mut client: ::ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Instantiate token
+            let token_constructor = ButtonRef::new(
+                MOCK_AMOUNT,
+                Some("DIBS".to_string()),
+                Some("DIBS".to_string()),
+                12,
+            );
+            let token_id: AccountId = client
+                .instantiate("az_button", &ink_e2e::alice(), token_constructor, 0, None)
+                .await
+                .expect("Token instantiate failed")
+                .account_id;
+
+            // Instantiate airdrop smart contract
+            let airdrop_constructor =
+                AzAirdropRef::new(token_id, MOCK_START, 20, 0, 31_556_952_000);
+            let airdrop_id: AccountId = client
+                .instantiate(
+                    "az_airdrop",
+                    &ink_e2e::alice(),
+                    airdrop_constructor,
+                    0,
+                    None,
+                )
+                .await
+                .expect("Airdrop instantiate failed")
+                .account_id;
+            let transfer_message = build_message::<ButtonRef>(token_id)
+                .call(|button| button.transfer(airdrop_id, 3, vec![]));
+            client
+                .call(&ink_e2e::alice(), transfer_message, 0, None)
+                .await
+                .unwrap();
+            let recipient_add_message = build_message::<AzAirdropRef>(airdrop_id).call(|airdrop| {
+                airdrop.recipient_add(account_id(ink_e2e::bob()), 1, None, None, None)
+            });
+            client
+                .call(&ink_e2e::alice(), recipient_add_message, 0, None)
+                .await
+                .unwrap();
+
+            // * it returns the config along with funding and registration status
+            let snapshot_message =
+                build_message::<AzAirdropRef>(airdrop_id).call(|airdrop| airdrop.snapshot());
+            let snapshot = client
+                .call_dry_run(&ink_e2e::alice(), &snapshot_message, 0, None)
+                .await
+                .return_value();
+            assert_eq!(snapshot.config.to_be_collected, 1);
+            assert_eq!(snapshot.balance, 3);
+            assert_eq!(snapshot.spare, 2);
+            assert!(!snapshot.started);
+            assert!(snapshot.registration_open);
+
+            Ok(())
+        }
+
         // I CAN'T MODIFY TIMESTAMP WITH INK_E2E, PLEASE TEST MANUALLY THAT
         // = * it transfers the collectable amount to the recipient
         // = * it increases the recipient's collected by the collectable amount
@@ -1259,12 +11372,7 @@ mod az_airdrop {
                 .call_dry_run(&ink_e2e::alice(), &return_spare_tokens_message, 0, None)
                 .await
                 .return_value();
-            assert_eq!(
-                result,
-                Err(AzAirdropError::UnprocessableEntity(
-                    "Amount is zero".to_string()
-                ))
-            );
+            assert_eq!(result, Err(AzAirdropError::AmountZero));
             // = when there is spare token
             let transfer_message = build_message::<ButtonRef>(token_id)
                 .call(|token| token.transfer(airdrop_id, 1, vec![]));
@@ -0,0 +1,54 @@
+use ink::{
+    env::hash::{Blake2x256, HashOutput},
+    primitives::AccountId,
+};
+
+type Balance = <ink::env::DefaultEnvironment as ink::env::Environment>::Balance;
+type Timestamp = <ink::env::DefaultEnvironment as ink::env::Environment>::Timestamp;
+pub type MerkleHash = <Blake2x256 as HashOutput>::Type;
+
+// blake2x256 of the SCALE encoded
+// (leaf_index, address, amount, collectable_at_tge_percentage, cliff_duration, vesting_duration)
+pub fn leaf(
+    leaf_index: u32,
+    address: AccountId,
+    amount: Balance,
+    collectable_at_tge_percentage: u8,
+    cliff_duration: Timestamp,
+    vesting_duration: Timestamp,
+) -> MerkleHash {
+    let mut output: MerkleHash = MerkleHash::default();
+    ink::env::hash_encoded::<Blake2x256, _>(
+        &(
+            leaf_index,
+            address,
+            amount,
+            collectable_at_tge_percentage,
+            cliff_duration,
+            vesting_duration,
+        ),
+        &mut output,
+    );
+
+    output
+}
+
+// Pairs are sorted before hashing so proofs don't need to carry left/right positions
+pub fn verify(root: MerkleHash, leaf: MerkleHash, proof: &[MerkleHash]) -> bool {
+    let mut computed: MerkleHash = leaf;
+    for sibling in proof.iter() {
+        let (first, second): (&MerkleHash, &MerkleHash) = if computed <= *sibling {
+            (&computed, sibling)
+        } else {
+            (sibling, &computed)
+        };
+        let mut input: [u8; 64] = [0; 64];
+        input[..32].copy_from_slice(first);
+        input[32..].copy_from_slice(second);
+        let mut output: MerkleHash = MerkleHash::default();
+        ink::env::hash_bytes::<Blake2x256>(&input, &mut output);
+        computed = output;
+    }
+
+    computed == root
+}
@@ -0,0 +1,19 @@
+use ink::{
+    env::{DefaultEnvironment, Environment},
+    primitives::AccountId,
+};
+
+type Balance = <DefaultEnvironment as Environment>::Balance;
+
+// Interface for a contract notified when allocations are cut short, e.g. to pay out insurance.
+// Notifications run with a capped gas limit and their failures are ignored.
+#[ink::trait_definition]
+pub trait VestingObserver {
+    // amount is the unvested amount taken back from address
+    #[ink(message)]
+    fn on_revoke(&mut self, address: AccountId, amount: Balance);
+
+    // amount is everything that was still owed to recipients
+    #[ink(message)]
+    fn on_sweep(&mut self, amount: Balance);
+}
@@ -0,0 +1,32 @@
+use crate::{
+    az_airdrop::{Config, Recipient, Stats},
+    errors::AzAirdropError,
+};
+use ink::{
+    env::{DefaultEnvironment, Environment},
+    primitives::AccountId,
+};
+
+type Balance = <DefaultEnvironment as Environment>::Balance;
+type Timestamp = <DefaultEnvironment as Environment>::Timestamp;
+
+// Read-only interface for indexers and other contracts.
+// Trait messages get selectors namespaced by the trait name, so they can't collide with handles.
+#[ink::trait_definition]
+pub trait AzAirdropQuery {
+    #[ink(message)]
+    fn collectable_amount(
+        &self,
+        address: AccountId,
+        timestamp: Timestamp,
+    ) -> Result<Balance, AzAirdropError>;
+
+    #[ink(message)]
+    fn config(&self) -> Config;
+
+    #[ink(message)]
+    fn show(&self, address: AccountId) -> Result<Recipient, AzAirdropError>;
+
+    #[ink(message)]
+    fn stats(&self) -> Stats;
+}
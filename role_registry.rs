@@ -0,0 +1,23 @@
+use ink::primitives::AccountId;
+
+pub type Role = u32;
+
+// Roles are shared across contracts, so they're namespaced by project rather than contract.
+// The admin holds every role.
+// Held by the admin only, it's never granted locally or through a registry
+pub const DEFAULT_ADMIN: Role = ink::selector_id!("az::default_admin");
+// Pauses and resumes admin ops and claims
+pub const PAUSER: Role = ink::selector_id!("az::pauser");
+// Manages recipients, held by sub-admins.
+// Keeps the sub-admin id so registries that already grant it keep working.
+pub const RECIPIENT_MANAGER: Role = ink::selector_id!("az::sub_admin");
+// Moves tokens in and out of the contract's balance
+pub const TREASURER: Role = ink::selector_id!("az::treasurer");
+
+// Interface a shared role registry contract has to implement.
+// Called with the selector of HasRole::has_role.
+#[ink::trait_definition]
+pub trait HasRole {
+    #[ink(message)]
+    fn has_role(&self, role: Role, account: AccountId) -> bool;
+}
@@ -0,0 +1,26 @@
+use crate::{
+    az_airdrop::{Description, Recipient},
+    errors::AzAirdropError,
+};
+use ink::{
+    env::{DefaultEnvironment, Environment},
+    primitives::AccountId,
+};
+
+type Balance = <DefaultEnvironment as Environment>::Balance;
+type Timestamp = <DefaultEnvironment as Environment>::Timestamp;
+
+// What a sales contract needs, so it can call through ink::contract_ref!(AzAirdropSales)
+// instead of AzAirdropRef. Reads go through ink::contract_ref!(AzAirdropQuery).
+#[ink::trait_definition]
+pub trait AzAirdropSales {
+    #[ink(message)]
+    fn recipient_add(
+        &mut self,
+        address: AccountId,
+        amount: Balance,
+        description: Option<Description>,
+        template_id: Option<u16>,
+        start_override: Option<Timestamp>,
+    ) -> Result<Recipient, AzAirdropError>;
+}
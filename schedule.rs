@@ -0,0 +1,241 @@
+use crate::errors::AzAirdropError;
+use ink::prelude::{format, string::ToString, vec::Vec};
+use primitive_types::U256;
+
+// Durations outside these are probably seconds entered as ms or similar
+#[cfg(feature = "diagnostics")]
+pub const MAX_PLAUSIBLE_DURATION: u64 = 631_152_000_000; // 20 years
+#[cfg(feature = "diagnostics")]
+pub const MIN_PLAUSIBLE_DURATION: u64 = 3_600_000; // 1 hour
+pub const FIXED_POINT_ONE: u128 = 1_000_000_000_000_000_000;
+pub const MAX_CURVE_EXPONENT: u8 = 10;
+pub const MAX_SCHEDULE_PHASES: usize = 24;
+pub const TOTAL_BPS: u16 = 10_000;
+
+// Shape of linear vesting. Higher exponents backload unlocks toward the end.
+#[derive(scale::Decode, scale::Encode, Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum Curve {
+    Linear,
+    Quadratic,
+    CustomExponent(u8),
+}
+impl Curve {
+    pub fn exponent(&self) -> u8 {
+        match self {
+            Curve::Linear => 1,
+            Curve::Quadratic => 2,
+            Curve::CustomExponent(exponent) => *exponent,
+        }
+    }
+}
+
+// Phases are evaluated in order from start.
+// Linear releases bps evenly over duration ms, beginning when the previous phase ends.
+// Instant releases bps at ms from start, which can't be before the previous phase ends.
+#[derive(scale::Decode, scale::Encode, Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum SchedulePhase {
+    Linear { duration: u64, bps: u16 },
+    Instant { at: u64, bps: u16 },
+}
+
+// Always compiled as ink! codegen refers to message return types even when they are cfg'd out
+#[derive(scale::Decode, scale::Encode, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum ScheduleWarning {
+    CliffDurationTooShort,
+    CliffDurationTooLong,
+    VestingDurationTooShort,
+    VestingDurationTooLong,
+}
+
+// Zero durations are never flagged as they are valid schedules
+#[cfg(feature = "diagnostics")]
+pub fn sanity_warnings(
+    cliff_duration: u64,
+    vesting_duration: u64,
+    phases: Option<&[SchedulePhase]>,
+) -> Vec<ScheduleWarning> {
+    let mut warnings: Vec<ScheduleWarning> = Vec::new();
+    let mut vesting_durations: Vec<u64> = Vec::new();
+    if let Some(phases) = phases {
+        for phase in phases.iter() {
+            if let SchedulePhase::Linear { duration, .. } = phase {
+                vesting_durations.push(*duration);
+            }
+        }
+        vesting_durations.push(end_offset(phases));
+    } else {
+        if cliff_duration > 0 && cliff_duration < MIN_PLAUSIBLE_DURATION {
+            warnings.push(ScheduleWarning::CliffDurationTooShort);
+        }
+        if cliff_duration > MAX_PLAUSIBLE_DURATION {
+            warnings.push(ScheduleWarning::CliffDurationTooLong);
+        }
+        vesting_durations.push(vesting_duration);
+    }
+    if vesting_durations
+        .iter()
+        .any(|duration| *duration > 0 && *duration < MIN_PLAUSIBLE_DURATION)
+    {
+        warnings.push(ScheduleWarning::VestingDurationTooShort);
+    }
+    if vesting_durations
+        .iter()
+        .any(|duration| *duration > MAX_PLAUSIBLE_DURATION)
+    {
+        warnings.push(ScheduleWarning::VestingDurationTooLong);
+    }
+
+    warnings
+}
+
+// amount * (time_reached / duration) ^ exponent
+pub fn curved_amount(amount: u128, time_reached: u64, duration: u64, curve: &Curve) -> u128 {
+    if time_reached >= duration {
+        return amount;
+    }
+    if *curve == Curve::Linear {
+        return (U256::from(amount) * U256::from(time_reached) / U256::from(duration)).as_u128();
+    }
+
+    // Fixed point with 18 decimals, ratio is at most FIXED_POINT_ONE so this can't overflow
+    let one: U256 = U256::from(FIXED_POINT_ONE);
+    let ratio: U256 = U256::from(time_reached) * one / U256::from(duration);
+    let mut curved_ratio: U256 = one;
+    for _ in 0..curve.exponent() {
+        curved_ratio = curved_ratio * ratio / one;
+    }
+
+    (U256::from(amount) * curved_ratio / one).as_u128()
+}
+
+// ms from start when the last phase ends
+pub fn end_offset(phases: &[SchedulePhase]) -> u64 {
+    let mut cursor: u64 = 0;
+    for phase in phases.iter() {
+        cursor = match phase {
+            SchedulePhase::Linear { duration, .. } => cursor.saturating_add(*duration),
+            SchedulePhase::Instant { at, .. } => cursor.max(*at),
+        };
+    }
+
+    cursor
+}
+
+// (ms from start, percentage) tranches as instant phases e.g. 10/20/30/40 unlocks
+pub fn phases_from_tranches(tranches: &[(u64, u8)]) -> Vec<SchedulePhase> {
+    tranches
+        .iter()
+        .map(|(at, percentage)| SchedulePhase::Instant {
+            at: *at,
+            bps: u16::from(*percentage) * 100,
+        })
+        .collect()
+}
+
+pub fn validate_curve(curve: &Curve) -> Result<(), AzAirdropError> {
+    let exponent: u8 = curve.exponent();
+    if exponent == 0 || exponent > MAX_CURVE_EXPONENT {
+        return Err(AzAirdropError::UnprocessableEntity(format!(
+            "Curve exponent must be between 1 and {MAX_CURVE_EXPONENT}"
+        )));
+    }
+
+    Ok(())
+}
+
+pub fn validate(start: u64, phases: &[SchedulePhase]) -> Result<(), AzAirdropError> {
+    if phases.is_empty() || phases.len() > MAX_SCHEDULE_PHASES {
+        return Err(AzAirdropError::UnprocessableEntity(format!(
+            "Schedule must have between 1 and {MAX_SCHEDULE_PHASES} phases"
+        )));
+    }
+    let mut cursor: u128 = 0;
+    let mut total_bps: u32 = 0;
+    for phase in phases.iter() {
+        match phase {
+            SchedulePhase::Linear { duration, bps } => {
+                if *duration == 0 {
+                    return Err(AzAirdropError::UnprocessableEntity(
+                        "Linear phase duration must be greater than 0".to_string(),
+                    ));
+                }
+                cursor += u128::from(*duration);
+                total_bps += u32::from(*bps);
+            }
+            SchedulePhase::Instant { at, bps } => {
+                if u128::from(*at) < cursor {
+                    return Err(AzAirdropError::UnprocessableEntity(
+                        "Instant phase can't be before the previous phase ends".to_string(),
+                    ));
+                }
+                cursor = u128::from(*at);
+                total_bps += u32::from(*bps);
+            }
+        }
+    }
+    if total_bps != u32::from(TOTAL_BPS) {
+        return Err(AzAirdropError::UnprocessableEntity(
+            "Schedule phases must sum to 100%".to_string(),
+        ));
+    }
+    // This can't over flow because all values are u64 and there are at most 24 phases
+    if u128::from(start) + cursor > u64::MAX.into() {
+        return Err(AzAirdropError::UnprocessableEntity(
+            "Combination of start and schedule phases exceeds limit".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+// Cumulative amount vested elapsed ms after start
+pub fn vested_amount(
+    total_amount: u128,
+    phases: &[SchedulePhase],
+    curve: &Curve,
+    elapsed: u64,
+) -> u128 {
+    let mut vested: U256 = U256::zero();
+    let mut cursor: u64 = 0;
+    for phase in phases.iter() {
+        match phase {
+            SchedulePhase::Linear { duration, bps } => {
+                let phase_end: u64 = cursor.saturating_add(*duration);
+                let phase_amount: U256 =
+                    U256::from(total_amount) * U256::from(*bps) / U256::from(TOTAL_BPS);
+                if elapsed < phase_end {
+                    // This can't underflow as elapsed is at least cursor when reaching this phase
+                    let time_reached: u64 = elapsed.saturating_sub(cursor);
+                    vested += U256::from(curved_amount(
+                        phase_amount.as_u128(),
+                        time_reached,
+                        *duration,
+                        curve,
+                    ));
+                    return vested.min(U256::from(total_amount)).as_u128();
+                }
+                vested += phase_amount;
+                cursor = phase_end;
+            }
+            SchedulePhase::Instant { at, bps } => {
+                if elapsed < *at {
+                    return vested.min(U256::from(total_amount)).as_u128();
+                }
+                vested += U256::from(total_amount) * U256::from(*bps) / U256::from(TOTAL_BPS);
+                cursor = cursor.max(*at);
+            }
+        }
+    }
+
+    // All phases have completed, this avoids leaving rounding dust behind
+    total_amount
+}